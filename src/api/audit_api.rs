@@ -0,0 +1,59 @@
+use actix_web::{
+    get,
+    web::{Data, Query, ReqData},
+    HttpResponse, Responder, ResponseError,
+};
+use jwt::RegisteredClaims;
+
+use crate::{
+    auths::utils::require_role,
+    model::{
+        audit_model::{AuditEvent, AuditQuery},
+        auth_model::{User, UserRole},
+    },
+    repo::{audit_repo::AuditRepo, user_repo::UserRepo},
+};
+
+/// Lists the audit events matching the query, newest first
+#[get("/admin/audit")]
+pub async fn list_audit_events(
+    db: Data<AuditRepo<AuditEvent>>,
+    user_db: Data<UserRepo<User>>,
+    query: Query<AuditQuery>,
+    claims: Option<ReqData<RegisteredClaims>>,
+) -> impl Responder {
+    if let Err(err) = require_role(&user_db, claims, UserRole::Admin).await {
+        return err.error_response();
+    }
+    let result = db.events(&query).await;
+
+    match result {
+        Ok(resp) => HttpResponse::Ok().json(resp),
+        Err(err) => err.error_response(),
+    }
+}
+
+/// Downloads the audit events matching the query as JSON lines
+#[get("/admin/audit/export")]
+pub async fn export_audit_events(
+    db: Data<AuditRepo<AuditEvent>>,
+    user_db: Data<UserRepo<User>>,
+    query: Query<AuditQuery>,
+    claims: Option<ReqData<RegisteredClaims>>,
+) -> impl Responder {
+    if let Err(err) = require_role(&user_db, claims, UserRole::Admin).await {
+        return err.error_response();
+    }
+    let result = db.export(&query).await;
+
+    match result {
+        Ok(lines) => HttpResponse::Ok()
+            .content_type("application/x-ndjson")
+            .insert_header((
+                "Content-Disposition",
+                "attachment; filename=\"audit.jsonl\"",
+            ))
+            .body(lines),
+        Err(err) => err.error_response(),
+    }
+}
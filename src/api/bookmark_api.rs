@@ -0,0 +1,110 @@
+use actix_web::{
+    delete, get, post,
+    web::{Data, Path, Query, ReqData},
+    HttpResponse, Responder, ResponseError,
+};
+use bson::oid::ObjectId;
+use jwt::RegisteredClaims;
+
+use crate::{
+    auths::utils::get_user_id,
+    dtos::dto::BookmarkPageDto,
+    model::{bookmark_model::Bookmark, pagination::CursorQuery, tweet_model::Tweet},
+    repo::{bookmark_repo::BookmarkRepo, tweet_repo::TweetRepo},
+};
+
+#[post("/bookmarks/{tweet_id}")]
+pub async fn add_bookmark(
+    db: Data<BookmarkRepo<Bookmark>>,
+    tweet_db: Data<TweetRepo<Tweet>>,
+    path: Path<(String,)>,
+    claims: Option<ReqData<RegisteredClaims>>,
+) -> impl Responder {
+    let user_id = match get_user_id(claims) {
+        Ok(id) => id,
+        Err(err) => return HttpResponse::Unauthorized().body(err.to_string()),
+    };
+    let tweet_id = path.0.as_str();
+    let mut tweet = match tweet_db.view_tweet(tweet_id, &user_id).await {
+        Ok(tweet) => tweet,
+        Err(err) => return err.error_response(),
+    };
+    let result = db.add(&user_id, tweet_id).await;
+
+    match result {
+        Ok(_) => {
+            tweet.bookmarked_by_me = true;
+            HttpResponse::Created().json(tweet)
+        }
+        Err(err) => err.error_response(),
+    }
+}
+
+#[delete("/bookmarks/{tweet_id}")]
+pub async fn remove_bookmark(
+    db: Data<BookmarkRepo<Bookmark>>,
+    path: Path<(String,)>,
+    claims: Option<ReqData<RegisteredClaims>>,
+) -> impl Responder {
+    let user_id = match get_user_id(claims) {
+        Ok(id) => id,
+        Err(err) => return HttpResponse::Unauthorized().body(err.to_string()),
+    };
+    let result = db.remove(&user_id, path.0.as_str()).await;
+
+    match result {
+        Ok(_) => HttpResponse::Ok().json("Bookmark removed successfully"),
+        Err(err) => err.error_response(),
+    }
+}
+
+/// Lists the user's bookmarked tweets, dropping bookmarks of tweets deleted since
+#[get("/bookmarks")]
+pub async fn list_bookmarks(
+    db: Data<BookmarkRepo<Bookmark>>,
+    tweet_db: Data<TweetRepo<Tweet>>,
+    query: Query<CursorQuery>,
+    claims: Option<ReqData<RegisteredClaims>>,
+) -> impl Responder {
+    let user_id = match get_user_id(claims) {
+        Ok(id) => id,
+        Err(err) => return HttpResponse::Unauthorized().body(err.to_string()),
+    };
+    let (bookmarks, next_cursor) = match db.bookmarks(&user_id, &query).await {
+        Ok(page) => page,
+        Err(err) => return err.error_response(),
+    };
+    let tweet_ids = bookmarks
+        .iter()
+        .map(|b| b.tweet_id)
+        .collect::<Vec<ObjectId>>();
+    let mut tweets = match tweet_db.referenced_tweets(&tweet_ids).await {
+        Ok(tweets) => tweets,
+        Err(err) => return err.error_response(),
+    };
+    let deleted = tweet_ids
+        .into_iter()
+        .filter(|id| !tweets.iter().any(|t| t.id == id.to_hex()))
+        .collect::<Vec<ObjectId>>();
+    if !deleted.is_empty() {
+        if let Err(err) = db.prune(&user_id, &deleted).await {
+            log::error!("Failed to remove bookmarks of deleted tweets: {}", err);
+        }
+    }
+    let audience = match tweet_db.audience(&user_id).await {
+        Ok(audience) => audience,
+        Err(err) => return err.error_response(),
+    };
+    tweets.retain(|t| audience.allows_tweet(t));
+    if let Err(err) = tweet_db.reveal_polls(&user_id, &mut tweets).await {
+        return err.error_response();
+    }
+    for tweet in tweets.iter_mut() {
+        tweet.bookmarked_by_me = true;
+    }
+
+    HttpResponse::Ok().json(BookmarkPageDto {
+        tweets,
+        next_cursor,
+    })
+}
@@ -0,0 +1,215 @@
+use actix_web::{
+    delete, get, post, put,
+    web::{Data, Json, Path, Query, ReqData},
+    HttpResponse, Responder, ResponseError,
+};
+use bson::oid::ObjectId;
+use jwt::RegisteredClaims;
+
+use crate::{
+    auths::utils::get_user_id,
+    config::settings::{dm_max_length, dm_max_participants},
+    errors::error::TweetError,
+    model::{
+        auth_model::User,
+        dm_model::{Conversation, ConversationRequest, DirectMessageRequest, DmSettingsRequest},
+        follow_model::Follow,
+        pagination::PageQuery,
+        relation_model::Relation,
+    },
+    repo::{
+        dm_repo::DmRepo, follow_repo::FollowRepo, relation_repo::RelationRepo, user_repo::UserRepo,
+    },
+    validation::content::validate_message,
+};
+
+/// Checks that every participant accepts direct messages from the user,
+/// either because they follow each other or because their DMs are open,
+/// and that no participant blocked or was blocked by the user
+async fn check_participants(
+    user_id: &str,
+    participant_ids: &[String],
+    user_db: &UserRepo<User>,
+    follow_db: &FollowRepo<Follow>,
+    relations: &RelationRepo<Relation>,
+) -> Result<Vec<ObjectId>, TweetError> {
+    let mut ids = vec![ObjectId::parse_str(user_id)
+        .map_err(|_| TweetError::Unauthorized("authentication error occurred".into()))?];
+    for participant_id in participant_ids {
+        let participant = user_db.find_user(participant_id).await?;
+        let id = participant.id.unwrap();
+        if ids.contains(&id) {
+            continue;
+        }
+        if relations.is_blocked(user_id, participant_id).await?
+            || !participant.open_dms && !follow_db.is_mutual(user_id, participant_id).await?
+        {
+            return Err(TweetError::Forbidden(format!(
+                "User {} does not accept direct messages from you",
+                participant_id
+            )));
+        }
+        ids.push(id);
+    }
+    if ids.len() < 2 {
+        return Err(TweetError::BadRequest(
+            "A conversation needs at least one other participant".into(),
+        ));
+    }
+    if ids.len() > dm_max_participants() {
+        return Err(TweetError::BadRequest(format!(
+            "A conversation can have at most {} participants",
+            dm_max_participants()
+        )));
+    }
+    Ok(ids)
+}
+
+#[get("/dm/conversations")]
+pub async fn list_conversations(
+    db: Data<DmRepo<Conversation>>,
+    page: Query<PageQuery>,
+    claims: Option<ReqData<RegisteredClaims>>,
+) -> impl Responder {
+    let user_id = match get_user_id(claims) {
+        Ok(id) => id,
+        Err(err) => return HttpResponse::Unauthorized().body(err.to_string()),
+    };
+    let result = db.conversations(&user_id, &page).await;
+
+    match result {
+        Ok(resp) => HttpResponse::Ok().json(resp),
+        Err(err) => err.error_response(),
+    }
+}
+
+#[post("/dm/conversations")]
+pub async fn start_conversation(
+    db: Data<DmRepo<Conversation>>,
+    user_db: Data<UserRepo<User>>,
+    follow_db: Data<FollowRepo<Follow>>,
+    relations: Data<RelationRepo<Relation>>,
+    request: Json<ConversationRequest>,
+    claims: Option<ReqData<RegisteredClaims>>,
+) -> impl Responder {
+    let user_id = match get_user_id(claims) {
+        Ok(id) => id,
+        Err(err) => return HttpResponse::Unauthorized().body(err.to_string()),
+    };
+    let participant_ids = match check_participants(
+        &user_id,
+        &request.participant_ids,
+        &user_db,
+        &follow_db,
+        &relations,
+    )
+    .await
+    {
+        Ok(ids) => ids,
+        Err(err) => return err.error_response(),
+    };
+    let result = db.start_conversation(participant_ids).await;
+
+    match result {
+        Ok(resp) => HttpResponse::Created().json(resp),
+        Err(err) => err.error_response(),
+    }
+}
+
+#[get("/dm/conversations/{id}/messages")]
+pub async fn list_messages(
+    db: Data<DmRepo<Conversation>>,
+    path: Path<(String,)>,
+    page: Query<PageQuery>,
+    claims: Option<ReqData<RegisteredClaims>>,
+) -> impl Responder {
+    let user_id = match get_user_id(claims) {
+        Ok(id) => id,
+        Err(err) => return HttpResponse::Unauthorized().body(err.to_string()),
+    };
+    let result = db.messages(path.0.as_str(), &user_id, &page).await;
+
+    match result {
+        Ok(resp) => HttpResponse::Ok().json(resp),
+        Err(err) => err.error_response(),
+    }
+}
+
+#[post("/dm/conversations/{id}/messages")]
+pub async fn send_message(
+    db: Data<DmRepo<Conversation>>,
+    path: Path<(String,)>,
+    request: Json<DirectMessageRequest>,
+    claims: Option<ReqData<RegisteredClaims>>,
+) -> impl Responder {
+    let user_id = match get_user_id(claims) {
+        Ok(id) => id,
+        Err(err) => return HttpResponse::Unauthorized().body(err.to_string()),
+    };
+    let body = match validate_message("body", request.body.as_deref(), dm_max_length()) {
+        Ok(body) => body,
+        Err(err) => return err.error_response(),
+    };
+    let result = db.send_message(path.0.as_str(), &user_id, &body).await;
+
+    match result {
+        Ok(resp) => HttpResponse::Created().json(resp),
+        Err(err) => err.error_response(),
+    }
+}
+
+#[post("/dm/conversations/{id}/read")]
+pub async fn mark_conversation_read(
+    db: Data<DmRepo<Conversation>>,
+    path: Path<(String,)>,
+    claims: Option<ReqData<RegisteredClaims>>,
+) -> impl Responder {
+    let user_id = match get_user_id(claims) {
+        Ok(id) => id,
+        Err(err) => return HttpResponse::Unauthorized().body(err.to_string()),
+    };
+    let result = db.mark_read(path.0.as_str(), &user_id).await;
+
+    match result {
+        Ok(resp) => HttpResponse::Ok().json(resp),
+        Err(err) => err.error_response(),
+    }
+}
+
+#[delete("/dm/conversations/{id}/messages/{message_id}")]
+pub async fn delete_message(
+    db: Data<DmRepo<Conversation>>,
+    path: Path<(String, String)>,
+    claims: Option<ReqData<RegisteredClaims>>,
+) -> impl Responder {
+    let user_id = match get_user_id(claims) {
+        Ok(id) => id,
+        Err(err) => return HttpResponse::Unauthorized().body(err.to_string()),
+    };
+    let result = db
+        .delete_message(path.0.as_str(), path.1.as_str(), &user_id)
+        .await;
+
+    match result {
+        Ok(resp) => HttpResponse::Ok().json(resp),
+        Err(err) => err.error_response(),
+    }
+}
+
+#[put("/dm/settings")]
+pub async fn update_dm_settings(
+    user_db: Data<UserRepo<User>>,
+    request: Json<DmSettingsRequest>,
+    claims: Option<ReqData<RegisteredClaims>>,
+) -> impl Responder {
+    let user_id = match get_user_id(claims) {
+        Ok(id) => id,
+        Err(err) => return HttpResponse::Unauthorized().body(err.to_string()),
+    };
+    let result = user_db.set_open_dms(&user_id, request.open_dms).await;
+
+    match result {
+        Ok(resp) => HttpResponse::Ok().json(resp),
+        Err(err) => err.error_response(),
+    }
+}
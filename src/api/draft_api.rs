@@ -0,0 +1,239 @@
+use actix_web::{
+    delete, get, post, put,
+    web::{Data, Json, Path, Query, ReqData},
+    HttpResponse, Responder, ResponseError,
+};
+use jwt::RegisteredClaims;
+
+use crate::{
+    auths::utils::get_user_id,
+    errors::error::{FieldError, TweetError},
+    model::{
+        draft_model::{Draft, DraftRequest},
+        pagination::PageQuery,
+    },
+    repo::draft_repo::DraftRepo,
+    services::publisher::TweetPublisher,
+};
+
+#[post("/drafts")]
+pub async fn create_draft(
+    db: Data<DraftRepo<Draft>>,
+    request: Json<DraftRequest>,
+    claims: Option<ReqData<RegisteredClaims>>,
+) -> impl Responder {
+    let user_id = match get_user_id(claims) {
+        Ok(id) => id,
+        Err(err) => return HttpResponse::Unauthorized().body(err.to_string()),
+    };
+    let mut request = request.into_inner();
+    request.publish_at = None;
+    let draft = match request.draft(&user_id) {
+        Ok(draft) => draft,
+        Err(err) => return err.error_response(),
+    };
+    let result = db.create(draft).await;
+
+    match result {
+        Ok(resp) => HttpResponse::Created().json(resp),
+        Err(err) => err.error_response(),
+    }
+}
+
+#[get("/drafts")]
+pub async fn list_drafts(
+    db: Data<DraftRepo<Draft>>,
+    page: Query<PageQuery>,
+    claims: Option<ReqData<RegisteredClaims>>,
+) -> impl Responder {
+    let user_id = match get_user_id(claims) {
+        Ok(id) => id,
+        Err(err) => return HttpResponse::Unauthorized().body(err.to_string()),
+    };
+    let result = db.drafts(&user_id, false, &page).await;
+
+    match result {
+        Ok(resp) => HttpResponse::Ok().json(resp),
+        Err(err) => err.error_response(),
+    }
+}
+
+#[get("/drafts/{id}")]
+pub async fn get_draft(
+    db: Data<DraftRepo<Draft>>,
+    path: Path<(String,)>,
+    claims: Option<ReqData<RegisteredClaims>>,
+) -> impl Responder {
+    let user_id = match get_user_id(claims) {
+        Ok(id) => id,
+        Err(err) => return HttpResponse::Unauthorized().body(err.to_string()),
+    };
+    let result = db.find_draft(path.0.as_str(), &user_id).await;
+
+    match result {
+        Ok(resp) => HttpResponse::Ok().json(resp.map()),
+        Err(err) => err.error_response(),
+    }
+}
+
+/// Replaces a draft or scheduled tweet, scheduling it when `publish_at` is set
+#[put("/drafts/{id}")]
+pub async fn update_draft(
+    db: Data<DraftRepo<Draft>>,
+    path: Path<(String,)>,
+    request: Json<DraftRequest>,
+    claims: Option<ReqData<RegisteredClaims>>,
+) -> impl Responder {
+    let user_id = match get_user_id(claims) {
+        Ok(id) => id,
+        Err(err) => return HttpResponse::Unauthorized().body(err.to_string()),
+    };
+    let draft = match request.draft(&user_id) {
+        Ok(draft) => draft,
+        Err(err) => return err.error_response(),
+    };
+    let result = db.update(path.0.as_str(), &user_id, draft).await;
+
+    match result {
+        Ok(resp) => HttpResponse::Ok().json(resp),
+        Err(err) => err.error_response(),
+    }
+}
+
+#[delete("/drafts/{id}")]
+pub async fn delete_draft(
+    db: Data<DraftRepo<Draft>>,
+    path: Path<(String,)>,
+    claims: Option<ReqData<RegisteredClaims>>,
+) -> impl Responder {
+    let user_id = match get_user_id(claims) {
+        Ok(id) => id,
+        Err(err) => return HttpResponse::Unauthorized().body(err.to_string()),
+    };
+    let result = db.delete(path.0.as_str(), &user_id).await;
+
+    match result {
+        Ok(_) => HttpResponse::Ok().json("Draft deleted successfully"),
+        Err(err) => err.error_response(),
+    }
+}
+
+#[post("/drafts/{id}/publish")]
+pub async fn publish_draft(
+    db: Data<DraftRepo<Draft>>,
+    publisher: Data<TweetPublisher>,
+    path: Path<(String,)>,
+    claims: Option<ReqData<RegisteredClaims>>,
+) -> impl Responder {
+    let user_id = match get_user_id(claims) {
+        Ok(id) => id,
+        Err(err) => return HttpResponse::Unauthorized().body(err.to_string()),
+    };
+    let draft = match db.claim(path.0.as_str(), &user_id).await {
+        Ok(draft) => draft,
+        Err(err) => return err.error_response(),
+    };
+    let result = publisher.publish_draft(&db, draft).await;
+
+    match result {
+        Ok(resp) => HttpResponse::Created().json(resp),
+        Err(err) => err.error_response(),
+    }
+}
+
+#[post("/scheduled")]
+pub async fn schedule_tweet(
+    db: Data<DraftRepo<Draft>>,
+    request: Json<DraftRequest>,
+    claims: Option<ReqData<RegisteredClaims>>,
+) -> impl Responder {
+    let user_id = match get_user_id(claims) {
+        Ok(id) => id,
+        Err(err) => return HttpResponse::Unauthorized().body(err.to_string()),
+    };
+    if request.publish_at.is_none() {
+        return TweetError::UnprocessableEntity(vec![FieldError::new(
+            "publish_at",
+            "required",
+            "This field is required",
+        )])
+        .error_response();
+    }
+    let draft = match request.draft(&user_id) {
+        Ok(draft) => draft,
+        Err(err) => return err.error_response(),
+    };
+    let result = db.create(draft).await;
+
+    match result {
+        Ok(resp) => HttpResponse::Created().json(resp),
+        Err(err) => err.error_response(),
+    }
+}
+
+#[get("/scheduled")]
+pub async fn list_scheduled(
+    db: Data<DraftRepo<Draft>>,
+    page: Query<PageQuery>,
+    claims: Option<ReqData<RegisteredClaims>>,
+) -> impl Responder {
+    let user_id = match get_user_id(claims) {
+        Ok(id) => id,
+        Err(err) => return HttpResponse::Unauthorized().body(err.to_string()),
+    };
+    let result = db.drafts(&user_id, true, &page).await;
+
+    match result {
+        Ok(resp) => HttpResponse::Ok().json(resp),
+        Err(err) => err.error_response(),
+    }
+}
+
+#[put("/scheduled/{id}")]
+pub async fn update_scheduled(
+    db: Data<DraftRepo<Draft>>,
+    path: Path<(String,)>,
+    request: Json<DraftRequest>,
+    claims: Option<ReqData<RegisteredClaims>>,
+) -> impl Responder {
+    let user_id = match get_user_id(claims) {
+        Ok(id) => id,
+        Err(err) => return HttpResponse::Unauthorized().body(err.to_string()),
+    };
+    if request.publish_at.is_none() {
+        return TweetError::UnprocessableEntity(vec![FieldError::new(
+            "publish_at",
+            "required",
+            "This field is required",
+        )])
+        .error_response();
+    }
+    let draft = match request.draft(&user_id) {
+        Ok(draft) => draft,
+        Err(err) => return err.error_response(),
+    };
+    let result = db.update(path.0.as_str(), &user_id, draft).await;
+
+    match result {
+        Ok(resp) => HttpResponse::Ok().json(resp),
+        Err(err) => err.error_response(),
+    }
+}
+
+#[delete("/scheduled/{id}")]
+pub async fn cancel_scheduled(
+    db: Data<DraftRepo<Draft>>,
+    path: Path<(String,)>,
+    claims: Option<ReqData<RegisteredClaims>>,
+) -> impl Responder {
+    let user_id = match get_user_id(claims) {
+        Ok(id) => id,
+        Err(err) => return HttpResponse::Unauthorized().body(err.to_string()),
+    };
+    let result = db.delete(path.0.as_str(), &user_id).await;
+
+    match result {
+        Ok(_) => HttpResponse::Ok().json("Scheduled tweet cancelled successfully"),
+        Err(err) => err.error_response(),
+    }
+}
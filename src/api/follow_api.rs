@@ -0,0 +1,204 @@
+use actix_web::{
+    delete, get, post, put,
+    web::{Data, Json, Path, ReqData},
+    HttpResponse, Responder, ResponseError,
+};
+use jwt::RegisteredClaims;
+
+use crate::{
+    auths::utils::get_user_id,
+    dtos::dto::FollowRequestDto,
+    errors::error::TweetError,
+    model::{
+        auth_model::User,
+        follow_model::{Follow, PrivacySettingsRequest},
+        relation_model::Relation,
+    },
+    repo::{follow_repo::FollowRepo, relation_repo::RelationRepo, user_repo::UserRepo},
+};
+
+#[post("/users/{user_id}/follow")]
+pub async fn follow_user(
+    db: Data<FollowRepo<Follow>>,
+    user_db: Data<UserRepo<User>>,
+    relations: Data<RelationRepo<Relation>>,
+    path: Path<(String,)>,
+    claims: Option<ReqData<RegisteredClaims>>,
+) -> impl Responder {
+    let user_id = match get_user_id(claims) {
+        Ok(id) => id,
+        Err(err) => return HttpResponse::Unauthorized().body(err.to_string()),
+    };
+    let followee_id = path.0.as_str();
+    let followee = match user_db.find_user(followee_id).await {
+        Ok(user) => user,
+        Err(err) => return err.error_response(),
+    };
+    match relations.is_blocked(&user_id, followee_id).await {
+        Ok(false) => {}
+        Ok(true) => {
+            return TweetError::Forbidden("You cannot follow this user".into()).error_response()
+        }
+        Err(err) => return err.error_response(),
+    }
+    let result = if followee.protected {
+        db.request_follow(&user_id, followee_id).await
+    } else {
+        db.follow(&user_id, followee_id).await
+    };
+
+    match result {
+        Ok(resp) => HttpResponse::Ok().json(resp),
+        Err(err) => err.error_response(),
+    }
+}
+
+#[delete("/users/{user_id}/follow")]
+pub async fn unfollow_user(
+    db: Data<FollowRepo<Follow>>,
+    path: Path<(String,)>,
+    claims: Option<ReqData<RegisteredClaims>>,
+) -> impl Responder {
+    let user_id = match get_user_id(claims) {
+        Ok(id) => id,
+        Err(err) => return HttpResponse::Unauthorized().body(err.to_string()),
+    };
+    let result = db.unfollow(&user_id, path.0.as_str()).await;
+
+    match result {
+        Ok(resp) => HttpResponse::Ok().json(resp),
+        Err(err) => err.error_response(),
+    }
+}
+
+#[get("/users/{user_id}/followers")]
+pub async fn followers(
+    db: Data<FollowRepo<Follow>>,
+    user_db: Data<UserRepo<User>>,
+    path: Path<(String,)>,
+) -> impl Responder {
+    let ids = match db.follower_ids(path.0.as_str()).await {
+        Ok(ids) => ids,
+        Err(err) => return err.error_response(),
+    };
+    let result = user_db.profiles(&ids).await;
+
+    match result {
+        Ok(resp) => HttpResponse::Ok().json(resp),
+        Err(err) => err.error_response(),
+    }
+}
+
+#[get("/users/{user_id}/following")]
+pub async fn following(
+    db: Data<FollowRepo<Follow>>,
+    user_db: Data<UserRepo<User>>,
+    path: Path<(String,)>,
+) -> impl Responder {
+    let ids = match db.following_ids(path.0.as_str()).await {
+        Ok(ids) => ids,
+        Err(err) => return err.error_response(),
+    };
+    let result = user_db.profiles(&ids).await;
+
+    match result {
+        Ok(resp) => HttpResponse::Ok().json(resp),
+        Err(err) => err.error_response(),
+    }
+}
+
+#[get("/follow-requests")]
+pub async fn follow_requests(
+    db: Data<FollowRepo<Follow>>,
+    user_db: Data<UserRepo<User>>,
+    claims: Option<ReqData<RegisteredClaims>>,
+) -> impl Responder {
+    let user_id = match get_user_id(claims) {
+        Ok(id) => id,
+        Err(err) => return HttpResponse::Unauthorized().body(err.to_string()),
+    };
+    let requests = match db.follow_requests(&user_id).await {
+        Ok(requests) => requests,
+        Err(err) => return err.error_response(),
+    };
+    let requester_ids = requests.iter().map(|r| r.requester_id).collect::<Vec<_>>();
+    let mut profiles = match user_db.profiles(&requester_ids).await {
+        Ok(profiles) => profiles,
+        Err(err) => return err.error_response(),
+    };
+    let result = requests
+        .iter()
+        .filter_map(|request| {
+            let requester_id = request.requester_id.to_hex();
+            let index = profiles.iter().position(|p| p.id == requester_id)?;
+            Some(FollowRequestDto {
+                id: request.id?.to_hex(),
+                requester: profiles.swap_remove(index),
+                created_at: request.created_at,
+            })
+        })
+        .collect::<Vec<FollowRequestDto>>();
+
+    HttpResponse::Ok().json(result)
+}
+
+#[post("/follow-requests/{request_id}/approve")]
+pub async fn approve_follow_request(
+    db: Data<FollowRepo<Follow>>,
+    path: Path<(String,)>,
+    claims: Option<ReqData<RegisteredClaims>>,
+) -> impl Responder {
+    let user_id = match get_user_id(claims) {
+        Ok(id) => id,
+        Err(err) => return HttpResponse::Unauthorized().body(err.to_string()),
+    };
+    let result = db.approve_request(&user_id, path.0.as_str()).await;
+
+    match result {
+        Ok(resp) => HttpResponse::Ok().json(resp),
+        Err(err) => err.error_response(),
+    }
+}
+
+#[post("/follow-requests/{request_id}/reject")]
+pub async fn reject_follow_request(
+    db: Data<FollowRepo<Follow>>,
+    path: Path<(String,)>,
+    claims: Option<ReqData<RegisteredClaims>>,
+) -> impl Responder {
+    let user_id = match get_user_id(claims) {
+        Ok(id) => id,
+        Err(err) => return HttpResponse::Unauthorized().body(err.to_string()),
+    };
+    let result = db.reject_request(&user_id, path.0.as_str()).await;
+
+    match result {
+        Ok(resp) => HttpResponse::Ok().json(resp),
+        Err(err) => err.error_response(),
+    }
+}
+
+/// Protects or unprotects the user's tweets, approving pending requests when unprotecting
+#[put("/account/privacy")]
+pub async fn update_privacy(
+    db: Data<FollowRepo<Follow>>,
+    user_db: Data<UserRepo<User>>,
+    request: Json<PrivacySettingsRequest>,
+    claims: Option<ReqData<RegisteredClaims>>,
+) -> impl Responder {
+    let user_id = match get_user_id(claims) {
+        Ok(id) => id,
+        Err(err) => return HttpResponse::Unauthorized().body(err.to_string()),
+    };
+    let result = user_db.set_protected(&user_id, request.protected).await;
+    if result.is_ok() && !request.protected {
+        if let Err(err) = db.approve_all(&user_id).await {
+            return err.error_response();
+        }
+    }
+
+    match result {
+        Ok(resp) => HttpResponse::Ok().json(resp),
+        Err(err) => err.error_response(),
+    }
+}
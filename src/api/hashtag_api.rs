@@ -0,0 +1,50 @@
+use actix_web::{
+    get,
+    web::{Data, Path, Query, ReqData},
+    HttpResponse, Responder, ResponseError,
+};
+use jwt::RegisteredClaims;
+
+use crate::{
+    auths::utils::get_user_id,
+    model::{bookmark_model::Bookmark, pagination::PageQuery, tweet_model::Tweet},
+    repo::{bookmark_repo::BookmarkRepo, tweet_repo::TweetRepo},
+    services::trending::{TrendQuery, TrendWindow, TrendingService},
+};
+
+#[get("/hashtags/{tag}")]
+pub async fn hashtag_tweets(
+    db: Data<TweetRepo<Tweet>>,
+    bookmarks: Data<BookmarkRepo<Bookmark>>,
+    path: Path<(String,)>,
+    page: Query<PageQuery>,
+    claims: Option<ReqData<RegisteredClaims>>,
+) -> impl Responder {
+    let user_id = match get_user_id(claims) {
+        Ok(id) => id,
+        Err(err) => return HttpResponse::Unauthorized().body(err.to_string()),
+    };
+    let tag = path.0.trim_start_matches('#');
+    if tag.is_empty() {
+        return HttpResponse::BadRequest().body("Hashtag not provided");
+    }
+    let mut tweets = match db.hashtag_tweets(tag, &user_id, &page).await {
+        Ok(tweets) => tweets,
+        Err(err) => return err.error_response(),
+    };
+    if let Err(err) = db.reveal_polls(&user_id, &mut tweets).await {
+        return err.error_response();
+    }
+    let result = bookmarks.mark_bookmarked(&user_id, &mut tweets).await;
+
+    match result {
+        Ok(_) => HttpResponse::Ok().json(tweets),
+        Err(err) => err.error_response(),
+    }
+}
+
+#[get("/trends")]
+pub async fn trends(trending: Data<TrendingService>, query: Query<TrendQuery>) -> impl Responder {
+    let window = query.window.unwrap_or(TrendWindow::Hour);
+    HttpResponse::Ok().json(trending.trends(window))
+}
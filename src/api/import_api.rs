@@ -0,0 +1,58 @@
+use actix_web::{
+    post,
+    web::{Data, Path, Payload, ReqData},
+    HttpResponse, Responder, ResponseError,
+};
+use futures::StreamExt;
+use jwt::RegisteredClaims;
+
+use crate::{
+    auths::utils::require_role,
+    config::settings::archive_import_max_size,
+    errors::error::TweetError,
+    model::auth_model::{User, UserRole},
+    repo::user_repo::UserRepo,
+    services::importer::ArchiveImporter,
+};
+
+/// Reads the archive sent as the request body, failing once it grows past the maximum size
+async fn read_archive(mut payload: Payload) -> Result<String, TweetError> {
+    let max_size = archive_import_max_size();
+    let mut data = Vec::new();
+    while let Some(chunk) = payload.next().await {
+        let chunk = chunk.map_err(|_| TweetError::BadRequest("Invalid archive".into()))?;
+        if data.len() + chunk.len() > max_size {
+            return Err(TweetError::BadRequest(format!(
+                "The archive must be at most {} bytes",
+                max_size
+            )));
+        }
+        data.extend_from_slice(&chunk);
+    }
+    String::from_utf8(data)
+        .map_err(|_| TweetError::BadRequest("The archive is not valid UTF-8".into()))
+}
+
+/// Imports the `tweets.js` file of a Twitter archive, sent as the request body, for the user
+#[post("/admin/users/{user_id}/import")]
+pub async fn import_archive(
+    importer: Data<ArchiveImporter>,
+    user_db: Data<UserRepo<User>>,
+    path: Path<(String,)>,
+    payload: Payload,
+    claims: Option<ReqData<RegisteredClaims>>,
+) -> impl Responder {
+    if let Err(err) = require_role(&user_db, claims, UserRole::Admin).await {
+        return err.error_response();
+    }
+    let content = match read_archive(payload).await {
+        Ok(content) => content,
+        Err(err) => return err.error_response(),
+    };
+    let result = importer.import(path.0.as_str(), &content).await;
+
+    match result {
+        Ok(resp) => HttpResponse::Ok().json(resp),
+        Err(err) => err.error_response(),
+    }
+}
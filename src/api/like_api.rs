@@ -1,28 +1,54 @@
 use actix_web::{
     post,
     delete,
-    web::{Data, Path},
-    HttpResponse, Responder,
+    web::{Data, Path, ReqData},
+    HttpRequest, HttpResponse, Responder, ResponseError,
 };
+use jwt::RegisteredClaims;
 
-use crate::{model::tweet_model::Tweet, repo::tweet_repo::TweetRepo};
+use crate::{
+    auths::utils::get_user_id, model::tweet_model::Tweet, repo::tweet_repo::TweetRepo,
+    services::idempotency::IdempotencyService,
+};
 
 #[post("/likes/{tweet_id}")]
-pub async fn plus_one(db: Data<TweetRepo<Tweet>>, tweet_id: Path<(String,)>) -> impl Responder {
+pub async fn plus_one(
+    db: Data<TweetRepo<Tweet>>,
+    idempotency: Data<IdempotencyService>,
+    tweet_id: Path<(String,)>,
+    claims: Option<ReqData<RegisteredClaims>>,
+    request: HttpRequest,
+) -> impl Responder {
+    let user_id = match get_user_id(claims) {
+        Ok(id) => id,
+        Err(err) => return HttpResponse::Unauthorized().body(err.to_string()),
+    };
     let id = tweet_id.0.as_str();
     if id.is_empty() {
         return HttpResponse::BadRequest().body(format!("Id not provided"));
     }
-    let result = db.create_like(id).await;
+    idempotency
+        .run(&request, &user_id, &(), || async {
+            let result = db.create_like(id, &user_id).await;
 
-    match result {
-        Ok(resp) => HttpResponse::Created().json(resp),
-        Err(err) => HttpResponse::InternalServerError().body(err.to_string()),
-    }
+            match result {
+                Ok(resp) => HttpResponse::Created().json(resp),
+                Err(err) => err.error_response(),
+            }
+        })
+        .await
 }
 
 #[delete("/likes/{tweet_id}/{like_id}")]
-pub async fn minus_one(db: Data<TweetRepo<Tweet>>, path: Path<(String, String,)>) -> impl Responder {
+pub async fn minus_one(
+    db: Data<TweetRepo<Tweet>>,
+    path: Path<(String, String)>,
+    claims: Option<ReqData<RegisteredClaims>>,
+) -> impl Responder {
+    let user_id = match get_user_id(claims) {
+        Ok(id) => id,
+        Err(err) => return HttpResponse::Unauthorized().body(err.to_string()),
+    };
     let tweet_id = path.0.as_str();
     let like_id = path.1.as_str();
     if tweet_id.is_empty() {
@@ -31,10 +57,10 @@ pub async fn minus_one(db: Data<TweetRepo<Tweet>>, path: Path<(String, String,)>
     if like_id.is_empty() {
         return HttpResponse::BadRequest().body(format!("like id not provided"));
     }
-    let result = db.remove_like(tweet_id, like_id).await;
+    let result = db.remove_like(tweet_id, like_id, &user_id).await;
 
     match result {
         Ok(resp) => HttpResponse::Ok().json(resp),
-        Err(err) => HttpResponse::InternalServerError().body(err.to_string()),
+        Err(err) => err.error_response(),
     }
 }
\ No newline at end of file
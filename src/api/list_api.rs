@@ -0,0 +1,253 @@
+use actix_web::{
+    delete, get, post, put,
+    web::{Data, Json, Path, Query, ReqData},
+    HttpResponse, Responder, ResponseError,
+};
+use jwt::RegisteredClaims;
+
+use crate::{
+    auths::utils::get_user_id,
+    errors::error::TweetError,
+    model::{
+        auth_model::User,
+        bookmark_model::Bookmark,
+        list_model::{ListRequest, UserList},
+        pagination::PageQuery,
+        relation_model::Relation,
+        tweet_model::Tweet,
+    },
+    repo::{
+        bookmark_repo::BookmarkRepo, list_repo::ListRepo, relation_repo::RelationRepo,
+        tweet_repo::TweetRepo, user_repo::UserRepo,
+    },
+};
+
+#[post("/lists")]
+pub async fn create_list(
+    db: Data<ListRepo<UserList>>,
+    request: Json<ListRequest>,
+    claims: Option<ReqData<RegisteredClaims>>,
+) -> impl Responder {
+    let user_id = match get_user_id(claims) {
+        Ok(id) => id,
+        Err(err) => return HttpResponse::Unauthorized().body(err.to_string()),
+    };
+    let list = match request.list(&user_id) {
+        Ok(list) => list,
+        Err(err) => return err.error_response(),
+    };
+    let result = db.create(list).await;
+
+    match result {
+        Ok(resp) => HttpResponse::Created().json(resp),
+        Err(err) => err.error_response(),
+    }
+}
+
+#[get("/lists")]
+pub async fn my_lists(
+    db: Data<ListRepo<UserList>>,
+    page: Query<PageQuery>,
+    claims: Option<ReqData<RegisteredClaims>>,
+) -> impl Responder {
+    let user_id = match get_user_id(claims) {
+        Ok(id) => id,
+        Err(err) => return HttpResponse::Unauthorized().body(err.to_string()),
+    };
+    let result = db.lists(&user_id, &user_id, &page).await;
+
+    match result {
+        Ok(resp) => HttpResponse::Ok().json(resp),
+        Err(err) => err.error_response(),
+    }
+}
+
+#[get("/users/{user_id}/lists")]
+pub async fn user_lists(
+    db: Data<ListRepo<UserList>>,
+    path: Path<(String,)>,
+    page: Query<PageQuery>,
+    claims: Option<ReqData<RegisteredClaims>>,
+) -> impl Responder {
+    let user_id = match get_user_id(claims) {
+        Ok(id) => id,
+        Err(err) => return HttpResponse::Unauthorized().body(err.to_string()),
+    };
+    let result = db.lists(path.0.as_str(), &user_id, &page).await;
+
+    match result {
+        Ok(resp) => HttpResponse::Ok().json(resp),
+        Err(err) => err.error_response(),
+    }
+}
+
+#[get("/lists/{list_id}")]
+pub async fn get_list(
+    db: Data<ListRepo<UserList>>,
+    path: Path<(String,)>,
+    claims: Option<ReqData<RegisteredClaims>>,
+) -> impl Responder {
+    let user_id = match get_user_id(claims) {
+        Ok(id) => id,
+        Err(err) => return HttpResponse::Unauthorized().body(err.to_string()),
+    };
+    let result = db.find_list(path.0.as_str(), &user_id).await;
+
+    match result {
+        Ok(resp) => HttpResponse::Ok().json(resp.map()),
+        Err(err) => err.error_response(),
+    }
+}
+
+#[put("/lists/{list_id}")]
+pub async fn update_list(
+    db: Data<ListRepo<UserList>>,
+    path: Path<(String,)>,
+    request: Json<ListRequest>,
+    claims: Option<ReqData<RegisteredClaims>>,
+) -> impl Responder {
+    let user_id = match get_user_id(claims) {
+        Ok(id) => id,
+        Err(err) => return HttpResponse::Unauthorized().body(err.to_string()),
+    };
+    let list = match request.list(&user_id) {
+        Ok(list) => list,
+        Err(err) => return err.error_response(),
+    };
+    let result = db.update(path.0.as_str(), &user_id, &list).await;
+
+    match result {
+        Ok(resp) => HttpResponse::Ok().json(resp),
+        Err(err) => err.error_response(),
+    }
+}
+
+#[delete("/lists/{list_id}")]
+pub async fn delete_list(
+    db: Data<ListRepo<UserList>>,
+    path: Path<(String,)>,
+    claims: Option<ReqData<RegisteredClaims>>,
+) -> impl Responder {
+    let user_id = match get_user_id(claims) {
+        Ok(id) => id,
+        Err(err) => return HttpResponse::Unauthorized().body(err.to_string()),
+    };
+    let result = db.delete(path.0.as_str(), &user_id).await;
+
+    match result {
+        Ok(resp) => HttpResponse::Ok().json(resp),
+        Err(err) => err.error_response(),
+    }
+}
+
+#[get("/lists/{list_id}/members")]
+pub async fn list_members(
+    db: Data<ListRepo<UserList>>,
+    user_db: Data<UserRepo<User>>,
+    path: Path<(String,)>,
+    claims: Option<ReqData<RegisteredClaims>>,
+) -> impl Responder {
+    let user_id = match get_user_id(claims) {
+        Ok(id) => id,
+        Err(err) => return HttpResponse::Unauthorized().body(err.to_string()),
+    };
+    let list = match db.find_list(path.0.as_str(), &user_id).await {
+        Ok(list) => list,
+        Err(err) => return err.error_response(),
+    };
+    let result = user_db.profiles(&list.member_ids).await;
+
+    match result {
+        Ok(resp) => HttpResponse::Ok().json(resp),
+        Err(err) => err.error_response(),
+    }
+}
+
+#[post("/lists/{list_id}/members/{user_id}")]
+pub async fn add_list_member(
+    db: Data<ListRepo<UserList>>,
+    user_db: Data<UserRepo<User>>,
+    relations: Data<RelationRepo<Relation>>,
+    path: Path<(String, String)>,
+    claims: Option<ReqData<RegisteredClaims>>,
+) -> impl Responder {
+    let user_id = match get_user_id(claims) {
+        Ok(id) => id,
+        Err(err) => return HttpResponse::Unauthorized().body(err.to_string()),
+    };
+    let (list_id, member_id) = (path.0.as_str(), path.1.as_str());
+    let member = match user_db.find_user(member_id).await {
+        Ok(member) => member,
+        Err(err) => return err.error_response(),
+    };
+    match relations.is_blocked(&user_id, member_id).await {
+        Ok(false) => {}
+        Ok(true) => {
+            return TweetError::Forbidden("You cannot add this user to a list".into())
+                .error_response()
+        }
+        Err(err) => return err.error_response(),
+    }
+    let result = db.add_member(list_id, &user_id, member.id.unwrap()).await;
+
+    match result {
+        Ok(resp) => HttpResponse::Ok().json(resp),
+        Err(err) => err.error_response(),
+    }
+}
+
+#[delete("/lists/{list_id}/members/{user_id}")]
+pub async fn remove_list_member(
+    db: Data<ListRepo<UserList>>,
+    path: Path<(String, String)>,
+    claims: Option<ReqData<RegisteredClaims>>,
+) -> impl Responder {
+    let user_id = match get_user_id(claims) {
+        Ok(id) => id,
+        Err(err) => return HttpResponse::Unauthorized().body(err.to_string()),
+    };
+    let result = db
+        .remove_member(path.0.as_str(), &user_id, path.1.as_str())
+        .await;
+
+    match result {
+        Ok(resp) => HttpResponse::Ok().json(resp),
+        Err(err) => err.error_response(),
+    }
+}
+
+/// Timeline of the tweets of the list members the user can see
+#[get("/lists/{list_id}/tweets")]
+pub async fn list_timeline(
+    db: Data<ListRepo<UserList>>,
+    tweet_db: Data<TweetRepo<Tweet>>,
+    bookmarks: Data<BookmarkRepo<Bookmark>>,
+    path: Path<(String,)>,
+    page: Query<PageQuery>,
+    claims: Option<ReqData<RegisteredClaims>>,
+) -> impl Responder {
+    let user_id = match get_user_id(claims) {
+        Ok(id) => id,
+        Err(err) => return HttpResponse::Unauthorized().body(err.to_string()),
+    };
+    let list = match db.find_list(path.0.as_str(), &user_id).await {
+        Ok(list) => list,
+        Err(err) => return err.error_response(),
+    };
+    let mut tweets = match tweet_db
+        .member_tweets(&list.member_ids, &user_id, &page)
+        .await
+    {
+        Ok(tweets) => tweets,
+        Err(err) => return err.error_response(),
+    };
+    if let Err(err) = tweet_db.reveal_polls(&user_id, &mut tweets).await {
+        return err.error_response();
+    }
+    let result = bookmarks.mark_bookmarked(&user_id, &mut tweets).await;
+
+    match result {
+        Ok(_) => HttpResponse::Ok().json(tweets),
+        Err(err) => err.error_response(),
+    }
+}
@@ -0,0 +1,261 @@
+use actix_multipart::{Field, Multipart};
+use actix_web::{
+    get, patch, post,
+    web::{self, Data, Json, Path, ReqData},
+    HttpResponse, Responder, ResponseError,
+};
+use bson::oid::ObjectId;
+use chrono::Utc;
+use futures::StreamExt;
+use jwt::RegisteredClaims;
+use uuid::Uuid;
+
+use crate::{
+    auths::utils::get_user_id,
+    config::settings::media_max_video_size,
+    errors::error::TweetError,
+    model::{
+        media_model::{Media, MediaRequest},
+        tweet_model::Tweet,
+    },
+    repo::{media_repo::MediaRepo, tweet_repo::TweetRepo},
+    services::{
+        media::{process_upload, ProcessedMedia},
+        media_store::MediaStore,
+    },
+};
+
+const MAX_ALT_TEXT_BYTES: usize = 4 * 1024;
+
+/// Reads a multipart field, failing once it grows past `max_size` bytes
+async fn read_field(field: &mut Field, max_size: usize) -> Result<Vec<u8>, TweetError> {
+    let mut data = Vec::new();
+    while let Some(chunk) = field.next().await {
+        let chunk = chunk.map_err(|_| TweetError::BadRequest("Invalid upload".into()))?;
+        if data.len() + chunk.len() > max_size {
+            return Err(TweetError::BadRequest(format!(
+                "The upload must be at most {} bytes",
+                max_size
+            )));
+        }
+        data.extend_from_slice(&chunk);
+    }
+    Ok(data)
+}
+
+/// Reads the `file` and optional `alt_text` fields of an upload
+async fn read_upload(mut payload: Multipart) -> Result<(Vec<u8>, MediaRequest), TweetError> {
+    let mut file = None;
+    let mut request = MediaRequest { alt_text: None };
+    while let Some(field) = payload.next().await {
+        let mut field = field.map_err(|_| TweetError::BadRequest("Invalid upload".into()))?;
+        match field.name() {
+            Some("file") => file = Some(read_field(&mut field, media_max_video_size()).await?),
+            Some("alt_text") => {
+                let alt_text = read_field(&mut field, MAX_ALT_TEXT_BYTES).await?;
+                request.alt_text = Some(String::from_utf8_lossy(&alt_text).into_owned());
+            }
+            _ => {}
+        }
+    }
+    match file {
+        Some(file) => Ok((file, request)),
+        None => Err(TweetError::BadRequest("File not provided".into())),
+    }
+}
+
+/// Get media the user may see, as not found otherwise
+async fn find_visible_media(
+    db: &MediaRepo<Media>,
+    tweets: &TweetRepo<Tweet>,
+    id: &str,
+    user_id: &str,
+) -> Result<Media, TweetError> {
+    let media = db.find(id).await?;
+    tweets.check_media_visible(&media, user_id).await?;
+    Ok(media)
+}
+
+/// Removes stored files of an upload that could not be completed
+fn remove_files(store: &dyn MediaStore, keys: &[String]) {
+    for key in keys {
+        if let Err(err) = store.delete(key) {
+            log::error!("Failed to remove media file {}: {}", key, err);
+        }
+    }
+}
+
+/// Reads a stored file on the blocking pool, files can be as large as a video
+async fn read_file(store: &Data<dyn MediaStore>, key: &str) -> Result<Vec<u8>, TweetError> {
+    let store = store.clone();
+    let key = key.to_string();
+    web::block(move || store.get(&key))
+        .await
+        .map_err(|_| TweetError::InternalServerError)?
+}
+
+/// Validates and stores an upload and its thumbnail, returning their keys.
+/// Decoding, re-encoding and writing files block, so this runs on the blocking pool
+fn store_upload(
+    store: &dyn MediaStore,
+    user_id: &str,
+    file: Vec<u8>,
+) -> Result<(ProcessedMedia, String, Option<String>), TweetError> {
+    let processed = process_upload(file)?;
+    let name = Uuid::new_v4();
+    let key = format!("{}/{}.{}", user_id, name, processed.extension);
+    store.put(&key, &processed.data)?;
+    let mut thumbnail_key = None;
+    if let Some(thumbnail) = &processed.thumbnail {
+        let thumbnail_name = format!(
+            "{}/{}_thumb.{}",
+            user_id,
+            name,
+            processed.thumbnail_extension()
+        );
+        if let Err(err) = store.put(&thumbnail_name, thumbnail) {
+            remove_files(store, &[key]);
+            return Err(err);
+        }
+        thumbnail_key = Some(thumbnail_name);
+    }
+    Ok((processed, key, thumbnail_key))
+}
+
+#[post("/media")]
+pub async fn upload_media(
+    db: Data<MediaRepo<Media>>,
+    store: Data<dyn MediaStore>,
+    payload: Multipart,
+    claims: Option<ReqData<RegisteredClaims>>,
+) -> impl Responder {
+    let user_id = match get_user_id(claims) {
+        Ok(id) => id,
+        Err(err) => return HttpResponse::Unauthorized().body(err.to_string()),
+    };
+    let (file, request) = match read_upload(payload).await {
+        Ok(upload) => upload,
+        Err(err) => return err.error_response(),
+    };
+    let alt_text = match request.validated_alt_text() {
+        Ok(alt_text) => alt_text,
+        Err(err) => return err.error_response(),
+    };
+    let owner_id = match ObjectId::parse_str(&user_id) {
+        Ok(id) => id,
+        Err(_) => {
+            return TweetError::BadRequest("Invalid user Id provided".into()).error_response()
+        }
+    };
+    let upload_store = store.clone();
+    let upload_user_id = user_id.clone();
+    let stored = web::block(move || store_upload(upload_store.get_ref(), &upload_user_id, file));
+    let (processed, key, thumbnail_key) = match stored.await {
+        Ok(Ok(stored)) => stored,
+        Ok(Err(err)) => return err.error_response(),
+        Err(_) => return TweetError::InternalServerError.error_response(),
+    };
+    let media = Media {
+        id: None,
+        user_id: owner_id,
+        kind: processed.kind,
+        content_type: processed.content_type.to_string(),
+        key: key.clone(),
+        thumbnail_key: thumbnail_key.clone(),
+        width: processed.width,
+        height: processed.height,
+        size: processed.data.len(),
+        alt_text,
+        attached: false,
+        created_at: Utc::now(),
+    };
+    let result = db.create(media).await;
+
+    match result {
+        Ok(resp) => HttpResponse::Created().json(resp.map()),
+        Err(err) => {
+            let keys = [Some(key), thumbnail_key].into_iter().flatten();
+            remove_files(store.get_ref(), &keys.collect::<Vec<String>>());
+            err.error_response()
+        }
+    }
+}
+
+#[patch("/media/{id}")]
+pub async fn update_media(
+    db: Data<MediaRepo<Media>>,
+    path: Path<(String,)>,
+    request: Json<MediaRequest>,
+    claims: Option<ReqData<RegisteredClaims>>,
+) -> impl Responder {
+    let user_id = match get_user_id(claims) {
+        Ok(id) => id,
+        Err(err) => return HttpResponse::Unauthorized().body(err.to_string()),
+    };
+    let alt_text = match request.validated_alt_text() {
+        Ok(alt_text) => alt_text,
+        Err(err) => return err.error_response(),
+    };
+    let result = db.set_alt_text(path.0.as_str(), &user_id, alt_text).await;
+
+    match result {
+        Ok(resp) => HttpResponse::Ok().json(resp.map()),
+        Err(err) => err.error_response(),
+    }
+}
+
+#[get("/media/{id}")]
+pub async fn get_media(
+    db: Data<MediaRepo<Media>>,
+    tweets: Data<TweetRepo<Tweet>>,
+    store: Data<dyn MediaStore>,
+    path: Path<(String,)>,
+    claims: Option<ReqData<RegisteredClaims>>,
+) -> impl Responder {
+    let user_id = match get_user_id(claims) {
+        Ok(id) => id,
+        Err(err) => return HttpResponse::Unauthorized().body(err.to_string()),
+    };
+    let media = match find_visible_media(&db, &tweets, path.0.as_str(), &user_id).await {
+        Ok(media) => media,
+        Err(err) => return err.error_response(),
+    };
+    let result = read_file(&store, &media.key).await;
+
+    match result {
+        Ok(data) => HttpResponse::Ok()
+            .content_type(media.content_type.as_str())
+            .body(data),
+        Err(err) => err.error_response(),
+    }
+}
+
+#[get("/media/{id}/thumbnail")]
+pub async fn get_media_thumbnail(
+    db: Data<MediaRepo<Media>>,
+    tweets: Data<TweetRepo<Tweet>>,
+    store: Data<dyn MediaStore>,
+    path: Path<(String,)>,
+    claims: Option<ReqData<RegisteredClaims>>,
+) -> impl Responder {
+    let user_id = match get_user_id(claims) {
+        Ok(id) => id,
+        Err(err) => return HttpResponse::Unauthorized().body(err.to_string()),
+    };
+    let media = match find_visible_media(&db, &tweets, path.0.as_str(), &user_id).await {
+        Ok(media) => media,
+        Err(err) => return err.error_response(),
+    };
+    let key = match &media.thumbnail_key {
+        Some(key) => key,
+        None => return TweetError::NotFound("This media has no thumbnail".into()).error_response(),
+    };
+    let result = read_file(&store, key).await;
+
+    match result {
+        Ok(data) => HttpResponse::Ok()
+            .content_type(media.thumbnail_content_type())
+            .body(data),
+        Err(err) => err.error_response(),
+    }
+}
@@ -1,3 +1,18 @@
+pub mod audit_api;
+pub mod bookmark_api;
+pub mod dm_api;
+pub mod draft_api;
+pub mod follow_api;
+pub mod hashtag_api;
+pub mod import_api;
 pub mod like_api;
+pub mod list_api;
+pub mod media_api;
+pub mod notification_api;
+pub mod relation_api;
+pub mod report_api;
+pub mod search_api;
+pub mod spam_api;
+pub mod stream_api;
 pub mod tweet_api;
 pub mod user_api;
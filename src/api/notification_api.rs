@@ -0,0 +1,118 @@
+use actix_web::{
+    get, post, put,
+    web::{Data, Json, Path, Query, ReqData},
+    HttpResponse, Responder, ResponseError,
+};
+use jwt::RegisteredClaims;
+
+use crate::{
+    auths::utils::get_user_id,
+    dtos::dto::NotificationPageDto,
+    model::{
+        auth_model::User,
+        notification_model::{Notification, NotificationPreferences},
+        pagination::PageQuery,
+    },
+    repo::{notification_repo::NotificationRepo, user_repo::UserRepo},
+};
+
+#[get("/notifications")]
+pub async fn list_notifications(
+    db: Data<NotificationRepo<Notification>>,
+    user_db: Data<UserRepo<User>>,
+    page: Query<PageQuery>,
+    claims: Option<ReqData<RegisteredClaims>>,
+) -> impl Responder {
+    let user_id = match get_user_id(claims) {
+        Ok(id) => id,
+        Err(err) => return HttpResponse::Unauthorized().body(err.to_string()),
+    };
+    let groups = match db.notifications(&user_id, &page).await {
+        Ok(groups) => groups,
+        Err(err) => return err.error_response(),
+    };
+    let unread_count = match db.unread_count(&user_id).await {
+        Ok(count) => count,
+        Err(err) => return err.error_response(),
+    };
+    let actor_ids = groups.iter().flat_map(|g| g.actors()).collect::<Vec<_>>();
+    let usernames = match user_db.usernames(&actor_ids).await {
+        Ok(usernames) => usernames,
+        Err(err) => return err.error_response(),
+    };
+
+    HttpResponse::Ok().json(NotificationPageDto {
+        unread_count,
+        notifications: groups.iter().map(|g| g.map(&usernames)).collect(),
+    })
+}
+
+#[post("/notifications/read")]
+pub async fn mark_all_read(
+    db: Data<NotificationRepo<Notification>>,
+    claims: Option<ReqData<RegisteredClaims>>,
+) -> impl Responder {
+    let user_id = match get_user_id(claims) {
+        Ok(id) => id,
+        Err(err) => return HttpResponse::Unauthorized().body(err.to_string()),
+    };
+    let result = db.mark_all_read(&user_id).await;
+
+    match result {
+        Ok(_) => HttpResponse::Ok().json("All notifications marked as read"),
+        Err(err) => err.error_response(),
+    }
+}
+
+#[post("/notifications/{id}/read")]
+pub async fn mark_read(
+    db: Data<NotificationRepo<Notification>>,
+    path: Path<(String,)>,
+    claims: Option<ReqData<RegisteredClaims>>,
+) -> impl Responder {
+    let user_id = match get_user_id(claims) {
+        Ok(id) => id,
+        Err(err) => return HttpResponse::Unauthorized().body(err.to_string()),
+    };
+    let result = db.mark_read(&user_id, path.0.as_str()).await;
+
+    match result {
+        Ok(_) => HttpResponse::Ok().json("Notification marked as read"),
+        Err(err) => err.error_response(),
+    }
+}
+
+#[get("/notifications/preferences")]
+pub async fn get_preferences(
+    db: Data<NotificationRepo<Notification>>,
+    claims: Option<ReqData<RegisteredClaims>>,
+) -> impl Responder {
+    let user_id = match get_user_id(claims) {
+        Ok(id) => id,
+        Err(err) => return HttpResponse::Unauthorized().body(err.to_string()),
+    };
+    let result = db.preferences(&user_id).await;
+
+    match result {
+        Ok(resp) => HttpResponse::Ok().json(resp),
+        Err(err) => err.error_response(),
+    }
+}
+
+#[put("/notifications/preferences")]
+pub async fn update_preferences(
+    db: Data<NotificationRepo<Notification>>,
+    request: Json<NotificationPreferences>,
+    claims: Option<ReqData<RegisteredClaims>>,
+) -> impl Responder {
+    let user_id = match get_user_id(claims) {
+        Ok(id) => id,
+        Err(err) => return HttpResponse::Unauthorized().body(err.to_string()),
+    };
+    let result = db.update_preferences(&user_id, request.into_inner()).await;
+
+    match result {
+        Ok(resp) => HttpResponse::Ok().json(resp),
+        Err(err) => err.error_response(),
+    }
+}
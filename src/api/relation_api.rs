@@ -0,0 +1,193 @@
+use actix_web::{
+    delete, get, post, put,
+    web::{Data, Json, Path, ReqData},
+    HttpResponse, Responder, ResponseError,
+};
+use jwt::RegisteredClaims;
+
+use crate::{
+    auths::utils::get_user_id,
+    model::{
+        auth_model::User,
+        follow_model::Follow,
+        relation_model::{MutedWordsRequest, Relation, RelationKind},
+    },
+    repo::{follow_repo::FollowRepo, relation_repo::RelationRepo, user_repo::UserRepo},
+};
+
+#[post("/users/{user_id}/block")]
+pub async fn block_user(
+    db: Data<RelationRepo<Relation>>,
+    user_db: Data<UserRepo<User>>,
+    follow_db: Data<FollowRepo<Follow>>,
+    path: Path<(String,)>,
+    claims: Option<ReqData<RegisteredClaims>>,
+) -> impl Responder {
+    let user_id = match get_user_id(claims) {
+        Ok(id) => id,
+        Err(err) => return HttpResponse::Unauthorized().body(err.to_string()),
+    };
+    let target_id = path.0.as_str();
+    if let Err(err) = user_db.find_user(target_id).await {
+        return err.error_response();
+    }
+    if let Err(err) = db.add(&user_id, target_id, RelationKind::Block).await {
+        return err.error_response();
+    }
+    let result = match follow_db.unfollow(&user_id, target_id).await {
+        Ok(_) => follow_db.unfollow(target_id, &user_id).await,
+        Err(err) => Err(err),
+    };
+
+    match result {
+        Ok(_) => HttpResponse::Ok().json("Blocked successfully"),
+        Err(err) => err.error_response(),
+    }
+}
+
+#[delete("/users/{user_id}/block")]
+pub async fn unblock_user(
+    db: Data<RelationRepo<Relation>>,
+    path: Path<(String,)>,
+    claims: Option<ReqData<RegisteredClaims>>,
+) -> impl Responder {
+    let user_id = match get_user_id(claims) {
+        Ok(id) => id,
+        Err(err) => return HttpResponse::Unauthorized().body(err.to_string()),
+    };
+    let result = db
+        .remove(&user_id, path.0.as_str(), RelationKind::Block)
+        .await;
+
+    match result {
+        Ok(_) => HttpResponse::Ok().json("Unblocked successfully"),
+        Err(err) => err.error_response(),
+    }
+}
+
+#[get("/blocks")]
+pub async fn list_blocks(
+    db: Data<RelationRepo<Relation>>,
+    user_db: Data<UserRepo<User>>,
+    claims: Option<ReqData<RegisteredClaims>>,
+) -> impl Responder {
+    let user_id = match get_user_id(claims) {
+        Ok(id) => id,
+        Err(err) => return HttpResponse::Unauthorized().body(err.to_string()),
+    };
+    let ids = match db.target_ids(&user_id, RelationKind::Block).await {
+        Ok(ids) => ids,
+        Err(err) => return err.error_response(),
+    };
+    let result = user_db.profiles(&ids).await;
+
+    match result {
+        Ok(resp) => HttpResponse::Ok().json(resp),
+        Err(err) => err.error_response(),
+    }
+}
+
+#[post("/users/{user_id}/mute")]
+pub async fn mute_user(
+    db: Data<RelationRepo<Relation>>,
+    user_db: Data<UserRepo<User>>,
+    path: Path<(String,)>,
+    claims: Option<ReqData<RegisteredClaims>>,
+) -> impl Responder {
+    let user_id = match get_user_id(claims) {
+        Ok(id) => id,
+        Err(err) => return HttpResponse::Unauthorized().body(err.to_string()),
+    };
+    let target_id = path.0.as_str();
+    if let Err(err) = user_db.find_user(target_id).await {
+        return err.error_response();
+    }
+    let result = db.add(&user_id, target_id, RelationKind::Mute).await;
+
+    match result {
+        Ok(_) => HttpResponse::Ok().json("Muted successfully"),
+        Err(err) => err.error_response(),
+    }
+}
+
+#[delete("/users/{user_id}/mute")]
+pub async fn unmute_user(
+    db: Data<RelationRepo<Relation>>,
+    path: Path<(String,)>,
+    claims: Option<ReqData<RegisteredClaims>>,
+) -> impl Responder {
+    let user_id = match get_user_id(claims) {
+        Ok(id) => id,
+        Err(err) => return HttpResponse::Unauthorized().body(err.to_string()),
+    };
+    let result = db
+        .remove(&user_id, path.0.as_str(), RelationKind::Mute)
+        .await;
+
+    match result {
+        Ok(_) => HttpResponse::Ok().json("Unmuted successfully"),
+        Err(err) => err.error_response(),
+    }
+}
+
+#[get("/mutes")]
+pub async fn list_mutes(
+    db: Data<RelationRepo<Relation>>,
+    user_db: Data<UserRepo<User>>,
+    claims: Option<ReqData<RegisteredClaims>>,
+) -> impl Responder {
+    let user_id = match get_user_id(claims) {
+        Ok(id) => id,
+        Err(err) => return HttpResponse::Unauthorized().body(err.to_string()),
+    };
+    let ids = match db.target_ids(&user_id, RelationKind::Mute).await {
+        Ok(ids) => ids,
+        Err(err) => return err.error_response(),
+    };
+    let result = user_db.profiles(&ids).await;
+
+    match result {
+        Ok(resp) => HttpResponse::Ok().json(resp),
+        Err(err) => err.error_response(),
+    }
+}
+
+#[get("/muted-words")]
+pub async fn muted_words(
+    user_db: Data<UserRepo<User>>,
+    claims: Option<ReqData<RegisteredClaims>>,
+) -> impl Responder {
+    let user_id = match get_user_id(claims) {
+        Ok(id) => id,
+        Err(err) => return HttpResponse::Unauthorized().body(err.to_string()),
+    };
+    let result = user_db.find_user(&user_id).await;
+
+    match result {
+        Ok(user) => HttpResponse::Ok().json(user.muted_words),
+        Err(err) => err.error_response(),
+    }
+}
+
+/// Replaces the words hiding tweets containing them from the user's listings
+#[put("/muted-words")]
+pub async fn update_muted_words(
+    user_db: Data<UserRepo<User>>,
+    request: Json<MutedWordsRequest>,
+    claims: Option<ReqData<RegisteredClaims>>,
+) -> impl Responder {
+    let user_id = match get_user_id(claims) {
+        Ok(id) => id,
+        Err(err) => return HttpResponse::Unauthorized().body(err.to_string()),
+    };
+    let words = match request.validated_words() {
+        Ok(words) => words,
+        Err(err) => return err.error_response(),
+    };
+    let result = user_db.set_muted_words(&user_id, &words).await;
+
+    match result {
+        Ok(_) => HttpResponse::Ok().json(words),
+        Err(err) => err.error_response(),
+    }
+}
@@ -0,0 +1,356 @@
+use actix_web::{
+    get, post, put,
+    web::{Data, Json, Path, Query, ReqData},
+    HttpRequest, HttpResponse, Responder, ResponseError,
+};
+use bson::oid::ObjectId;
+use chrono::Utc;
+use jwt::RegisteredClaims;
+
+use crate::{
+    auths::utils::{client_info, get_user_id, require_role},
+    errors::error::{FieldError, TweetError},
+    model::{
+        audit_model::{AuditAction, AuditEvent, ClientInfo},
+        auth_model::{User, UserRole},
+        pagination::PageQuery,
+        report_model::{
+            HeldReviewRequest, ModerationAction, ModerationActionRequest, ModerationRecord, Report,
+            ReportQuery, ReportRequest, ReportStatus, ReportStatusRequest, ReportTarget,
+        },
+        tweet_model::Tweet,
+    },
+    repo::{
+        audit_repo::AuditRepo,
+        report_repo::{invalid_transition, ReportRepo},
+        tweet_repo::TweetRepo,
+        user_repo::UserRepo,
+    },
+    services::publisher::TweetPublisher,
+};
+
+/// Finds the author of the reported content, failing when the reporter cannot see it
+async fn find_offender(
+    report: &Report,
+    reporter_id: &str,
+    tweet_db: &TweetRepo<Tweet>,
+    user_db: &UserRepo<User>,
+) -> Result<Option<ObjectId>, TweetError> {
+    let target_id = report.target_id.to_hex();
+    let author_id = match report.target {
+        ReportTarget::Tweet => Some(tweet_db.view_tweet(&target_id, reporter_id).await?.user_id),
+        ReportTarget::Comment => {
+            let tweet_id = report.tweet_id.map(|id| id.to_hex()).unwrap_or_default();
+            let tweet = tweet_db.view_tweet(&tweet_id, reporter_id).await?;
+            match tweet.comments.into_iter().find(|c| c.id == target_id) {
+                Some(comment) => comment.user_id,
+                None => {
+                    return Err(TweetError::NotFound(format!(
+                        "No comment with id {} found",
+                        target_id
+                    )))
+                }
+            }
+        }
+        ReportTarget::User => Some(user_db.find_user(&target_id).await?.id.unwrap().to_hex()),
+    };
+    Ok(author_id.and_then(|id| ObjectId::parse_str(id).ok()))
+}
+
+/// Applies a moderation action to the content or user a report is about
+async fn apply_action(
+    report: &Report,
+    action: ModerationAction,
+    moderator_id: &str,
+    publisher: &TweetPublisher,
+    audit: &AuditRepo<AuditEvent>,
+    client: ClientInfo,
+) -> Result<(), TweetError> {
+    let target_id = report.target_id.to_hex();
+    match (action, report.target) {
+        (ModerationAction::HideTweet, ReportTarget::Tweet) => {
+            let tweet = publisher.tweets.set_withheld(&target_id, true).await?;
+            publisher.search.index_tweet(&tweet);
+        }
+        (ModerationAction::RemoveContent, ReportTarget::Tweet) => {
+            let result = publisher
+                .tweets
+                .delete_tweet(&target_id, moderator_id, None)
+                .await;
+            let event = AuditEvent::new(
+                AuditAction::DeleteTweet,
+                Some(moderator_id.to_string()),
+                client,
+            );
+            audit
+                .record(event.target(&target_id).outcome(&result))
+                .await;
+            result?;
+            publisher.search.remove_tweet(&target_id);
+        }
+        (ModerationAction::RemoveContent, ReportTarget::Comment) => {
+            let tweet_id = report.tweet_id.map(|id| id.to_hex()).unwrap_or_default();
+            publisher.tweets.get_tweet(&tweet_id).await?;
+            let result = publisher.tweets.remove_comment(&tweet_id, &target_id).await;
+            let event = AuditEvent::new(
+                AuditAction::DeleteComment,
+                Some(moderator_id.to_string()),
+                client,
+            );
+            audit
+                .record(event.target(&target_id).outcome(&result))
+                .await;
+            result?;
+        }
+        (ModerationAction::SuspendUser, _) => match report.offender_id {
+            Some(offender_id) => {
+                publisher
+                    .users
+                    .set_suspended(&offender_id.to_hex(), true)
+                    .await?
+            }
+            None => {
+                return Err(TweetError::BadRequest(
+                    "The reported content has no author to suspend".into(),
+                ))
+            }
+        },
+        _ => {
+            return Err(TweetError::UnprocessableEntity(vec![FieldError::new(
+                "action",
+                "invalid_action",
+                "This action cannot be applied to the reported content",
+            )]))
+        }
+    }
+    Ok(())
+}
+
+#[post("/reports")]
+pub async fn create_report(
+    db: Data<ReportRepo<Report>>,
+    tweet_db: Data<TweetRepo<Tweet>>,
+    user_db: Data<UserRepo<User>>,
+    request: Json<ReportRequest>,
+    claims: Option<ReqData<RegisteredClaims>>,
+) -> impl Responder {
+    let user_id = match get_user_id(claims) {
+        Ok(id) => id,
+        Err(err) => return HttpResponse::Unauthorized().body(err.to_string()),
+    };
+    let mut report = match request.report(&user_id) {
+        Ok(report) => report,
+        Err(err) => return err.error_response(),
+    };
+    report.offender_id = match find_offender(&report, &user_id, &tweet_db, &user_db).await {
+        Ok(offender_id) => offender_id,
+        Err(err) => return err.error_response(),
+    };
+    let result = db.create(report).await;
+
+    match result {
+        Ok(resp) => HttpResponse::Created().json(resp),
+        Err(err) => err.error_response(),
+    }
+}
+
+#[get("/moderation/reports")]
+pub async fn list_reports(
+    db: Data<ReportRepo<Report>>,
+    user_db: Data<UserRepo<User>>,
+    query: Query<ReportQuery>,
+    claims: Option<ReqData<RegisteredClaims>>,
+) -> impl Responder {
+    if let Err(err) = require_role(&user_db, claims, UserRole::Moderator).await {
+        return err.error_response();
+    }
+    let result = db.reports(query.status, &query.page_query()).await;
+
+    match result {
+        Ok(resp) => HttpResponse::Ok().json(resp),
+        Err(err) => err.error_response(),
+    }
+}
+
+#[get("/moderation/reports/{report_id}")]
+pub async fn get_report(
+    db: Data<ReportRepo<Report>>,
+    user_db: Data<UserRepo<User>>,
+    path: Path<(String,)>,
+    claims: Option<ReqData<RegisteredClaims>>,
+) -> impl Responder {
+    if let Err(err) = require_role(&user_db, claims, UserRole::Moderator).await {
+        return err.error_response();
+    }
+    let result = db.find_report(path.0.as_str()).await;
+
+    match result {
+        Ok(resp) => HttpResponse::Ok().json(resp.map()),
+        Err(err) => err.error_response(),
+    }
+}
+
+/// Triages or dismisses a report, reports are actioned by taking an action
+#[put("/moderation/reports/{report_id}/status")]
+pub async fn update_report_status(
+    db: Data<ReportRepo<Report>>,
+    user_db: Data<UserRepo<User>>,
+    path: Path<(String,)>,
+    request: Json<ReportStatusRequest>,
+    claims: Option<ReqData<RegisteredClaims>>,
+) -> impl Responder {
+    let moderator = match require_role(&user_db, claims, UserRole::Moderator).await {
+        Ok(moderator) => moderator,
+        Err(err) => return err.error_response(),
+    };
+    if request.status == ReportStatus::Actioned {
+        return TweetError::UnprocessableEntity(vec![FieldError::new(
+            "status",
+            "action_required",
+            "Reports are actioned by taking an action",
+        )])
+        .error_response();
+    }
+    let record = ModerationRecord {
+        moderator_id: moderator.id.unwrap(),
+        status: request.status,
+        action: None,
+        note: request.note.clone(),
+        created_at: Utc::now(),
+    };
+    let result = db.record(path.0.as_str(), record).await;
+
+    match result {
+        Ok(resp) => HttpResponse::Ok().json(resp),
+        Err(err) => err.error_response(),
+    }
+}
+
+/// Hides or removes the reported content or suspends its author, marking the report actioned
+#[post("/moderation/reports/{report_id}/actions")]
+pub async fn take_action(
+    db: Data<ReportRepo<Report>>,
+    publisher: Data<TweetPublisher>,
+    audit: Data<AuditRepo<AuditEvent>>,
+    path: Path<(String,)>,
+    request: Json<ModerationActionRequest>,
+    claims: Option<ReqData<RegisteredClaims>>,
+    http_request: HttpRequest,
+) -> impl Responder {
+    let moderator = match require_role(&publisher.users, claims, UserRole::Moderator).await {
+        Ok(moderator) => moderator,
+        Err(err) => return err.error_response(),
+    };
+    let report = match db.find_report(path.0.as_str()).await {
+        Ok(report) => report,
+        Err(err) => return err.error_response(),
+    };
+    if !report.status.next().contains(&ReportStatus::Actioned) {
+        return invalid_transition(report.status, ReportStatus::Actioned).error_response();
+    }
+    let moderator_id = moderator.id.unwrap().to_hex();
+    let applied = apply_action(
+        &report,
+        request.action,
+        &moderator_id,
+        &publisher,
+        &audit,
+        client_info(&http_request),
+    )
+    .await;
+    if let Err(err) = applied {
+        return err.error_response();
+    }
+    let record = ModerationRecord {
+        moderator_id: moderator.id.unwrap(),
+        status: ReportStatus::Actioned,
+        action: Some(request.action),
+        note: request.note.clone(),
+        created_at: Utc::now(),
+    };
+    let result = db.record(path.0.as_str(), record).await;
+
+    match result {
+        Ok(resp) => HttpResponse::Ok().json(resp),
+        Err(err) => err.error_response(),
+    }
+}
+
+/// Lists the tweets and comments the content filter held for review
+#[get("/moderation/held")]
+pub async fn list_held(
+    tweet_db: Data<TweetRepo<Tweet>>,
+    user_db: Data<UserRepo<User>>,
+    page: Query<PageQuery>,
+    claims: Option<ReqData<RegisteredClaims>>,
+) -> impl Responder {
+    if let Err(err) = require_role(&user_db, claims, UserRole::Moderator).await {
+        return err.error_response();
+    }
+    let result = tweet_db.held_content(&page).await;
+
+    match result {
+        Ok(resp) => HttpResponse::Ok().json(resp),
+        Err(err) => err.error_response(),
+    }
+}
+
+/// Publishes or removes a held tweet, or one of its comments when `comment_id` is given
+#[post("/moderation/held/{tweet_id}/review")]
+pub async fn review_held(
+    db: Data<ReportRepo<Report>>,
+    publisher: Data<TweetPublisher>,
+    audit: Data<AuditRepo<AuditEvent>>,
+    path: Path<(String,)>,
+    request: Json<HeldReviewRequest>,
+    claims: Option<ReqData<RegisteredClaims>>,
+    http_request: HttpRequest,
+) -> impl Responder {
+    let moderator = match require_role(&publisher.users, claims, UserRole::Moderator).await {
+        Ok(moderator) => moderator,
+        Err(err) => return err.error_response(),
+    };
+    let tweet_db = &publisher.tweets;
+    let search = &publisher.search;
+    let tweet_id = path.0.as_str();
+    let comment_id = request.comment_id.as_deref();
+    let moderator_id = moderator.id.unwrap();
+    let result = tweet_db
+        .review_held(
+            tweet_id,
+            comment_id,
+            request.approve,
+            &moderator_id.to_hex(),
+        )
+        .await;
+    if !request.approve {
+        let (action, target) = match comment_id {
+            Some(comment_id) => (AuditAction::DeleteComment, comment_id),
+            None => (AuditAction::DeleteTweet, tweet_id),
+        };
+        let event = AuditEvent::new(
+            action,
+            Some(moderator_id.to_hex()),
+            client_info(&http_request),
+        );
+        audit.record(event.target(target).outcome(&result)).await;
+    }
+    let resp = match result {
+        Ok(resp) => resp,
+        Err(err) => return err.error_response(),
+    };
+    if comment_id.is_none() {
+        match tweet_db.get_tweet(tweet_id).await {
+            Ok(tweet) => search.index_tweet(&tweet),
+            Err(_) => search.remove_tweet(tweet_id),
+        }
+    }
+    let result = db
+        .record_held_review(tweet_id, comment_id, moderator_id, request.approve)
+        .await;
+
+    match result {
+        Ok(_) => HttpResponse::Ok().json(resp),
+        Err(err) => err.error_response(),
+    }
+}
@@ -0,0 +1,89 @@
+use actix_web::{
+    get,
+    web::{Data, Query, ReqData},
+    HttpResponse, Responder, ResponseError,
+};
+use jwt::RegisteredClaims;
+
+use crate::{
+    auths::utils::get_user_id,
+    dtos::dto::TweetDto,
+    model::{
+        auth_model::User,
+        bookmark_model::Bookmark,
+        search_model::{SearchRequest, SearchSort},
+        tweet_model::Tweet,
+    },
+    repo::{bookmark_repo::BookmarkRepo, tweet_repo::TweetRepo, user_repo::UserRepo},
+    services::search::SearchService,
+    text::search_query::SearchQuery,
+};
+
+#[get("/search")]
+pub async fn search(
+    db: Data<TweetRepo<Tweet>>,
+    user_db: Data<UserRepo<User>>,
+    bookmarks: Data<BookmarkRepo<Bookmark>>,
+    search: Data<SearchService>,
+    request: Query<SearchRequest>,
+    claims: Option<ReqData<RegisteredClaims>>,
+) -> impl Responder {
+    let user_id = match get_user_id(claims) {
+        Ok(id) => id,
+        Err(err) => return HttpResponse::Unauthorized().body(err.to_string()),
+    };
+    let audience = match db.audience(&user_id).await {
+        Ok(audience) => audience,
+        Err(err) => return err.error_response(),
+    };
+    let query = SearchQuery::parse(&request.q);
+    let author_id = match &query.from {
+        Some(handle) => match user_db.find_by_username(handle).await {
+            Ok(Some(user)) => user.id,
+            Ok(None) => return HttpResponse::Ok().json(Vec::<TweetDto>::new()),
+            Err(err) => return err.error_response(),
+        },
+        None => None,
+    };
+    let sort = request.sort.unwrap_or(SearchSort::Relevance);
+    let mut tweets = match search
+        .search(
+            &db,
+            &query,
+            author_id,
+            &audience,
+            sort,
+            &request.page_query(),
+        )
+        .await
+    {
+        Ok(tweets) => tweets,
+        Err(err) => return err.error_response(),
+    };
+    if let Err(err) = db.reveal_polls(&user_id, &mut tweets).await {
+        return err.error_response();
+    }
+    let result = bookmarks.mark_bookmarked(&user_id, &mut tweets).await;
+
+    match result {
+        Ok(_) => HttpResponse::Ok().json(tweets),
+        Err(err) => err.error_response(),
+    }
+}
+
+#[get("/search/users")]
+pub async fn search_users(
+    user_db: Data<UserRepo<User>>,
+    request: Query<SearchRequest>,
+) -> impl Responder {
+    let prefix = request.q.trim().trim_start_matches('@');
+    if prefix.is_empty() {
+        return HttpResponse::BadRequest().body("Search query not provided");
+    }
+    let result = user_db.search_users(prefix, &request.page_query()).await;
+
+    match result {
+        Ok(resp) => HttpResponse::Ok().json(resp),
+        Err(err) => err.error_response(),
+    }
+}
@@ -0,0 +1,53 @@
+use actix_web::{
+    delete, get,
+    web::{Data, Path, Query, ReqData},
+    HttpResponse, Responder, ResponseError,
+};
+use jwt::RegisteredClaims;
+
+use crate::{
+    auths::utils::require_role,
+    model::{
+        auth_model::{User, UserRole},
+        spam_model::SpamEventQuery,
+    },
+    repo::user_repo::UserRepo,
+    services::spam::SpamGuard,
+};
+
+/// Lists the spam decisions taken on posts, filtered by decision or user
+#[get("/moderation/spam")]
+pub async fn list_spam_events(
+    spam: Data<SpamGuard>,
+    user_db: Data<UserRepo<User>>,
+    query: Query<SpamEventQuery>,
+    claims: Option<ReqData<RegisteredClaims>>,
+) -> impl Responder {
+    if let Err(err) = require_role(&user_db, claims, UserRole::Moderator).await {
+        return err.error_response();
+    }
+    let result = spam.events.events(&query).await;
+
+    match result {
+        Ok(resp) => HttpResponse::Ok().json(resp),
+        Err(err) => err.error_response(),
+    }
+}
+
+/// Lifts the shadow limit of an account wrongly taken for a spammer
+#[delete("/moderation/users/{user_id}/limit")]
+pub async fn lift_limit(
+    user_db: Data<UserRepo<User>>,
+    path: Path<(String,)>,
+    claims: Option<ReqData<RegisteredClaims>>,
+) -> impl Responder {
+    if let Err(err) = require_role(&user_db, claims, UserRole::Moderator).await {
+        return err.error_response();
+    }
+    let result = user_db.set_limited(&path.0, false).await;
+
+    match result {
+        Ok(_) => HttpResponse::Ok().json("Shadow limit lifted successfully"),
+        Err(err) => err.error_response(),
+    }
+}
@@ -0,0 +1,211 @@
+use std::collections::VecDeque;
+
+use actix_web::{
+    get,
+    web::{Bytes, Data, Payload, Query, ReqData},
+    HttpRequest, HttpResponse, Responder, ResponseError,
+};
+use actix_ws::Message;
+use futures::StreamExt;
+use jwt::RegisteredClaims;
+use serde::Deserialize;
+use tokio::sync::broadcast::{error::RecvError, Receiver};
+
+use crate::{
+    auths::utils::get_user_id,
+    errors::error::TweetError,
+    model::tweet_model::Tweet,
+    repo::tweet_repo::TweetRepo,
+    services::event_bus::{Event, EventBus, EventPayload, Subscriber, WatchRequest},
+};
+
+#[derive(Debug, Deserialize)]
+pub struct StreamQuery {
+    /// Comma separated ids of the tweets the client is viewing
+    pub tweets: Option<String>,
+    pub last_event_id: Option<u64>,
+}
+
+impl StreamQuery {
+    fn watched(&self) -> Vec<String> {
+        self.tweets
+            .as_deref()
+            .unwrap_or_default()
+            .split(',')
+            .filter(|id| !id.is_empty())
+            .map(|id| id.to_string())
+            .collect()
+    }
+
+    /// Reads the id to resume from, preferring the standard `Last-Event-ID` header
+    fn last_event_id(&self, req: &HttpRequest) -> Option<u64> {
+        req.headers()
+            .get("Last-Event-ID")
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.parse::<u64>().ok())
+            .or(self.last_event_id)
+    }
+}
+
+/// Events waiting to be sent to one client
+struct EventQueue {
+    replay: VecDeque<Event>,
+    receiver: Receiver<Event>,
+    subscriber: Subscriber,
+    last_id: u64,
+}
+
+impl EventQueue {
+    /// Waits for the next event visible to the subscriber, `None` once the bus is closed
+    async fn next(&mut self) -> Option<Event> {
+        loop {
+            let event = match self.replay.pop_front() {
+                Some(event) => event,
+                None => match self.receiver.recv().await {
+                    Ok(event) => event,
+                    Err(RecvError::Lagged(_)) => Event {
+                        id: self.last_id,
+                        payload: EventPayload::Reset,
+                    },
+                    Err(RecvError::Closed) => return None,
+                },
+            };
+            self.last_id = self.last_id.max(event.id);
+            if event.is_visible_to(&self.subscriber) {
+                return Some(event);
+            }
+        }
+    }
+}
+
+fn subscribe(bus: &EventBus, subscriber: Subscriber, last_event_id: Option<u64>) -> EventQueue {
+    let subscription = bus.subscribe(last_event_id);
+    EventQueue {
+        replay: subscription.replay.into(),
+        receiver: subscription.receiver,
+        subscriber,
+        last_id: last_event_id.unwrap_or(0),
+    }
+}
+
+/// Creates the subscriber for a user, receiving only the tweets the user is in the audience of
+/// and stats only for the watched tweets the user may see
+async fn new_subscriber(
+    db: &TweetRepo<Tweet>,
+    user_id: String,
+    watched: Vec<String>,
+) -> Result<Subscriber, TweetError> {
+    let audience = db.audience(&user_id).await?;
+    let watched = db.visible_tweet_ids(&watched, &audience).await?;
+    let mut subscriber = Subscriber::new(user_id, watched);
+    subscriber.audience = audience;
+    Ok(subscriber)
+}
+
+/// Drops the tweets the subscriber may not see from a watch request
+async fn visible_watch_request(
+    db: &TweetRepo<Tweet>,
+    subscriber: &Subscriber,
+    mut request: WatchRequest,
+) -> Result<WatchRequest, TweetError> {
+    request.watch = db
+        .visible_tweet_ids(&request.watch, &subscriber.audience)
+        .await?;
+    Ok(request)
+}
+
+#[get("/stream/events")]
+pub async fn stream_events(
+    bus: Data<EventBus>,
+    db: Data<TweetRepo<Tweet>>,
+    query: Query<StreamQuery>,
+    req: HttpRequest,
+    claims: Option<ReqData<RegisteredClaims>>,
+) -> impl Responder {
+    let user_id = match get_user_id(claims) {
+        Ok(id) => id,
+        Err(err) => return HttpResponse::Unauthorized().body(err.to_string()),
+    };
+    let subscriber = match new_subscriber(&db, user_id, query.watched()).await {
+        Ok(subscriber) => subscriber,
+        Err(err) => return err.error_response(),
+    };
+    let queue = subscribe(&bus, subscriber, query.last_event_id(&req));
+
+    let stream = futures::stream::unfold(queue, |mut queue| async move {
+        let event = queue.next().await?;
+        let data = serde_json::to_string(&event).unwrap_or_default();
+        let chunk = format!(
+            "id: {}\nevent: {}\ndata: {}\n\n",
+            event.id,
+            event.name(),
+            data
+        );
+        Some((Ok::<Bytes, actix_web::Error>(Bytes::from(chunk)), queue))
+    });
+
+    HttpResponse::Ok()
+        .content_type("text/event-stream")
+        .insert_header(("Cache-Control", "no-cache"))
+        .streaming(stream)
+}
+
+#[get("/stream/ws")]
+pub async fn stream_ws(
+    bus: Data<EventBus>,
+    db: Data<TweetRepo<Tweet>>,
+    query: Query<StreamQuery>,
+    req: HttpRequest,
+    body: Payload,
+    claims: Option<ReqData<RegisteredClaims>>,
+) -> impl Responder {
+    let user_id = match get_user_id(claims) {
+        Ok(id) => id,
+        Err(err) => return HttpResponse::Unauthorized().body(err.to_string()),
+    };
+    let subscriber = match new_subscriber(&db, user_id, query.watched()).await {
+        Ok(subscriber) => subscriber,
+        Err(err) => return err.error_response(),
+    };
+    let (response, mut session, mut messages) = match actix_ws::handle(&req, body) {
+        Ok(handshake) => handshake,
+        Err(err) => return err.error_response(),
+    };
+    let mut queue = subscribe(&bus, subscriber, query.last_event_id(&req));
+
+    actix_rt::spawn(async move {
+        loop {
+            tokio::select! {
+                message = messages.next() => match message {
+                    Some(Ok(Message::Text(text))) => {
+                        if let Ok(request) = serde_json::from_str::<WatchRequest>(&text) {
+                            match visible_watch_request(&db, &queue.subscriber, request).await {
+                                Ok(request) => queue.subscriber.apply(request),
+                                Err(err) => log::error!("Failed to check watched tweets: {}", err),
+                            }
+                        }
+                    }
+                    Some(Ok(Message::Ping(bytes))) => {
+                        if session.pong(&bytes).await.is_err() {
+                            return;
+                        }
+                    }
+                    Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                    Some(Ok(_)) => {}
+                },
+                event = queue.next() => match event {
+                    Some(event) => {
+                        let data = serde_json::to_string(&event).unwrap_or_default();
+                        if session.text(data).await.is_err() {
+                            return;
+                        }
+                    }
+                    None => break,
+                },
+            }
+        }
+        let _ = session.close(None).await;
+    });
+
+    response
+}
@@ -1,113 +1,446 @@
 use actix_web::{
-    delete, get, post,
-    web::{Data, Json, Path, ReqData},
-    HttpResponse, Responder,
+    delete, get, patch, post,
+    web::{Data, Json, Path, Query, ReqData},
+    HttpRequest, HttpResponse, Responder, ResponseError,
 };
 use jwt::RegisteredClaims;
 
 use crate::{
-    auths::utils::get_user_id,
+    auths::utils::{client_info, get_user_id},
+    dtos::dto::TweetDto,
+    errors::error::{FieldError, TweetError},
     model::{
+        audit_model::{AuditAction, AuditEvent},
+        bookmark_model::Bookmark,
+        pagination::PageQuery,
+        report_model::ReportTarget,
         tweet_comment::{CommentAction, CommentRequest},
-        tweet_model::{Tweet, TweetActions, TweetRequest},
+        tweet_model::{Tweet, TweetRequest},
+        tweet_poll::VoteRequest,
     },
-    repo::tweet_repo::TweetRepo,
+    repo::{audit_repo::AuditRepo, bookmark_repo::BookmarkRepo, tweet_repo::TweetRepo},
+    services::{
+        conditional::{cached_json, has_if_match, if_match},
+        content_filter::FilterAction,
+        idempotency::IdempotencyService,
+        publisher::TweetPublisher,
+        search::SearchService,
+    },
+    text::entities::extract_entities,
 };
 
 #[post("/tweets")]
 pub async fn create_tweet(
     request: Json<TweetRequest>,
     claims: Option<ReqData<RegisteredClaims>>,
+    publisher: Data<TweetPublisher>,
+    idempotency: Data<IdempotencyService>,
+    http_request: HttpRequest,
+) -> impl Responder {
+    let user_id = match get_user_id(claims) {
+        Ok(id) => id,
+        Err(err) => return HttpResponse::Unauthorized().body(err.to_string()),
+    };
+    idempotency
+        .run(&http_request, &user_id, &*request, || async {
+            let result = publisher.publish(&request, &user_id, None).await;
+
+            match result {
+                Ok(resp) if resp.withheld => HttpResponse::Accepted().json(resp),
+                Ok(resp) => HttpResponse::Created().json(resp),
+                Err(err) => err.error_response(),
+            }
+        })
+        .await
+}
+
+#[get("/tweets")]
+pub async fn list_tweets(
     db: Data<TweetRepo<Tweet>>,
+    bookmarks: Data<BookmarkRepo<Bookmark>>,
+    claims: Option<ReqData<RegisteredClaims>>,
+    request: HttpRequest,
 ) -> impl Responder {
     let user_id = match get_user_id(claims) {
         Ok(id) => id,
         Err(err) => return HttpResponse::Unauthorized().body(err.to_string()),
     };
-    let tweet = request.0.tweet(user_id).unwrap();
-    let result = db.create_tweet(tweet).await;
+    let mut tweets = match db.all_tweets(&user_id).await {
+        Ok(tweets) => tweets,
+        Err(err) => return err.error_response(),
+    };
+    if let Err(err) = db.reveal_polls(&user_id, &mut tweets).await {
+        return err.error_response();
+    }
+    let result = bookmarks.mark_bookmarked(&user_id, &mut tweets).await;
 
     match result {
-        Ok(resp) => HttpResponse::Created().json(resp),
-        Err(err) => HttpResponse::InternalServerError().body(err.to_string()),
+        Ok(_) => cached_json(&request, &tweets),
+        Err(err) => err.error_response(),
     }
 }
 
-#[get("/tweets")]
-pub async fn list_tweets(
+#[get("/users/{user_id}/tweets")]
+pub async fn user_tweets(
     db: Data<TweetRepo<Tweet>>,
+    bookmarks: Data<BookmarkRepo<Bookmark>>,
+    path: Path<(String,)>,
+    page: Query<PageQuery>,
     claims: Option<ReqData<RegisteredClaims>>,
+    request: HttpRequest,
 ) -> impl Responder {
     let user_id = match get_user_id(claims) {
         Ok(id) => id,
         Err(err) => return HttpResponse::Unauthorized().body(err.to_string()),
     };
-    let result = db.all_tweets(&user_id).await;
+    let mut tweets = match db.profile_tweets(path.0.as_str(), &user_id, &page).await {
+        Ok(tweets) => tweets,
+        Err(err) => return err.error_response(),
+    };
+    if let Err(err) = db.reveal_polls(&user_id, &mut tweets).await {
+        return err.error_response();
+    }
+    let result = bookmarks.mark_bookmarked(&user_id, &mut tweets).await;
 
     match result {
-        Ok(resp) => HttpResponse::Ok().json(resp),
-        Err(err) => HttpResponse::InternalServerError().body(err.to_string()),
+        Ok(_) => cached_json(&request, &tweets),
+        Err(err) => err.error_response(),
     }
 }
 
 #[get("/tweets/{path}")]
-pub async fn get_tweet(db: Data<TweetRepo<Tweet>>, path: Path<(String,)>) -> impl Responder {
+pub async fn get_tweet(
+    db: Data<TweetRepo<Tweet>>,
+    bookmarks: Data<BookmarkRepo<Bookmark>>,
+    path: Path<(String,)>,
+    claims: Option<ReqData<RegisteredClaims>>,
+    request: HttpRequest,
+) -> impl Responder {
+    let user_id = match get_user_id(claims) {
+        Ok(id) => id,
+        Err(err) => return HttpResponse::Unauthorized().body(err.to_string()),
+    };
     let id = path.0.as_str();
     if id.is_empty() {
         return HttpResponse::BadRequest().body(format!("Id not provided"));
     }
-    let result = db.get_tweet(id).await;
+    let result = tweet_view(&db, &bookmarks, id, &user_id).await;
+
+    match result {
+        Ok(tweet) => cached_json(&request, &tweet),
+        Err(err) => err.error_response(),
+    }
+}
+
+#[patch("/tweets/{path}")]
+pub async fn edit_tweet(
+    publisher: Data<TweetPublisher>,
+    bookmarks: Data<BookmarkRepo<Bookmark>>,
+    path: Path<(String,)>,
+    request: Json<TweetRequest>,
+    claims: Option<ReqData<RegisteredClaims>>,
+    http_request: HttpRequest,
+) -> impl Responder {
+    let user_id = match get_user_id(claims) {
+        Ok(id) => id,
+        Err(err) => return HttpResponse::Unauthorized().body(err.to_string()),
+    };
+    let id = path.0.as_str();
+    let db = &publisher.tweets;
+    let version = match expected_version(db, &bookmarks, &http_request, id, &user_id).await {
+        Ok(version) => version,
+        Err(err) => return err.error_response(),
+    };
+    let message = match request.validated_message() {
+        Ok(message) => message,
+        Err(err) => return err.error_response(),
+    };
+    let decision = match publisher.filter.check("message", &message) {
+        Ok(decision) => decision,
+        Err(err) => return err.error_response(),
+    };
+    let mut entities = extract_entities(&message);
+    if let Err(err) = publisher.users.resolve_mentions(&mut entities).await {
+        return err.error_response();
+    }
+    let mut result = db
+        .edit_tweet(id, &user_id, &message, entities, version)
+        .await;
+    if result.is_ok() && decision.action == FilterAction::Hold {
+        let reason = decision.reason.unwrap_or_else(|| "Held for review".into());
+        result = db.hold_tweet(id, &reason).await;
+    }
+
+    match result {
+        Ok(resp) => {
+            publisher.search.index_tweet(&resp);
+            if resp.withheld {
+                HttpResponse::Accepted().json(resp)
+            } else {
+                HttpResponse::Ok().json(resp)
+            }
+        }
+        Err(err) => err.error_response(),
+    }
+}
+
+#[get("/tweets/{path}/history")]
+pub async fn tweet_history(
+    db: Data<TweetRepo<Tweet>>,
+    path: Path<(String,)>,
+    claims: Option<ReqData<RegisteredClaims>>,
+) -> impl Responder {
+    let user_id = match get_user_id(claims) {
+        Ok(id) => id,
+        Err(err) => return HttpResponse::Unauthorized().body(err.to_string()),
+    };
+    let id = path.0.as_str();
+    let result = db.tweet_history(id, &user_id).await;
 
     match result {
         Ok(resp) => HttpResponse::Ok().json(resp),
-        Err(err) => HttpResponse::InternalServerError().body(err.to_string()),
+        Err(err) => err.error_response(),
     }
 }
 
 #[delete("/tweets/{path}")]
-pub async fn delete_tweet(db: Data<TweetRepo<Tweet>>, path: Path<(String,)>) -> impl Responder {
+pub async fn delete_tweet(
+    db: Data<TweetRepo<Tweet>>,
+    bookmarks: Data<BookmarkRepo<Bookmark>>,
+    search: Data<SearchService>,
+    audit: Data<AuditRepo<AuditEvent>>,
+    path: Path<(String,)>,
+    claims: Option<ReqData<RegisteredClaims>>,
+    request: HttpRequest,
+) -> impl Responder {
+    let user_id = match get_user_id(claims) {
+        Ok(id) => id,
+        Err(err) => return HttpResponse::Unauthorized().body(err.to_string()),
+    };
     let id = path.0.as_str();
     if id.is_empty() {
         return HttpResponse::BadRequest().body(format!("Id not provided").to_string());
     }
-    let result = db.delete_tweet(id).await;
+    let version = match expected_version(&db, &bookmarks, &request, id, &user_id).await {
+        Ok(version) => version,
+        Err(err) => return err.error_response(),
+    };
+    let result = db.delete_own_tweet(id, &user_id, version).await;
+    let event = AuditEvent::new(
+        AuditAction::DeleteTweet,
+        Some(user_id),
+        client_info(&request),
+    );
+    audit.record(event.target(id).outcome(&result)).await;
+
+    match result {
+        Ok(resp) => {
+            search.remove_tweet(id);
+            HttpResponse::Ok().json(resp)
+        }
+        Err(err) => err.error_response(),
+    }
+}
+
+/// Restores a tweet the author deleted, within the restore window
+#[post("/tweets/{path}/restore")]
+pub async fn restore_tweet(
+    db: Data<TweetRepo<Tweet>>,
+    search: Data<SearchService>,
+    path: Path<(String,)>,
+    claims: Option<ReqData<RegisteredClaims>>,
+) -> impl Responder {
+    let user_id = match get_user_id(claims) {
+        Ok(id) => id,
+        Err(err) => return HttpResponse::Unauthorized().body(err.to_string()),
+    };
+    let result = db.restore_tweet(path.0.as_str(), &user_id).await;
+
+    match result {
+        Ok(resp) => {
+            search.index_tweet(&resp);
+            HttpResponse::Ok().json(resp)
+        }
+        Err(err) => err.error_response(),
+    }
+}
+
+#[post("/tweets/{path}/pin")]
+pub async fn pin_tweet(
+    db: Data<TweetRepo<Tweet>>,
+    path: Path<(String,)>,
+    claims: Option<ReqData<RegisteredClaims>>,
+) -> impl Responder {
+    let user_id = match get_user_id(claims) {
+        Ok(id) => id,
+        Err(err) => return HttpResponse::Unauthorized().body(err.to_string()),
+    };
+    let result = db.pin_tweet(path.0.as_str(), &user_id).await;
 
     match result {
         Ok(resp) => HttpResponse::Ok().json(resp),
-        Err(err) => HttpResponse::InternalServerError().body(err.to_string()),
+        Err(err) => err.error_response(),
+    }
+}
+
+#[delete("/tweets/{path}/pin")]
+pub async fn unpin_tweet(
+    db: Data<TweetRepo<Tweet>>,
+    path: Path<(String,)>,
+    claims: Option<ReqData<RegisteredClaims>>,
+) -> impl Responder {
+    let user_id = match get_user_id(claims) {
+        Ok(id) => id,
+        Err(err) => return HttpResponse::Unauthorized().body(err.to_string()),
+    };
+    let result = db.unpin_tweet(path.0.as_str(), &user_id).await;
+
+    match result {
+        Ok(resp) => HttpResponse::Ok().json(resp),
+        Err(err) => err.error_response(),
     }
 }
 
 #[post("/tweets/{path}/comment")]
 pub async fn add_comment(
     db: Data<TweetRepo<Tweet>>,
+    publisher: Data<TweetPublisher>,
+    idempotency: Data<IdempotencyService>,
     path: Path<(String,)>,
     request: Json<CommentRequest>,
+    claims: Option<ReqData<RegisteredClaims>>,
+    http_request: HttpRequest,
 ) -> impl Responder {
+    let user_id = match get_user_id(claims) {
+        Ok(id) => id,
+        Err(err) => return HttpResponse::Unauthorized().body(err.to_string()),
+    };
     let tweet_id = path.0.as_str();
-    let comment = request.into_inner().comment(tweet_id).unwrap();
-    let result = db.add_comment(tweet_id, &comment.message).await;
+    idempotency
+        .run(&http_request, &user_id, &*request, || async {
+            let mut comment = match request.comment(tweet_id, &user_id) {
+                Ok(comment) => comment,
+                Err(err) => return err.error_response(),
+            };
+            match publisher.filter.check("message", &comment.message) {
+                Ok(decision) if decision.action == FilterAction::Hold => {
+                    comment.held_reason =
+                        Some(decision.reason.unwrap_or_else(|| "Held for review".into()));
+                }
+                Ok(_) => {}
+                Err(err) => return err.error_response(),
+            }
+            if let Err(err) = publisher
+                .spam
+                .check(&user_id, ReportTarget::Comment, &comment.message)
+                .await
+            {
+                return err.error_response();
+            }
+            let held = comment.held_reason.is_some();
+            let result = db.add_comment(tweet_id, comment).await;
 
-    match result {
-        Ok(resp) => HttpResponse::Ok().json(resp),
-        Err(err) => HttpResponse::InternalServerError().body(err.to_string()),
-    }
+            match result {
+                Ok(resp) if held => HttpResponse::Accepted().json(resp),
+                Ok(resp) => HttpResponse::Ok().json(resp),
+                Err(err) => err.error_response(),
+            }
+        })
+        .await
 }
 
 #[delete("/tweets/{tweet_id}/comment/{comment_id}")]
 pub async fn delete_comment(
     db: Data<TweetRepo<Tweet>>,
+    audit: Data<AuditRepo<AuditEvent>>,
     path: Path<(String, String)>,
+    claims: Option<ReqData<RegisteredClaims>>,
+    request: HttpRequest,
 ) -> impl Responder {
+    let user_id = match get_user_id(claims) {
+        Ok(id) => id,
+        Err(err) => return HttpResponse::Unauthorized().body(err.to_string()),
+    };
     let tweet_id = path.0.as_str();
     let comment_id = path.1.as_str();
     if tweet_id.is_empty() || comment_id.is_empty() {
         return HttpResponse::BadRequest().body(format!("Id not provided").to_string());
     }
-    let result = db.remove_comment(tweet_id, comment_id).await;
+    let result = db.remove_own_comment(tweet_id, comment_id, &user_id).await;
+    let event = AuditEvent::new(
+        AuditAction::DeleteComment,
+        Some(user_id),
+        client_info(&request),
+    );
+    audit
+        .record(event.target(comment_id).outcome(&result))
+        .await;
 
     match result {
         Ok(resp) => HttpResponse::Ok().json(resp),
-        Err(err) => HttpResponse::InternalServerError().body(err.to_string()),
+        Err(err) => err.error_response(),
+    }
+}
+
+#[post("/tweets/{path}/poll/vote")]
+pub async fn vote_poll(
+    db: Data<TweetRepo<Tweet>>,
+    path: Path<(String,)>,
+    request: Json<VoteRequest>,
+    claims: Option<ReqData<RegisteredClaims>>,
+) -> impl Responder {
+    let user_id = match get_user_id(claims) {
+        Ok(id) => id,
+        Err(err) => return HttpResponse::Unauthorized().body(err.to_string()),
+    };
+    let option = match request.option {
+        Some(option) => option,
+        None => {
+            return TweetError::UnprocessableEntity(vec![FieldError::new(
+                "option",
+                "required",
+                "This field is required",
+            )])
+            .error_response()
+        }
+    };
+    let result = db.vote_poll(path.0.as_str(), &user_id, option).await;
+
+    match result {
+        Ok(resp) => HttpResponse::Ok().json(resp),
+        Err(err) => err.error_response(),
+    }
+}
+
+/// Gets the tweet as the viewer sees it, the body its ETag is computed from
+async fn tweet_view(
+    db: &TweetRepo<Tweet>,
+    bookmarks: &BookmarkRepo<Bookmark>,
+    id: &str,
+    user_id: &str,
+) -> Result<TweetDto, TweetError> {
+    let mut tweet = db.view_tweet(id, user_id).await?;
+    db.reveal_polls(user_id, std::slice::from_mut(&mut tweet))
+        .await?;
+    bookmarks
+        .mark_bookmarked(user_id, std::slice::from_mut(&mut tweet))
+        .await?;
+    Ok(tweet)
+}
+
+/// Checks the `If-Match` precondition, returning the version the tweet must still be at
+/// when the write is applied
+async fn expected_version(
+    db: &TweetRepo<Tweet>,
+    bookmarks: &BookmarkRepo<Bookmark>,
+    request: &HttpRequest,
+    id: &str,
+    user_id: &str,
+) -> Result<Option<u64>, TweetError> {
+    if !has_if_match(request) {
+        return Ok(None);
     }
+    let tweet = tweet_view(db, bookmarks, id, user_id).await?;
+    if_match(request, &tweet)?;
+    Ok(Some(tweet.version))
 }
@@ -1,21 +1,31 @@
 use actix_web::{
-    post,
+    delete, get, post,
     web::{Data, Json, ReqData},
-    HttpMessage, HttpRequest, HttpResponse, Responder,
+    HttpMessage, HttpRequest, HttpResponse, Responder, ResponseError,
 };
 use jwt::RegisteredClaims;
 
-use crate::auths::utils::get_user_id;
+use crate::auths::utils::{client_info, get_user_id};
 use crate::{
-    auths::auth::{AuthData, ChangePasswordRequest, CreateUser},
-    model::auth_model::User,
-    repo::user_repo::UserRepo,
+    auths::auth::{AuthData, ChangePasswordRequest, CreateUser, DeleteAccountRequest},
+    dtos::dto::AccountDeletionDto,
+    model::{
+        audit_model::{AuditAction, AuditEvent},
+        auth_model::User,
+    },
+    repo::{audit_repo::AuditRepo, user_repo::UserRepo},
+    services::account::AccountService,
+    validation::account::validate_username,
 };
 
 #[post("/api/v1/user/register")]
 pub async fn register(db: Data<UserRepo<User>>, new_user: Json<CreateUser>) -> impl Responder {
     let data: CreateUser = new_user.into_inner();
-    let user = User::new(&data.email, &data.password);
+    let username = match validate_username(data.username.as_deref()) {
+        Ok(username) => username,
+        Err(err) => return err.error_response(),
+    };
+    let user = User::new(&data.email, &data.password, username);
     let result = db.register(user).await;
     match result {
         Ok(resp) => HttpResponse::Ok().json(resp),
@@ -24,9 +34,16 @@ pub async fn register(db: Data<UserRepo<User>>, new_user: Json<CreateUser>) -> i
 }
 
 #[post("/api/v1/user/login")]
-pub async fn login(db: Data<UserRepo<User>>, auth: Json<AuthData>) -> impl Responder {
+pub async fn login(
+    db: Data<UserRepo<User>>,
+    audit: Data<AuditRepo<AuditEvent>>,
+    auth: Json<AuthData>,
+    request: HttpRequest,
+) -> impl Responder {
     let user: AuthData = auth.into_inner();
     let result = db.valid_user(&user).await;
+    let event = AuditEvent::new(AuditAction::Login, Some(user.email), client_info(&request));
+    audit.record(event.outcome(&result)).await;
     match result {
         Ok(resp) => HttpResponse::Ok().json(resp),
         Err(err) => HttpResponse::Unauthorized().body(err.to_string()),
@@ -36,16 +53,23 @@ pub async fn login(db: Data<UserRepo<User>>, auth: Json<AuthData>) -> impl Respo
 #[post("/user/change-password")]
 pub async fn change_password(
     db: Data<UserRepo<User>>,
+    audit: Data<AuditRepo<AuditEvent>>,
     req: Json<ChangePasswordRequest>,
     claims: Option<ReqData<RegisteredClaims>>,
     request: HttpRequest,
 ) -> impl Responder {
-    match get_user_id(claims) {
+    let user_id = match get_user_id(claims) {
         Ok(id) => id,
         Err(err) => return HttpResponse::Unauthorized().body(err.to_string()),
     };
     let password_request: ChangePasswordRequest = req.into_inner();
     let result = db.change_password(password_request).await;
+    let event = AuditEvent::new(
+        AuditAction::PasswordChange,
+        Some(user_id),
+        client_info(&request),
+    );
+    audit.record(event.outcome(&result)).await;
     match result {
         Ok(resp) => {
             let mut mut_request = request.extensions_mut();
@@ -57,9 +81,77 @@ pub async fn change_password(
 }
 
 #[post("/user/logout")]
-pub async fn signout(req: HttpRequest) -> impl Responder {
+pub async fn signout(
+    audit: Data<AuditRepo<AuditEvent>>,
+    claims: Option<ReqData<RegisteredClaims>>,
+    req: HttpRequest,
+) -> impl Responder {
+    let user_id = get_user_id(claims).ok();
+    audit
+        .record(AuditEvent::new(
+            AuditAction::Logout,
+            user_id,
+            client_info(&req),
+        ))
+        .await;
     let mut request = req.extensions_mut();
     request.clear();
     let message = Box::new("Logged out successfully");
     HttpResponse::Ok().json(message)
 }
+
+/// Downloads everything the service keeps about the user as a JSON file
+#[get("/user/export")]
+pub async fn export_account(
+    account: Data<AccountService>,
+    claims: Option<ReqData<RegisteredClaims>>,
+) -> impl Responder {
+    let user_id = match get_user_id(claims) {
+        Ok(id) => id,
+        Err(err) => return HttpResponse::Unauthorized().body(err.to_string()),
+    };
+    let result = account.export(&user_id).await;
+
+    match result {
+        Ok(export) => HttpResponse::Ok()
+            .insert_header((
+                "Content-Disposition",
+                "attachment; filename=\"account-export.json\"",
+            ))
+            .json(export),
+        Err(err) => err.error_response(),
+    }
+}
+
+/// Schedules the deletion of the account and signs it out everywhere.
+/// Signing in again before the grace period is over keeps the account.
+#[delete("/user")]
+pub async fn delete_account(
+    db: Data<UserRepo<User>>,
+    audit: Data<AuditRepo<AuditEvent>>,
+    req: Json<DeleteAccountRequest>,
+    claims: Option<ReqData<RegisteredClaims>>,
+    request: HttpRequest,
+) -> impl Responder {
+    let user_id = match get_user_id(claims) {
+        Ok(id) => id,
+        Err(err) => return HttpResponse::Unauthorized().body(err.to_string()),
+    };
+    let result = db.request_deletion(&user_id, &req.password).await;
+    let event = AuditEvent::new(
+        AuditAction::DeleteAccount,
+        Some(user_id),
+        client_info(&request),
+    );
+    audit.record(event.outcome(&result)).await;
+    match result {
+        Ok(erase_at) => {
+            request.extensions_mut().clear();
+            HttpResponse::Ok().json(AccountDeletionDto {
+                message: "Account scheduled for deletion, sign in before then to keep it".into(),
+                erase_at,
+            })
+        }
+        Err(err) => err.error_response(),
+    }
+}
@@ -10,6 +10,8 @@ pub struct AuthData {
 pub struct CreateUser {
     pub email: String,
     pub password: String,
+    #[serde(default)]
+    pub username: Option<String>,
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
@@ -18,3 +20,8 @@ pub struct ChangePasswordRequest {
     pub password: String,
     pub new_password: String,
 }
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct DeleteAccountRequest {
+    pub password: String,
+}
@@ -1,13 +1,17 @@
 use super::utils::get_jwt_key;
 use crate::errors::error::TweetError;
+use crate::{
+    model::auth_model::{User, TOKEN_GENERATION_CLAIM},
+    repo::user_repo::UserRepo,
+};
 use actix_web::HttpMessage;
-use actix_web::{dev::ServiceRequest, Error};
+use actix_web::{dev::ServiceRequest, web::Data, Error};
 use actix_web_httpauth::extractors::{
     bearer::{self, BearerAuth},
     AuthenticationError,
 };
 use chrono::Utc;
-use jwt::{RegisteredClaims, VerifyWithKey};
+use jwt::{Claims, RegisteredClaims, VerifyWithKey};
 
 /// Authentication validator using BearerAuth and ServiceRequest.
 pub async fn validator(
@@ -16,11 +20,14 @@ pub async fn validator(
 ) -> Result<ServiceRequest, (Error, ServiceRequest)> {
     let token_string = credentials.token();
 
-    let claims: Result<RegisteredClaims, TweetError> = token_string
+    let claims: Result<Claims, TweetError> = token_string
         .verify_with_key(&get_jwt_key())
         .map_err(|_| TweetError::Unauthorized("Invalid token".into()));
     match claims {
-        Ok(claim) => {
+        Ok(Claims {
+            registered: claim,
+            private,
+        }) => {
             let expired = is_token_expired(claim.expiration.unwrap_or_default());
             if expired {
                 let config = req
@@ -35,6 +42,29 @@ pub async fn validator(
                     req,
                 ));
             }
+            // Tokens issued before generations were introduced belong to the first one
+            let generation = private
+                .get(TOKEN_GENERATION_CLAIM)
+                .and_then(|generation| generation.as_u64())
+                .unwrap_or_default();
+            let revoked = match is_token_revoked(&req, &claim, generation).await {
+                Ok(revoked) => revoked,
+                // The token may be fine, failing to check it is not an authentication error
+                Err(err) => return Err((err.into(), req)),
+            };
+            if revoked {
+                let config = req
+                    .app_data::<bearer::Config>()
+                    .cloned()
+                    .unwrap_or_default()
+                    .scope("/api/v1");
+                return Err((
+                    AuthenticationError::from(config)
+                        .with_error_description("Token has been revoked")
+                        .into(),
+                    req,
+                ));
+            }
             req.extensions_mut().insert(claim);
             Ok(req)
         }
@@ -52,3 +82,24 @@ pub async fn validator(
 fn is_token_expired(ex: u64) -> bool {
     Some(ex).unwrap_or(0) < Utc::now().timestamp() as u64
 }
+
+/// Checks the token generation against the one of its user, deleted and suspended users have
+/// no valid tokens
+async fn is_token_revoked(
+    req: &ServiceRequest,
+    claim: &RegisteredClaims,
+    generation: u64,
+) -> Result<bool, TweetError> {
+    let (users, user_id) = match (
+        req.app_data::<Data<UserRepo<User>>>(),
+        &claim.json_web_token_id,
+    ) {
+        (Some(users), Some(user_id)) => (users, user_id),
+        _ => return Ok(false),
+    };
+    match users.find_user(user_id).await {
+        Ok(user) => Ok(user.suspended || user.is_token_revoked(generation)),
+        Err(TweetError::NotFound(_)) => Ok(true),
+        Err(_) => Err(TweetError::InternalServerError),
+    }
+}
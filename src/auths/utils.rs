@@ -1,9 +1,17 @@
-use actix_web::web::ReqData;
+use actix_web::{http::header, web::ReqData, HttpRequest};
 use hmac::{Hmac, Mac};
 use jwt::RegisteredClaims;
 use sha2::Sha256;
 
-use crate::errors::error::TweetError;
+use crate::{
+    config::settings::trusted_proxies,
+    errors::error::TweetError,
+    model::{
+        audit_model::ClientInfo,
+        auth_model::{User, UserRole},
+    },
+    repo::user_repo::UserRepo,
+};
 
 type HmacSha256 = Hmac<Sha256>;
 
@@ -34,3 +42,42 @@ pub fn get_user_id(claims: Option<ReqData<RegisteredClaims>>) -> Result<String,
     };
     Ok(user_id)
 }
+
+/// Gets the authenticated user, failing unless they have at least the given role
+pub async fn require_role(
+    users: &UserRepo<User>,
+    claims: Option<ReqData<RegisteredClaims>>,
+    role: UserRole,
+) -> Result<User, TweetError> {
+    let user = users.find_user(&get_user_id(claims)?).await?;
+    if user.role < role {
+        return Err(TweetError::Forbidden(
+            "You are not allowed to perform this action".into(),
+        ));
+    }
+    Ok(user)
+}
+
+/// Gets the client address and user agent of a request for the audit log
+pub fn client_info(request: &HttpRequest) -> ClientInfo {
+    ClientInfo {
+        ip: client_ip(request),
+        user_agent: request
+            .headers()
+            .get(header::USER_AGENT)
+            .and_then(|agent| agent.to_str().ok())
+            .map(String::from),
+    }
+}
+
+/// Address of the connected peer, forwarding headers are only read when the peer is one of
+/// the trusted proxies since any client can send them
+fn client_ip(request: &HttpRequest) -> Option<String> {
+    let peer = request.peer_addr()?.ip();
+    if trusted_proxies().contains(&peer) {
+        if let Some(ip) = request.connection_info().realip_remote_addr() {
+            return Some(ip.to_string());
+        }
+    }
+    Some(peer.to_string())
+}
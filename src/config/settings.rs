@@ -0,0 +1,235 @@
+use std::{env, net::IpAddr, str::FromStr};
+
+use chrono::Duration;
+
+/// Reads an environment variable, falling back to `default` when it is missing or invalid
+fn env_or<T: FromStr>(key: &str, default: T) -> T {
+    env::var(key)
+        .ok()
+        .and_then(|value| value.parse::<T>().ok())
+        .unwrap_or(default)
+}
+
+/// Window after creation during which the author may edit a tweet
+pub fn tweet_edit_window() -> Duration {
+    Duration::minutes(env_or("TWEET_EDIT_WINDOW_MINUTES", 30))
+}
+
+/// Window after deletion during which the author may restore a tweet
+pub fn tweet_restore_window() -> Duration {
+    Duration::days(env_or("TWEET_RESTORE_WINDOW_DAYS", 7))
+}
+
+/// Time deleted tweets are kept before being purged
+pub fn tweet_deleted_retention() -> Duration {
+    Duration::days(env_or("TWEET_DELETED_RETENTION_DAYS", 30))
+}
+
+/// Seconds between two runs of the job purging deleted tweets
+pub fn tweet_purge_interval() -> u64 {
+    env_or("TWEET_PURGE_SECONDS", 3600)
+}
+
+/// Maximum weighted length of a tweet message
+pub fn tweet_max_length() -> usize {
+    env_or("TWEET_MAX_LENGTH", 280)
+}
+
+/// Maximum weighted length of a comment message
+pub fn comment_max_length() -> usize {
+    env_or("COMMENT_MAX_LENGTH", 280)
+}
+
+/// Weight every URL counts for, regardless of its real length
+pub fn url_weight() -> usize {
+    env_or("URL_WEIGHT", 23)
+}
+
+/// Seconds between two refreshes of the trending topics cache
+pub fn trending_refresh_interval() -> u64 {
+    env_or("TRENDING_REFRESH_SECONDS", 60)
+}
+
+/// Number of hashtags kept per trending window
+pub fn trending_limit() -> usize {
+    env_or("TRENDING_LIMIT", 10)
+}
+
+/// Number of recent events kept for streaming clients to resume from
+pub fn event_history_capacity() -> usize {
+    env_or("EVENT_HISTORY_CAPACITY", 1024)
+}
+
+/// Maximum weighted length of a direct message
+pub fn dm_max_length() -> usize {
+    env_or("DM_MAX_LENGTH", 10000)
+}
+
+/// Maximum number of participants in a group conversation, including its creator
+pub fn dm_max_participants() -> usize {
+    env_or("DM_MAX_PARTICIPANTS", 10)
+}
+
+/// Directory the filesystem media store writes uploads to
+pub fn media_dir() -> String {
+    env_or("MEDIA_DIR", "media".to_string())
+}
+
+/// Maximum size in bytes of an uploaded image or GIF
+pub fn media_max_image_size() -> usize {
+    env_or("MEDIA_MAX_IMAGE_BYTES", 5 * 1024 * 1024)
+}
+
+/// Maximum size in bytes of an uploaded video
+pub fn media_max_video_size() -> usize {
+    env_or("MEDIA_MAX_VIDEO_BYTES", 15 * 1024 * 1024)
+}
+
+/// Largest width and height of generated thumbnails, in pixels
+pub fn media_thumbnail_size() -> u32 {
+    env_or("MEDIA_THUMBNAIL_SIZE", 320)
+}
+
+/// Maximum number of media attached to a tweet
+pub fn media_max_per_tweet() -> usize {
+    env_or("MEDIA_MAX_PER_TWEET", 4)
+}
+
+/// Seconds between two runs of the scheduled tweet publisher
+pub fn scheduler_interval() -> u64 {
+    env_or("SCHEDULER_INTERVAL_SECONDS", 30)
+}
+
+/// Maximum length of a list name
+pub fn list_name_max_length() -> usize {
+    env_or("LIST_NAME_MAX_LENGTH", 25)
+}
+
+/// Maximum length of a list description
+pub fn list_description_max_length() -> usize {
+    env_or("LIST_DESCRIPTION_MAX_LENGTH", 100)
+}
+
+/// Maximum number of accounts in a list
+pub fn list_max_members() -> usize {
+    env_or("LIST_MAX_MEMBERS", 5000)
+}
+
+/// Maximum length of the details of a report
+pub fn report_details_max_length() -> usize {
+    env_or("REPORT_DETAILS_MAX_LENGTH", 500)
+}
+
+/// File the content filter reads its word and regex blocklist from
+pub fn content_blocklist_path() -> String {
+    env_or("CONTENT_BLOCKLIST_PATH", "blocklist.txt".to_string())
+}
+
+/// Seconds between two checks of the blocklist file for changes
+pub fn content_blocklist_reload_interval() -> u64 {
+    env_or("CONTENT_BLOCKLIST_RELOAD_SECONDS", 10)
+}
+
+/// Maximum number of muted words per user
+pub fn muted_words_max() -> usize {
+    env_or("MUTED_WORDS_MAX", 100)
+}
+
+/// Accounts younger than this are scored as new accounts by the spam heuristics
+pub fn spam_new_account_age() -> Duration {
+    Duration::hours(env_or("SPAM_NEW_ACCOUNT_HOURS", 24))
+}
+
+/// Window posting velocity is measured over
+pub fn spam_velocity_window() -> Duration {
+    Duration::minutes(env_or("SPAM_VELOCITY_WINDOW_MINUTES", 10))
+}
+
+/// Tweets and comments an account may post within the velocity window
+pub fn spam_velocity_limit() -> usize {
+    env_or("SPAM_VELOCITY_LIMIT", 20)
+}
+
+/// Tweets and comments a new account may post within the velocity window
+pub fn spam_new_account_velocity_limit() -> usize {
+    env_or("SPAM_NEW_ACCOUNT_VELOCITY_LIMIT", 5)
+}
+
+/// Window recent tweets are compared over to detect duplicate content
+pub fn spam_duplicate_window() -> Duration {
+    Duration::hours(env_or("SPAM_DUPLICATE_WINDOW_HOURS", 24))
+}
+
+/// Word overlap, between 0 and 1, from which two tweets count as duplicates
+pub fn spam_duplicate_similarity() -> f64 {
+    env_or("SPAM_DUPLICATE_SIMILARITY", 0.8)
+}
+
+/// Share of links among the words of a post from which it counts as link spam
+pub fn spam_link_density() -> f64 {
+    env_or("SPAM_LINK_DENSITY", 0.3)
+}
+
+/// Mentions in a single post from which it counts as mention spam
+pub fn spam_max_mentions() -> usize {
+    env_or("SPAM_MAX_MENTIONS", 5)
+}
+
+/// Spam score from which a post is flagged for review
+pub fn spam_flag_score() -> u32 {
+    env_or("SPAM_FLAG_SCORE", 2)
+}
+
+/// Spam score from which a post is rejected until the user slows down
+pub fn spam_throttle_score() -> u32 {
+    env_or("SPAM_THROTTLE_SCORE", 4)
+}
+
+/// Spam score from which the account is shadow limited
+pub fn spam_shadow_limit_score() -> u32 {
+    env_or("SPAM_SHADOW_LIMIT_SCORE", 6)
+}
+
+/// Time between a deletion request and the account being erased, signing in cancels it
+pub fn account_deletion_grace_period() -> Duration {
+    Duration::days(env_or("ACCOUNT_DELETION_GRACE_DAYS", 14))
+}
+
+/// Seconds between two runs of the job erasing accounts past their grace period
+pub fn account_purge_interval() -> u64 {
+    env_or("ACCOUNT_PURGE_SECONDS", 3600)
+}
+
+/// Maximum size in bytes of a Twitter archive `tweets.js` file sent to the importer
+pub fn archive_import_max_size() -> usize {
+    env_or("ARCHIVE_IMPORT_MAX_BYTES", 100 * 1024 * 1024)
+}
+
+/// Time a response is kept for replay to clients retrying with the same idempotency key
+pub fn idempotency_key_ttl() -> Duration {
+    Duration::hours(env_or("IDEMPOTENCY_KEY_TTL_HOURS", 24))
+}
+
+/// Time after which a request holding an idempotency key that never finished is run again
+pub fn idempotency_lock_timeout() -> Duration {
+    Duration::seconds(env_or("IDEMPOTENCY_LOCK_TIMEOUT_SECONDS", 60))
+}
+
+/// Seconds between two runs of the job purging expired idempotency keys
+pub fn idempotency_purge_interval() -> u64 {
+    env_or("IDEMPOTENCY_PURGE_SECONDS", 3600)
+}
+
+/// Addresses of the reverse proxies whose forwarding headers are trusted for the client address
+pub fn trusted_proxies() -> Vec<IpAddr> {
+    env::var("TRUSTED_PROXIES")
+        .unwrap_or_default()
+        .split(',')
+        .filter_map(|proxy| proxy.trim().parse::<IpAddr>().ok())
+        .collect()
+}
+
+/// Seconds the ids of the protected and shadow limited accounts are cached for
+pub fn restricted_ids_cache_seconds() -> u64 {
+    env_or("RESTRICTED_IDS_CACHE_SECONDS", 30)
+}
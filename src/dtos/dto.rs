@@ -1,16 +1,25 @@
-use crate::model::{like_model::Like, tweet_comment::Comment, tweet_model::Tweet};
+use crate::model::{
+    audience_model::{ReplyPolicy, Visibility},
+    audit_model::{AuditAction, AuditOutcome},
+    draft_model::DraftStatus,
+    media_model::MediaKind,
+    notification_model::NotificationKind,
+    report_model::{ModerationAction, ReportReason, ReportStatus, ReportTarget},
+    spam_model::{SpamDecision, SpamSignal},
+    tweet_poll::PollRequest,
+};
 use chrono::{DateTime, Utc};
-use mongodb::bson::oid::ObjectId;
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LikeDto {
     pub id: String,
     pub created_at: DateTime<Utc>,
     pub tweet_id: String,
+    pub user_id: Option<String>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TweetDto {
     pub id: String,
     pub user_id: String,
@@ -18,14 +27,87 @@ pub struct TweetDto {
     pub message: String,
     pub likes: Vec<LikeDto>,
     pub comments: Vec<CommentDto>,
+    pub updated_at: Option<DateTime<Utc>>,
+    pub version: u64,
+    pub edited: bool,
+    pub edit_count: usize,
+    pub entities: TweetEntitiesDto,
+    pub media: Vec<MediaDto>,
+    pub poll: Option<PollDto>,
+    pub visibility: Visibility,
+    pub reply_policy: ReplyPolicy,
+    pub withheld: bool,
+    pub is_pinned: bool,
+    pub bookmarked_by_me: bool,
+    pub deleted: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PollOptionDto {
+    pub text: String,
+    pub votes: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PollDto {
+    pub options: Vec<PollOptionDto>,
+    pub total_votes: Option<u64>,
+    pub ends_at: DateTime<Utc>,
+    pub closed: bool,
+    pub voted_option: Option<usize>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MediaDto {
+    pub id: String,
+    pub kind: MediaKind,
+    pub content_type: String,
+    pub url: String,
+    pub thumbnail_url: Option<String>,
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    pub size: usize,
+    pub alt_text: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EntityDto {
+    pub text: String,
+    pub indices: [usize; 2],
+    pub utf16_indices: [usize; 2],
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TweetEntitiesDto {
+    pub hashtags: Vec<EntityDto>,
+    pub mentions: Vec<EntityDto>,
+    pub urls: Vec<EntityDto>,
+    pub cashtags: Vec<EntityDto>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
+pub struct TweetEditDto {
+    pub message: String,
+    pub created_at: DateTime<Utc>,
+    pub replaced_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CommentDto {
     pub id: String,
     pub created_at: DateTime<Utc>,
     pub message: String,
     pub tweet_id: String,
+    pub user_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrendDto {
+    pub hashtag: String,
+    pub tweet_count: usize,
+    pub score: f64,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -34,39 +116,197 @@ pub struct UserDto {
     pub message: String,
 }
 
-impl TweetDto {
-    /// Transforms <b>TweetDto</b> to <b>Tweet</b> using mapping.
-    pub fn to_tweet(self) -> Tweet {
-        Tweet {
-            id: Some(ObjectId::parse_str(&self.id).unwrap()),
-            user_id: Some(ObjectId::parse_str(&self.id).unwrap()),
-            message: self.message.to_owned(),
-            created_at: self.created_at,
-            likes: self.likes.into_iter().map(|l| l.to_like()).collect(),
-            comments: self.comments.into_iter().map(|c| c.to_comment()).collect(),
-        }
-    }
-}
-
-impl LikeDto {
-    /// Transforms <b>LikeDto</b> to <b>Like</b> using mapping.
-    pub fn to_like(&self) -> Like {
-        Like {
-            id: Some(ObjectId::parse_str(&self.id).unwrap()),
-            created_at: self.created_at,
-            tweet_id: Some(ObjectId::parse_str(&self.tweet_id).unwrap()),
-        }
-    }
-}
-
-impl CommentDto {
-     /// Transforms <b>CommentDto</b> to <b>Comment</b> using mapping.
-    pub fn to_comment(&self) -> Comment {
-        Comment {
-            id: Some(ObjectId::parse_str(&self.id).unwrap()),
-            created_at: self.created_at,
-            message: self.message.clone(),
-            tweet_id: Some(ObjectId::parse_str(&self.tweet_id).unwrap()),
-        }
-    }
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UserProfileDto {
+    pub id: String,
+    pub username: Option<String>,
+    pub protected: bool,
+    pub pinned_tweet_id: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct FollowRequestDto {
+    pub id: String,
+    pub requester: UserProfileDto,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct NotificationDto {
+    pub id: String,
+    pub kind: NotificationKind,
+    pub tweet_id: Option<String>,
+    pub actor_ids: Vec<String>,
+    pub actor_count: usize,
+    pub message: String,
+    pub read: bool,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct NotificationPageDto {
+    pub unread_count: u64,
+    pub notifications: Vec<NotificationDto>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ConversationDto {
+    pub id: String,
+    pub participant_ids: Vec<String>,
+    pub is_group: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReadReceiptDto {
+    pub user_id: String,
+    pub read_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DirectMessageDto {
+    pub id: String,
+    pub conversation_id: String,
+    pub sender_id: String,
+    pub body: String,
+    pub sent_at: DateTime<Utc>,
+    pub read_by: Vec<ReadReceiptDto>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BookmarkPageDto {
+    pub tweets: Vec<TweetDto>,
+    pub next_cursor: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DraftDto {
+    pub id: String,
+    pub message: String,
+    pub media_ids: Vec<String>,
+    pub poll: Option<PollRequest>,
+    pub visibility: Visibility,
+    pub reply_policy: ReplyPolicy,
+    pub status: DraftStatus,
+    pub publish_at: Option<DateTime<Utc>>,
+    pub tweet_id: Option<String>,
+    pub error: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ListDto {
+    pub id: String,
+    pub owner_id: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub private: bool,
+    pub member_count: usize,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ModerationRecordDto {
+    pub moderator_id: String,
+    pub status: ReportStatus,
+    pub action: Option<ModerationAction>,
+    pub note: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ReportDto {
+    pub id: String,
+    pub reporter_id: String,
+    pub target: ReportTarget,
+    pub target_id: String,
+    pub tweet_id: Option<String>,
+    pub offender_id: Option<String>,
+    pub reason: ReportReason,
+    pub details: Option<String>,
+    pub status: ReportStatus,
+    pub history: Vec<ModerationRecordDto>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct HeldContentDto {
+    pub tweet_id: String,
+    pub comment_id: Option<String>,
+    pub user_id: Option<String>,
+    pub message: String,
+    pub reason: String,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SpamEventDto {
+    pub id: String,
+    pub user_id: String,
+    pub target: ReportTarget,
+    pub message: String,
+    pub score: u32,
+    pub signals: Vec<SpamSignal>,
+    pub decision: SpamDecision,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AuditEventDto {
+    pub id: String,
+    pub actor: Option<String>,
+    pub action: AuditAction,
+    pub target: Option<String>,
+    pub ip: Option<String>,
+    pub user_agent: Option<String>,
+    pub outcome: AuditOutcome,
+    pub detail: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AccountExportDto {
+    pub profile: UserProfileDto,
+    pub email: String,
+    pub tweets: Vec<TweetDto>,
+    pub comments: Vec<CommentDto>,
+    pub liked_tweet_ids: Vec<String>,
+    pub follower_ids: Vec<String>,
+    pub following_ids: Vec<String>,
+    pub conversations: Vec<ConversationDto>,
+    pub messages: Vec<DirectMessageDto>,
+    pub exported_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AccountDeletionDto {
+    pub message: String,
+    pub erase_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImportErrorDto {
+    pub index: usize,
+    pub source_id: Option<String>,
+    pub message: String,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct ImportReportDto {
+    pub imported_tweets: usize,
+    pub imported_replies: usize,
+    pub skipped: usize,
+    pub errors: Vec<ImportErrorDto>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DeletedTweetDto {
+    pub id: String,
+    pub deleted_at: DateTime<Utc>,
+    pub restorable_until: DateTime<Utc>,
 }
@@ -1,9 +1,28 @@
 // errors.rs
 use actix_web::{error::ResponseError, HttpResponse};
 use derive_more::Display;
+use serde::Serialize;
+
+/// Describes why a single request field failed validation
+#[derive(Debug, Clone, Serialize)]
+pub struct FieldError {
+    pub field: String,
+    pub code: String,
+    pub message: String,
+}
+
+impl FieldError {
+    pub fn new(field: &str, code: &str, message: &str) -> Self {
+        FieldError {
+            field: field.to_string(),
+            code: code.to_string(),
+            message: message.to_string(),
+        }
+    }
+}
 
 #[derive(Debug, Display)]
-///Tweet errors 
+///Tweet errors
 pub enum TweetError {
     ///Internal server error due to network related issues
     #[display(fmt = "Internal Server Error, Please try later")]
@@ -16,6 +35,30 @@ pub enum TweetError {
     ///Authentication error when authentication fails or unauthorised
     #[display(fmt = "Unauthorized: {}", _0)]
     Unauthorized(String),
+
+    ///Forbidden error when the user is not allowed to perform the action
+    #[display(fmt = "Forbidden: {}", _0)]
+    Forbidden(String),
+
+    ///Not found error when the requested resource does not exist
+    #[display(fmt = "NotFound: {}", _0)]
+    NotFound(String),
+
+    ///Conflict error when the request clashes with one still being processed
+    #[display(fmt = "Conflict: {}", _0)]
+    Conflict(String),
+
+    ///Precondition failed error when the resource changed since the client last read it
+    #[display(fmt = "PreconditionFailed: {}", _0)]
+    PreconditionFailed(String),
+
+    ///Too many requests error when the user is posting faster than allowed
+    #[display(fmt = "TooManyRequests: {}", _0)]
+    TooManyRequests(String),
+
+    ///Validation error when one or more request fields are invalid
+    #[display(fmt = "UnprocessableEntity: request validation failed")]
+    UnprocessableEntity(Vec<FieldError>),
 }
 
 // impl ResponseError trait allows to convert our errors into http responses with appropriate data
@@ -27,6 +70,18 @@ impl ResponseError for TweetError {
             }
             TweetError::BadRequest(ref message) => HttpResponse::BadRequest().json(message),
             TweetError::Unauthorized(ref message) => HttpResponse::Unauthorized().json(message),
+            TweetError::Forbidden(ref message) => HttpResponse::Forbidden().json(message),
+            TweetError::NotFound(ref message) => HttpResponse::NotFound().json(message),
+            TweetError::Conflict(ref message) => HttpResponse::Conflict().json(message),
+            TweetError::PreconditionFailed(ref message) => {
+                HttpResponse::PreconditionFailed().json(message)
+            }
+            TweetError::TooManyRequests(ref message) => {
+                HttpResponse::TooManyRequests().json(message)
+            }
+            TweetError::UnprocessableEntity(ref errors) => {
+                HttpResponse::UnprocessableEntity().json(serde_json::json!({ "errors": errors }))
+            }
         }
     }
 }
@@ -2,20 +2,62 @@ extern crate actix_web;
 extern crate log;
 
 use actix_web::{middleware, web::Data, App, HttpServer};
+use chrono::Utc;
+use config::settings::{
+    account_purge_interval, content_blocklist_path, content_blocklist_reload_interval,
+    event_history_capacity, idempotency_purge_interval, media_dir, scheduler_interval,
+    trending_refresh_interval, tweet_deleted_retention, tweet_purge_interval,
+};
 use dbconn::MongoPool;
-use model::{auth_model::User, tweet_model::Tweet};
-use repo::{tweet_repo::TweetRepo, user_repo::UserRepo};
+use model::{
+    audit_model::AuditEvent,
+    auth_model::User,
+    bookmark_model::Bookmark,
+    dm_model::{Conversation, DirectMessage},
+    draft_model::Draft,
+    follow_model::{Follow, FollowRequest},
+    idempotency_model::IdempotencyRecord,
+    list_model::UserList,
+    media_model::Media,
+    notification_model::{Notification, NotificationPreferences},
+    relation_model::Relation,
+    report_model::{HeldReview, Report},
+    spam_model::SpamEvent,
+    tweet_model::Tweet,
+};
+use repo::{
+    audit_repo::AuditRepo, bookmark_repo::BookmarkRepo, dm_repo::DmRepo, draft_repo::DraftRepo,
+    follow_repo::FollowRepo, idempotency_repo::IdempotencyRepo, list_repo::ListRepo,
+    media_repo::MediaRepo, notification_repo::NotificationRepo, relation_repo::RelationRepo,
+    report_repo::ReportRepo, spam_repo::SpamRepo, tweet_repo::TweetRepo, user_repo::UserRepo,
+};
 use routes::router;
-use std::{env, io};
+use services::{
+    account::AccountService,
+    content_filter::{ContentFilter, ContentPolicy, FileBlocklist},
+    event_bus::EventBus,
+    idempotency::IdempotencyService,
+    importer::ArchiveImporter,
+    media_store::{FileSystemStore, MediaStore},
+    publisher::TweetPublisher,
+    search::{SearchBackend, SearchService},
+    spam::SpamGuard,
+    trending::TrendingService,
+};
+use std::{env, io, sync::Arc, time::Duration};
 
 mod api;
 mod auths;
+mod config;
 mod dbconn;
 mod dtos;
 mod errors;
 mod model;
 mod repo;
 mod routes;
+mod services;
+mod text;
+mod validation;
 
 #[actix_rt::main]
 async fn main() -> io::Result<()> {
@@ -24,11 +66,210 @@ async fn main() -> io::Result<()> {
 
     let db = MongoPool::<Tweet>::connect().await;
     let user_db = MongoPool::<User>::connect().await;
+    let notification_db = MongoPool::<Notification>::connect().await;
+    let preferences_db = MongoPool::<NotificationPreferences>::connect().await;
+    let follow_db = MongoPool::<Follow>::connect().await;
+    let follow_request_db = MongoPool::<FollowRequest>::connect().await;
+    let conversation_db = MongoPool::<Conversation>::connect().await;
+    let message_db = MongoPool::<DirectMessage>::connect().await;
+    let relation_db = MongoPool::<Relation>::connect().await;
+    let bookmark_db = MongoPool::<Bookmark>::connect().await;
+    let media_db = MongoPool::<Media>::connect().await;
+    let draft_db = MongoPool::<Draft>::connect().await;
+    let list_db = MongoPool::<UserList>::connect().await;
+    let report_db = MongoPool::<Report>::connect().await;
+    let held_review_db = MongoPool::<HeldReview>::connect().await;
+    let spam_db = MongoPool::<SpamEvent>::connect().await;
+    let audit_db = MongoPool::<AuditEvent>::connect().await;
+    let idempotency_db = MongoPool::<IdempotencyRecord>::connect().await;
+    let events = Arc::new(EventBus::new(event_history_capacity()));
+    let relations = RelationRepo {
+        collection: relation_db.collection,
+    };
+    let notifications = NotificationRepo {
+        collection: notification_db.collection,
+        preferences: preferences_db.collection,
+        events: events.clone(),
+        relations: relations.clone(),
+    };
+    let follows = FollowRepo {
+        collection: follow_db.collection,
+        requests: follow_request_db.collection,
+        notifications: notifications.clone(),
+    };
+    let users = UserRepo {
+        collection: user_db.collection,
+        restricted: Arc::default(),
+    };
     let pool = Data::new(TweetRepo {
         collection: db.collection,
+        notifications: notifications.clone(),
+        events: events.clone(),
+        relations: relations.clone(),
+        follows: follows.clone(),
+        users: users.clone(),
     });
-    let user_pool = Data::new(UserRepo {
-        collection: user_db.collection,
+    let follow_pool = Data::new(follows);
+    let dm_pool = Data::new(DmRepo {
+        collection: conversation_db.collection,
+        messages: message_db.collection,
+        events: events.clone(),
+        relations: relations.clone(),
+    });
+    let notification_pool = Data::new(notifications);
+    let relation_pool = Data::new(relations);
+    let bookmark_pool = Data::new(BookmarkRepo {
+        collection: bookmark_db.collection,
+    });
+    let media_pool = Data::new(MediaRepo {
+        collection: media_db.collection,
+    });
+    let list_pool = Data::new(ListRepo {
+        collection: list_db.collection,
+    });
+    let report_pool = Data::new(ReportRepo {
+        collection: report_db.collection,
+        held_reviews: held_review_db.collection,
+    });
+    let audit_pool = Data::new(AuditRepo {
+        collection: audit_db.collection,
+    });
+    let media_store: Data<dyn MediaStore> =
+        Data::from(Arc::new(FileSystemStore::new(media_dir())) as Arc<dyn MediaStore>);
+    let event_bus = Data::from(events);
+    let user_pool = Data::new(users);
+    let trending = Data::new(TrendingService::default());
+    let search = Data::new(SearchService::new(SearchBackend::from_env()));
+    if let Err(err) = search.init(&pool).await {
+        log::error!("Failed to initialise search: {}", err);
+    }
+    let draft_pool = Data::new(DraftRepo {
+        collection: draft_db.collection,
+    });
+    let content_filter =
+        Data::new(ContentFilter::new(vec![
+            Arc::new(FileBlocklist::new(content_blocklist_path())) as Arc<dyn ContentPolicy>,
+        ]));
+    let spam_guard = Data::new(SpamGuard {
+        tweets: pool.clone(),
+        users: user_pool.clone(),
+        events: SpamRepo {
+            collection: spam_db.collection,
+        },
+    });
+    let publisher = Data::new(TweetPublisher {
+        tweets: pool.clone(),
+        users: user_pool.clone(),
+        media: media_pool.clone(),
+        search: search.clone(),
+        filter: content_filter.clone(),
+        spam: spam_guard.clone(),
+    });
+    let account = Data::new(AccountService {
+        users: user_pool.clone(),
+        tweets: pool.clone(),
+        follows: follow_pool.clone(),
+        dms: dm_pool.clone(),
+        bookmarks: bookmark_pool.clone(),
+        relations: relation_pool.clone(),
+        lists: list_pool.clone(),
+        notifications: notification_pool.clone(),
+        search: search.clone(),
+        drafts: draft_pool.clone(),
+        media: media_pool.clone(),
+        media_store: media_store.clone(),
+        reports: report_pool.clone(),
+        spam: spam_guard.clone(),
+        audit: audit_pool.clone(),
+    });
+    let importer = Data::new(ArchiveImporter {
+        tweets: pool.clone(),
+        users: user_pool.clone(),
+        search: search.clone(),
+    });
+    let idempotency = Data::new(IdempotencyService {
+        records: IdempotencyRepo {
+            collection: idempotency_db.collection,
+        },
+    });
+    if let Err(err) = idempotency.init().await {
+        log::error!("Failed to initialise idempotency keys: {}", err);
+    }
+
+    // `import-archive <user_id> <tweets.js>` imports a Twitter archive instead of serving
+    let args = env::args().collect::<Vec<String>>();
+    if args.get(1).map(String::as_str) == Some("import-archive") {
+        return import_archive(&importer, &args[2..]).await;
+    }
+
+    let trending_task = trending.clone();
+    let trending_pool = pool.clone();
+    actix_rt::spawn(async move {
+        let mut interval =
+            actix_rt::time::interval(Duration::from_secs(trending_refresh_interval()));
+        loop {
+            interval.tick().await;
+            if let Err(err) = trending_task.refresh(&trending_pool).await {
+                log::error!("Failed to refresh trending topics: {}", err);
+            }
+        }
+    });
+
+    let reload_filter = content_filter.clone();
+    actix_rt::spawn(async move {
+        let mut interval =
+            actix_rt::time::interval(Duration::from_secs(content_blocklist_reload_interval()));
+        loop {
+            interval.tick().await;
+            reload_filter.reload();
+        }
+    });
+
+    let scheduler_publisher = publisher.clone();
+    let scheduler_drafts = draft_pool.clone();
+    actix_rt::spawn(async move {
+        let mut interval = actix_rt::time::interval(Duration::from_secs(scheduler_interval()));
+        loop {
+            interval.tick().await;
+            if let Err(err) = scheduler_publisher.publish_due(&scheduler_drafts).await {
+                log::error!("Failed to publish scheduled tweets: {}", err);
+            }
+        }
+    });
+
+    let purge_account = account.clone();
+    actix_rt::spawn(async move {
+        let mut interval = actix_rt::time::interval(Duration::from_secs(account_purge_interval()));
+        loop {
+            interval.tick().await;
+            if let Err(err) = purge_account.purge_due().await {
+                log::error!("Failed to erase deleted accounts: {}", err);
+            }
+        }
+    });
+
+    let purge_pool = pool.clone();
+    actix_rt::spawn(async move {
+        let mut interval = actix_rt::time::interval(Duration::from_secs(tweet_purge_interval()));
+        loop {
+            interval.tick().await;
+            let before = Utc::now() - tweet_deleted_retention();
+            if let Err(err) = purge_pool.purge_deleted(before).await {
+                log::error!("Failed to purge deleted tweets: {}", err);
+            }
+        }
+    });
+
+    let purge_idempotency = idempotency.clone();
+    actix_rt::spawn(async move {
+        let mut interval =
+            actix_rt::time::interval(Duration::from_secs(idempotency_purge_interval()));
+        loop {
+            interval.tick().await;
+            if let Err(err) = purge_idempotency.records.purge_expired(Utc::now()).await {
+                log::error!("Failed to purge expired idempotency keys: {}", err);
+            }
+        }
     });
 
     HttpServer::new(move || {
@@ -36,9 +277,52 @@ async fn main() -> io::Result<()> {
             .wrap(middleware::Logger::default())
             .app_data(user_pool.clone())
             .app_data(pool.clone())
+            .app_data(notification_pool.clone())
+            .app_data(follow_pool.clone())
+            .app_data(dm_pool.clone())
+            .app_data(relation_pool.clone())
+            .app_data(bookmark_pool.clone())
+            .app_data(media_pool.clone())
+            .app_data(list_pool.clone())
+            .app_data(report_pool.clone())
+            .app_data(audit_pool.clone())
+            .app_data(media_store.clone())
+            .app_data(trending.clone())
+            .app_data(search.clone())
+            .app_data(event_bus.clone())
+            .app_data(draft_pool.clone())
+            .app_data(publisher.clone())
+            .app_data(content_filter.clone())
+            .app_data(spam_guard.clone())
+            .app_data(account.clone())
+            .app_data(importer.clone())
+            .app_data(idempotency.clone())
             .configure(router::init)
     })
     .bind(("127.0.0.1", 8080))?
     .run()
     .await
 }
+
+/// Imports a Twitter archive from the command line and prints the import report
+async fn import_archive(importer: &ArchiveImporter, args: &[String]) -> io::Result<()> {
+    let (user_id, path) = match args {
+        [user_id, path] => (user_id, path),
+        _ => {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "usage: import-archive <user_id> <tweets.js>",
+            ))
+        }
+    };
+    let content = std::fs::read_to_string(path)?;
+    let report = importer
+        .import(user_id, &content)
+        .await
+        .map_err(|err| io::Error::other(err.to_string()))?;
+    println!(
+        "{}",
+        serde_json::to_string_pretty(&report).unwrap_or_default()
+    );
+    Ok(())
+}
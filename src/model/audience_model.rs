@@ -0,0 +1,141 @@
+use std::collections::HashSet;
+
+use mongodb::bson::{doc, oid::ObjectId, Document, Regex as BsonRegex};
+use regex::{Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
+
+use crate::dtos::dto::TweetDto;
+
+/// Who can see a tweet, besides its author
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Visibility {
+    #[default]
+    Public,
+    Followers,
+    Mentioned,
+}
+
+/// Who can reply to a tweet, besides its author
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReplyPolicy {
+    #[default]
+    Everyone,
+    /// Only people the author follows
+    Following,
+    Mentioned,
+}
+
+/// The tweets a viewer is allowed to see, given who they follow, which
+/// authors are protected, which users they blocked, muted or were blocked by
+/// and the words they muted.
+#[derive(Debug, Clone, Default)]
+pub struct Audience {
+    pub viewer_id: String,
+    pub following: HashSet<String>,
+    pub protected: HashSet<String>,
+    pub hidden: HashSet<String>,
+    muted_words: Option<String>,
+    muted: Option<Regex>,
+}
+
+impl Audience {
+    pub fn new(viewer_id: &str) -> Self {
+        Audience {
+            viewer_id: viewer_id.to_string(),
+            ..Audience::default()
+        }
+    }
+
+    /// Hides the tweets of others containing any of the words, matched case-insensitively
+    pub fn mute_words(&mut self, words: &[String]) {
+        if words.is_empty() {
+            return;
+        }
+        let alternatives = words
+            .iter()
+            .map(|w| regex::escape(w))
+            .collect::<Vec<String>>()
+            .join("|");
+        let pattern = format!(r"(?:^|\W)(?:{})(?:\W|$)", alternatives);
+        self.muted = RegexBuilder::new(&pattern)
+            .case_insensitive(true)
+            .build()
+            .ok();
+        self.muted_words = Some(pattern);
+    }
+
+    /// Checks whether the text contains a muted word
+    pub fn mutes(&self, text: &str) -> bool {
+        self.muted.as_ref().is_some_and(|m| m.is_match(text))
+    }
+
+    /// Checks whether the viewer can see a tweet of the author
+    pub fn allows(&self, author_id: &str, visibility: Visibility, mentioned: &[String]) -> bool {
+        if author_id == self.viewer_id {
+            return true;
+        }
+        if self.hidden.contains(author_id) {
+            return false;
+        }
+        let follows = self.following.contains(author_id);
+        if self.protected.contains(author_id) && !follows {
+            return false;
+        }
+        match visibility {
+            Visibility::Public => true,
+            Visibility::Followers => follows,
+            Visibility::Mentioned => mentioned.contains(&self.viewer_id),
+        }
+    }
+
+    /// Checks whether the viewer can see the tweet, withheld tweets are only visible to their author
+    pub fn allows_tweet(&self, tweet: &TweetDto) -> bool {
+        if tweet.user_id != self.viewer_id && (tweet.withheld || self.mutes(&tweet.message)) {
+            return false;
+        }
+        let mentioned = tweet
+            .entities
+            .mentions
+            .iter()
+            .filter_map(|m| m.user_id.clone())
+            .collect::<Vec<String>>();
+        self.allows(&tweet.user_id, tweet.visibility, &mentioned)
+    }
+
+    /// Query matching the tweets the viewer can see, the same rules as `allows_tweet`
+    pub fn filter(&self) -> Document {
+        let viewer_id = ObjectId::parse_str(&self.viewer_id).ok();
+        let following = object_ids(&self.following);
+        let mut others = doc! {
+            "withheld": {"$ne": true},
+            "user_id": {"$nin": object_ids(&self.hidden)},
+            "$and": [
+                {"$or": [
+                    {"visibility": {"$in": [null, "public"]}},
+                    {"visibility": "followers", "user_id": {"$in": &following}},
+                    {"visibility": "mentioned", "entities.mentions.user_id": viewer_id}
+                ]},
+                {"$or": [
+                    {"user_id": {"$nin": object_ids(&self.protected)}},
+                    {"user_id": {"$in": &following}}
+                ]}
+            ]
+        };
+        if let Some(pattern) = &self.muted_words {
+            let muted = BsonRegex {
+                pattern: pattern.clone(),
+                options: "i".to_string(),
+            };
+            others.insert("message", doc! {"$not": muted});
+        }
+        doc! {"$or": [{"user_id": viewer_id}, others]}
+    }
+}
+
+fn object_ids(ids: &HashSet<String>) -> Vec<ObjectId> {
+    ids.iter()
+        .filter_map(|id| ObjectId::parse_str(id).ok())
+        .collect()
+}
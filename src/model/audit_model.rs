@@ -0,0 +1,113 @@
+use chrono::{DateTime, Utc};
+use mongodb::bson::oid::ObjectId;
+use serde::{Deserialize, Serialize};
+
+use crate::{dtos::dto::AuditEventDto, model::pagination::PageQuery};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditAction {
+    Login,
+    Logout,
+    PasswordChange,
+    DeleteTweet,
+    DeleteComment,
+    DeleteAccount,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AuditOutcome {
+    Success,
+    Failure,
+}
+
+/// Where a request came from, as far as the server can tell
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
+pub struct ClientInfo {
+    pub ip: Option<String>,
+    pub user_agent: Option<String>,
+}
+
+/// A security relevant event, audit events are never updated or deleted
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct AuditEvent {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    pub id: Option<ObjectId>,
+    /// Id of the signed in user, or the email given when signing in
+    pub actor: Option<String>,
+    pub action: AuditAction,
+    /// Id of the tweet or comment acted on
+    pub target: Option<String>,
+    pub ip: Option<String>,
+    pub user_agent: Option<String>,
+    pub outcome: AuditOutcome,
+    /// Why the action failed
+    pub detail: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl AuditEvent {
+    pub fn new(action: AuditAction, actor: Option<String>, client: ClientInfo) -> Self {
+        AuditEvent {
+            id: None,
+            actor,
+            action,
+            target: None,
+            ip: client.ip,
+            user_agent: client.user_agent,
+            outcome: AuditOutcome::Success,
+            detail: None,
+            created_at: Utc::now(),
+        }
+    }
+
+    pub fn target(mut self, target: &str) -> Self {
+        self.target = Some(target.to_string());
+        self
+    }
+
+    /// Sets the outcome from the result of the audited action
+    pub fn outcome<T, E: ToString>(mut self, result: &Result<T, E>) -> Self {
+        if let Err(err) = result {
+            self.outcome = AuditOutcome::Failure;
+            self.detail = Some(err.to_string());
+        }
+        self
+    }
+
+    /// Transforms <b>AuditEvent</b> to <b>AuditEventDto</b> using mapping.
+    pub fn map(&self) -> AuditEventDto {
+        AuditEventDto {
+            id: self.id.map(|id| id.to_hex()).unwrap_or_default(),
+            actor: self.actor.clone(),
+            action: self.action,
+            target: self.target.clone(),
+            ip: self.ip.clone(),
+            user_agent: self.user_agent.clone(),
+            outcome: self.outcome,
+            detail: self.detail.clone(),
+            created_at: self.created_at,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct AuditQuery {
+    pub actor: Option<String>,
+    pub action: Option<AuditAction>,
+    pub outcome: Option<AuditOutcome>,
+    pub since: Option<DateTime<Utc>>,
+    pub until: Option<DateTime<Utc>>,
+    pub page: Option<u64>,
+    pub limit: Option<u64>,
+}
+
+impl AuditQuery {
+    pub fn page_query(&self) -> PageQuery {
+        PageQuery {
+            page: self.page,
+            limit: self.limit,
+        }
+    }
+}
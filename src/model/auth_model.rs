@@ -3,27 +3,90 @@ use std::time::{self, Duration, SystemTime};
 use argonautica::{Hasher, Verifier};
 use bson::oid::ObjectId;
 use chrono::{DateTime, Utc};
-use jwt::{claims::RegisteredClaims, header::HeaderType, Header, SignWithKey, Token};
+use jwt::{
+    claims::{Claims, RegisteredClaims},
+    header::HeaderType,
+    Header, SignWithKey, Token,
+};
 use serde::{Deserialize, Serialize};
 
-use crate::auths::utils::get_jwt_key;
+use crate::{auths::utils::get_jwt_key, dtos::dto::UserProfileDto};
 
-#[derive(Debug, Serialize, Deserialize)]
+/// What a user is allowed to do, each role including the permissions of the previous ones
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum UserRole {
+    #[default]
+    User,
+    Moderator,
+    Admin,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct User {
     #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
     pub id: Option<ObjectId>,
     pub created_at: DateTime<Utc>,
     pub email: String,
     pub password: String,
+    #[serde(default)]
+    pub username: Option<String>,
+    #[serde(default)]
+    pub open_dms: bool,
+    #[serde(default)]
+    pub protected: bool,
+    #[serde(default)]
+    pub pinned_tweet_id: Option<ObjectId>,
+    #[serde(default)]
+    pub role: UserRole,
+    #[serde(default)]
+    pub suspended: bool,
+    /// Shadow limited accounts only show their tweets to themselves and their followers
+    #[serde(default)]
+    pub limited: bool,
+    /// Words hiding the tweets containing them from the user's listings
+    #[serde(default)]
+    pub muted_words: Vec<String>,
+    /// When the user asked for their account to be deleted, it is erased after a grace period
+    #[serde(default)]
+    pub deletion_requested_at: Option<DateTime<Utc>>,
+    /// Bumped to revoke every token issued so far, tokens carry the generation they were
+    /// issued in
+    #[serde(default)]
+    pub token_generation: u64,
 }
 
+/// Private claim holding the token generation of the user the token was issued to
+pub const TOKEN_GENERATION_CLAIM: &str = "gen";
+
 impl User {
-    pub fn new(email: &str, password: &str) -> Self {
+    pub fn new(email: &str, password: &str, username: Option<String>) -> Self {
         User {
             id: None,
             created_at: Utc::now(),
             email: email.to_string(),
             password: Self::hash_password(password),
+            username,
+            open_dms: false,
+            protected: false,
+            pinned_tweet_id: None,
+            role: UserRole::User,
+            suspended: false,
+            limited: false,
+            muted_words: vec![],
+            deletion_requested_at: None,
+            token_generation: 0,
+        }
+    }
+
+    /// Transforms <b>User</b> to <b>UserProfileDto</b> using mapping.
+    pub fn map(&self) -> UserProfileDto {
+        UserProfileDto {
+            id: self.id.unwrap().to_hex(),
+            username: self.username.clone(),
+            protected: self.protected,
+            pinned_tweet_id: self.pinned_tweet_id.map(|id| id.to_hex()),
+            created_at: self.created_at,
         }
     }
 
@@ -39,7 +102,6 @@ impl User {
             .with_secret_key(secret)
             .hash()
             .unwrap()
-      
     }
 
     /// Verifies the password using the verifier algorithm
@@ -54,6 +116,11 @@ impl User {
             .unwrap()
     }
 
+    /// Checks whether a token of the given generation was revoked since it was issued
+    pub fn is_token_revoked(&self, generation: u64) -> bool {
+        generation < self.token_generation
+    }
+
     /// Generates token string using the provided claims.
     pub fn generate_token(&self, password: &str) -> Option<String> {
         let key = get_jwt_key();
@@ -86,6 +153,11 @@ impl User {
                 ..Default::default()
             };
 
+            let mut claims = Claims::new(claims);
+            claims
+                .private
+                .insert(TOKEN_GENERATION_CLAIM.into(), self.token_generation.into());
+
             let token = Token::new(headers, claims).sign_with_key(&key).unwrap();
             Some(token.as_str().into())
         } else {
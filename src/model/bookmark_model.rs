@@ -0,0 +1,23 @@
+use chrono::{DateTime, Utc};
+use mongodb::bson::oid::ObjectId;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Bookmark {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    pub id: Option<ObjectId>,
+    pub user_id: ObjectId,
+    pub tweet_id: ObjectId,
+    pub created_at: DateTime<Utc>,
+}
+
+impl Bookmark {
+    pub fn new(user_id: ObjectId, tweet_id: ObjectId) -> Self {
+        Bookmark {
+            id: None,
+            user_id,
+            tweet_id,
+            created_at: Utc::now(),
+        }
+    }
+}
@@ -0,0 +1,110 @@
+use chrono::{DateTime, Utc};
+use mongodb::bson::oid::ObjectId;
+use serde::{Deserialize, Serialize};
+
+use crate::dtos::dto::{ConversationDto, DirectMessageDto, ReadReceiptDto};
+
+/// A private conversation between two users, or a small group
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Conversation {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    pub id: Option<ObjectId>,
+    pub participant_ids: Vec<ObjectId>,
+    pub is_group: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl Conversation {
+    pub fn new(participant_ids: Vec<ObjectId>) -> Self {
+        let now = Utc::now();
+        Conversation {
+            id: None,
+            is_group: participant_ids.len() > 2,
+            participant_ids,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    pub fn has_participant(&self, user_id: &ObjectId) -> bool {
+        self.participant_ids.contains(user_id)
+    }
+
+    /// Transforms <b>Conversation</b> to <b>ConversationDto</b> using mapping.
+    pub fn map(&self) -> ConversationDto {
+        ConversationDto {
+            id: self.id.unwrap().to_hex(),
+            participant_ids: self.participant_ids.iter().map(|p| p.to_hex()).collect(),
+            is_group: self.is_group,
+            created_at: self.created_at,
+            updated_at: self.updated_at,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ReadReceipt {
+    pub user_id: ObjectId,
+    pub read_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct DirectMessage {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    pub id: Option<ObjectId>,
+    pub conversation_id: ObjectId,
+    pub sender_id: ObjectId,
+    pub body: String,
+    pub sent_at: DateTime<Utc>,
+    pub read_by: Vec<ReadReceipt>,
+    pub deleted_for: Vec<ObjectId>,
+}
+
+impl DirectMessage {
+    pub fn new(conversation_id: ObjectId, sender_id: ObjectId, body: &str) -> Self {
+        DirectMessage {
+            id: Some(ObjectId::new()),
+            conversation_id,
+            sender_id,
+            body: body.to_string(),
+            sent_at: Utc::now(),
+            read_by: vec![],
+            deleted_for: vec![],
+        }
+    }
+
+    /// Transforms <b>DirectMessage</b> to <b>DirectMessageDto</b> using mapping.
+    pub fn map(&self) -> DirectMessageDto {
+        DirectMessageDto {
+            id: self.id.unwrap().to_hex(),
+            conversation_id: self.conversation_id.to_hex(),
+            sender_id: self.sender_id.to_hex(),
+            body: self.body.clone(),
+            sent_at: self.sent_at,
+            read_by: self
+                .read_by
+                .iter()
+                .map(|r| ReadReceiptDto {
+                    user_id: r.user_id.to_hex(),
+                    read_at: r.read_at,
+                })
+                .collect(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct ConversationRequest {
+    pub participant_ids: Vec<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct DirectMessageRequest {
+    pub body: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct DmSettingsRequest {
+    pub open_dms: bool,
+}
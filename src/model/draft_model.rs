@@ -0,0 +1,130 @@
+use chrono::{DateTime, Utc};
+use mongodb::bson::oid::ObjectId;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    dtos::dto::DraftDto,
+    errors::error::{FieldError, TweetError},
+    model::{
+        audience_model::{ReplyPolicy, Visibility},
+        tweet_model::{TweetActions, TweetRequest},
+        tweet_poll::PollRequest,
+    },
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DraftStatus {
+    /// Saved and editable, not visible to anyone else
+    Draft,
+    /// Waiting for its publish time
+    Scheduled,
+    /// Claimed by the publisher
+    Publishing,
+    Published,
+    Failed,
+}
+
+/// An unpublished tweet, either kept as a draft or scheduled for later.
+///
+/// `tweet_id` is reserved when the draft is created and used as the id of the
+/// published tweet, so publishing the same draft twice never creates two tweets.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Draft {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    pub id: Option<ObjectId>,
+    pub user_id: ObjectId,
+    pub message: String,
+    pub media_ids: Vec<String>,
+    pub poll: Option<PollRequest>,
+    #[serde(default)]
+    pub visibility: Visibility,
+    #[serde(default)]
+    pub reply_policy: ReplyPolicy,
+    pub status: DraftStatus,
+    pub publish_at: Option<DateTime<Utc>>,
+    pub tweet_id: ObjectId,
+    pub claimed_at: Option<DateTime<Utc>>,
+    pub error: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl Draft {
+    /// The request the tweet is published from
+    pub fn request(&self) -> TweetRequest {
+        TweetRequest {
+            message: Some(self.message.clone()),
+            media_ids: self.media_ids.clone(),
+            poll: self.poll.clone(),
+            visibility: Some(self.visibility),
+            reply_policy: Some(self.reply_policy),
+        }
+    }
+
+    /// Transforms <b>Draft</b> to <b>DraftDto</b> using mapping.
+    pub fn map(&self) -> DraftDto {
+        DraftDto {
+            id: self.id.unwrap().to_hex(),
+            message: self.message.clone(),
+            media_ids: self.media_ids.clone(),
+            poll: self.poll.clone(),
+            visibility: self.visibility,
+            reply_policy: self.reply_policy,
+            status: self.status,
+            publish_at: self.publish_at,
+            tweet_id: (self.status == DraftStatus::Published).then(|| self.tweet_id.to_hex()),
+            error: self.error.clone(),
+            created_at: self.created_at,
+            updated_at: self.updated_at,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct DraftRequest {
+    #[serde(flatten)]
+    pub tweet: TweetRequest,
+    pub publish_at: Option<DateTime<Utc>>,
+}
+
+impl DraftRequest {
+    /// Validates the content as a tweet and creates the draft, scheduled when `publish_at` is set
+    pub fn draft(&self, user_id: &str) -> Result<Draft, TweetError> {
+        let tweet = self.tweet.tweet(user_id.to_string())?;
+        self.validate_publish_at()?;
+        let now = Utc::now();
+        Ok(Draft {
+            id: None,
+            user_id: tweet.user_id.unwrap(),
+            message: tweet.message,
+            media_ids: self.tweet.media_ids.clone(),
+            poll: self.tweet.poll.clone(),
+            visibility: tweet.visibility,
+            reply_policy: tweet.reply_policy,
+            status: match self.publish_at {
+                Some(_) => DraftStatus::Scheduled,
+                None => DraftStatus::Draft,
+            },
+            publish_at: self.publish_at,
+            tweet_id: ObjectId::new(),
+            claimed_at: None,
+            error: None,
+            created_at: now,
+            updated_at: now,
+        })
+    }
+
+    fn validate_publish_at(&self) -> Result<(), TweetError> {
+        match self.publish_at {
+            Some(publish_at) if publish_at <= Utc::now() => {
+                Err(TweetError::UnprocessableEntity(vec![FieldError::new(
+                    "publish_at",
+                    "in_past",
+                    "The publish time must be in the future",
+                )]))
+            }
+            _ => Ok(()),
+        }
+    }
+}
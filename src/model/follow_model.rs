@@ -0,0 +1,49 @@
+use chrono::{DateTime, Utc};
+use mongodb::bson::oid::ObjectId;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Follow {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    pub id: Option<ObjectId>,
+    pub follower_id: ObjectId,
+    pub followee_id: ObjectId,
+    pub created_at: DateTime<Utc>,
+}
+
+impl Follow {
+    pub fn new(follower_id: ObjectId, followee_id: ObjectId) -> Self {
+        Follow {
+            id: None,
+            follower_id,
+            followee_id,
+            created_at: Utc::now(),
+        }
+    }
+}
+
+/// A pending request to follow a protected account
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct FollowRequest {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    pub id: Option<ObjectId>,
+    pub requester_id: ObjectId,
+    pub target_id: ObjectId,
+    pub created_at: DateTime<Utc>,
+}
+
+impl FollowRequest {
+    pub fn new(requester_id: ObjectId, target_id: ObjectId) -> Self {
+        FollowRequest {
+            id: None,
+            requester_id,
+            target_id,
+            created_at: Utc::now(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PrivacySettingsRequest {
+    pub protected: bool,
+}
@@ -0,0 +1,44 @@
+use chrono::{DateTime, Utc};
+use mongodb::bson::oid::ObjectId;
+use serde::{Deserialize, Serialize};
+
+/// Header a client sets on a write it may retry
+pub const IDEMPOTENCY_KEY_HEADER: &str = "Idempotency-Key";
+
+/// Header set on responses replayed from a previous request
+pub const IDEMPOTENT_REPLAYED_HEADER: &str = "Idempotent-Replayed";
+
+/// Longest idempotency key accepted
+pub const IDEMPOTENCY_KEY_MAX_LENGTH: usize = 255;
+
+/// Response of the first request made with an idempotency key
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct StoredResponse {
+    pub status: u16,
+    pub content_type: Option<String>,
+    pub body: String,
+}
+
+/// A write made with an idempotency key, unique per user and key
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct IdempotencyRecord {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    pub id: Option<ObjectId>,
+    pub user_id: ObjectId,
+    pub key: String,
+    /// Hash of the method, path and payload of the first request
+    pub request_hash: String,
+    /// Missing while the first request is still in flight
+    pub response: Option<StoredResponse>,
+    pub locked_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+}
+
+/// Outcome of claiming an idempotency key
+#[derive(Debug, Clone)]
+pub enum IdempotencyClaim {
+    /// The key is new, or its previous request was abandoned, the request runs
+    Acquired,
+    /// The key was already used for the same request, its response is sent back
+    Replay(StoredResponse),
+}
@@ -0,0 +1,73 @@
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+
+use crate::{dtos::dto::ImportErrorDto, errors::error::TweetError};
+
+/// Format of the timestamps of a Twitter archive, e.g. `Wed Oct 10 20:19:24 +0000 2018`
+const ARCHIVE_DATE_FORMAT: &str = "%a %b %d %H:%M:%S %z %Y";
+
+/// An entry of a `tweets.js` file, older archives do not wrap the tweet
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum ArchiveEntry {
+    Wrapped { tweet: ArchiveTweet },
+    Bare(ArchiveTweet),
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ArchiveTweet {
+    pub id_str: String,
+    pub full_text: String,
+    pub created_at: String,
+    #[serde(default)]
+    pub in_reply_to_status_id_str: Option<String>,
+}
+
+impl ArchiveTweet {
+    pub fn created_at(&self) -> Result<DateTime<Utc>, String> {
+        DateTime::parse_from_str(&self.created_at, ARCHIVE_DATE_FORMAT)
+            .map(|date| date.with_timezone(&Utc))
+            .map_err(|_| format!("Invalid created_at {}", self.created_at))
+    }
+
+    /// The text of the tweet without the HTML escaping of the archive
+    pub fn message(&self) -> String {
+        self.full_text
+            .replace("&lt;", "<")
+            .replace("&gt;", ">")
+            .replace("&amp;", "&")
+    }
+}
+
+/// Parses a `tweets.js` file, `window.YTD.tweets.part0 = [...]`, into its tweets.
+/// Entries that cannot be read are returned as errors so the others can still be imported.
+pub fn parse_archive(
+    content: &str,
+) -> Result<Vec<Result<ArchiveTweet, ImportErrorDto>>, TweetError> {
+    let content = content.trim();
+    let json = match content.find('=') {
+        Some(index) if !content.starts_with('[') => &content[index + 1..],
+        _ => content,
+    };
+    let entries = serde_json::from_str::<Vec<serde_json::Value>>(json.trim().trim_end_matches(';'))
+        .map_err(|_| TweetError::BadRequest("The archive is not a valid tweets.js file".into()))?;
+    Ok(entries
+        .into_iter()
+        .enumerate()
+        .map(|(index, entry)| {
+            let source_id = entry
+                .pointer("/tweet/id_str")
+                .or_else(|| entry.get("id_str"))
+                .and_then(|id| id.as_str())
+                .map(String::from);
+            match serde_json::from_value::<ArchiveEntry>(entry) {
+                Ok(ArchiveEntry::Wrapped { tweet }) | Ok(ArchiveEntry::Bare(tweet)) => Ok(tweet),
+                Err(_) => Err(ImportErrorDto {
+                    index,
+                    source_id,
+                    message: "Entry is not a valid archive tweet".into(),
+                }),
+            }
+        })
+        .collect())
+}
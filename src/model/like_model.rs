@@ -10,6 +10,8 @@ pub struct Like {
     pub id: Option<ObjectId>,
     pub created_at: DateTime<Utc>,
     pub tweet_id: Option<ObjectId>,
+    #[serde(default)]
+    pub user_id: Option<ObjectId>,
 }
 
 impl Like {
@@ -19,13 +21,15 @@ impl Like {
             id: self.id.unwrap().to_hex(),
             created_at: self.created_at,
             tweet_id: self.tweet_id.unwrap().to_hex(),
+            user_id: self.user_id.map(|id| id.to_hex()),
         }
     }
-    pub fn new(tweet_id: &str) -> Self {
+    pub fn new(tweet_id: &str, user_id: &str) -> Self {
         Self {
             id: Some(ObjectId::new()),
             created_at: Utc::now(),
             tweet_id: Some(ObjectId::parse_str(tweet_id).unwrap()),
+            user_id: Some(ObjectId::parse_str(user_id).unwrap()),
         }
     }
 }
@@ -0,0 +1,79 @@
+use chrono::{DateTime, Utc};
+use mongodb::bson::oid::ObjectId;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    config::settings::{list_description_max_length, list_name_max_length},
+    dtos::dto::ListDto,
+    errors::error::TweetError,
+    validation::content::validate_message,
+};
+
+/// A named list of accounts curated by its owner, read as a timeline
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct UserList {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    pub id: Option<ObjectId>,
+    pub owner_id: ObjectId,
+    pub name: String,
+    pub description: Option<String>,
+    pub private: bool,
+    pub member_ids: Vec<ObjectId>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl UserList {
+    /// Private lists are only visible to their owner
+    pub fn is_visible_to(&self, user_id: &ObjectId) -> bool {
+        !self.private || &self.owner_id == user_id
+    }
+
+    /// Transforms <b>UserList</b> to <b>ListDto</b> using mapping.
+    pub fn map(&self) -> ListDto {
+        ListDto {
+            id: self.id.unwrap().to_hex(),
+            owner_id: self.owner_id.to_hex(),
+            name: self.name.clone(),
+            description: self.description.clone(),
+            private: self.private,
+            member_count: self.member_ids.len(),
+            created_at: self.created_at,
+            updated_at: self.updated_at,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct ListRequest {
+    pub name: Option<String>,
+    pub description: Option<String>,
+    #[serde(default)]
+    pub private: bool,
+}
+
+impl ListRequest {
+    /// Validates the request and creates a list owned by the user, without members
+    pub fn list(&self, owner_id: &str) -> Result<UserList, TweetError> {
+        let owner_id = ObjectId::parse_str(owner_id)
+            .map_err(|_| TweetError::BadRequest("Invalid user Id provided".into()))?;
+        let name = validate_message("name", self.name.as_deref(), list_name_max_length())?;
+        let description = self
+            .description
+            .as_deref()
+            .filter(|d| !d.trim().is_empty())
+            .map(|d| validate_message("description", Some(d), list_description_max_length()))
+            .transpose()?;
+        let now = Utc::now();
+        Ok(UserList {
+            id: None,
+            owner_id,
+            name: name.trim().to_string(),
+            description,
+            private: self.private,
+            member_ids: vec![],
+            created_at: now,
+            updated_at: now,
+        })
+    }
+}
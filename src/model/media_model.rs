@@ -0,0 +1,81 @@
+use chrono::{DateTime, Utc};
+use mongodb::bson::oid::ObjectId;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    dtos::dto::MediaDto, errors::error::TweetError, validation::content::validate_message,
+};
+
+const ALT_TEXT_MAX_LENGTH: usize = 1000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MediaKind {
+    Image,
+    Gif,
+    Video,
+}
+
+/// An uploaded file, stored in the media store under `key`
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Media {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    pub id: Option<ObjectId>,
+    pub user_id: ObjectId,
+    pub kind: MediaKind,
+    pub content_type: String,
+    pub key: String,
+    pub thumbnail_key: Option<String>,
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    pub size: usize,
+    pub alt_text: Option<String>,
+    pub attached: bool,
+    pub created_at: DateTime<Utc>,
+}
+
+impl Media {
+    /// Content type of the thumbnail, GIF thumbnails being a still PNG of the first frame
+    pub fn thumbnail_content_type(&self) -> &str {
+        match self.kind {
+            MediaKind::Gif => "image/png",
+            _ => &self.content_type,
+        }
+    }
+
+    /// Transforms <b>Media</b> to <b>MediaDto</b> using mapping.
+    pub fn map(&self) -> MediaDto {
+        let id = self.id.unwrap().to_hex();
+        MediaDto {
+            url: format!("/api/v1/media/{}", id),
+            thumbnail_url: self
+                .thumbnail_key
+                .as_ref()
+                .map(|_| format!("/api/v1/media/{}/thumbnail", id)),
+            id,
+            kind: self.kind,
+            content_type: self.content_type.clone(),
+            width: self.width,
+            height: self.height,
+            size: self.size,
+            alt_text: self.alt_text.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct MediaRequest {
+    pub alt_text: Option<String>,
+}
+
+impl MediaRequest {
+    /// Validates the alt text, treating an empty one as missing
+    pub fn validated_alt_text(&self) -> Result<Option<String>, TweetError> {
+        match self.alt_text.as_deref() {
+            Some(text) if !text.trim().is_empty() => {
+                validate_message("alt_text", Some(text), ALT_TEXT_MAX_LENGTH).map(Some)
+            }
+            _ => Ok(None),
+        }
+    }
+}
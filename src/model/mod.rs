@@ -1,5 +1,24 @@
+pub mod audience_model;
+pub mod audit_model;
 pub mod auth_model;
+pub mod bookmark_model;
+pub mod dm_model;
 pub mod docs;
+pub mod draft_model;
+pub mod follow_model;
+pub mod idempotency_model;
+pub mod import_model;
 pub mod like_model;
+pub mod list_model;
+pub mod media_model;
+pub mod notification_model;
+pub mod pagination;
+pub mod relation_model;
+pub mod report_model;
+pub mod search_model;
+pub mod spam_model;
 pub mod tweet_comment;
+pub mod tweet_edit;
+pub mod tweet_entity;
 pub mod tweet_model;
+pub mod tweet_poll;
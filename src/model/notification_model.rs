@@ -0,0 +1,177 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use mongodb::bson::oid::ObjectId;
+use serde::{Deserialize, Serialize};
+
+use crate::dtos::dto::NotificationDto;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NotificationKind {
+    Like,
+    Comment,
+    Mention,
+    Follow,
+    Retweet,
+}
+
+impl NotificationKind {
+    /// Describes the action, as in "alice liked your tweet"
+    pub fn action(&self) -> &'static str {
+        match self {
+            NotificationKind::Like => "liked your tweet",
+            NotificationKind::Comment => "replied to your tweet",
+            NotificationKind::Mention => "mentioned you",
+            NotificationKind::Follow => "followed you",
+            NotificationKind::Retweet => "retweeted your tweet",
+        }
+    }
+
+    /// Whether notifications of this kind are grouped, e.g. all likes of one tweet
+    fn is_aggregated(&self) -> bool {
+        matches!(
+            self,
+            NotificationKind::Like | NotificationKind::Follow | NotificationKind::Retweet
+        )
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Notification {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    pub id: Option<ObjectId>,
+    pub user_id: ObjectId,
+    pub actor_id: ObjectId,
+    pub kind: NotificationKind,
+    pub tweet_id: Option<ObjectId>,
+    pub group_key: String,
+    pub read: bool,
+    pub created_at: DateTime<Utc>,
+}
+
+impl Notification {
+    /// Creates a notification for `user_id` about an action `actor_id` performed
+    pub fn new(
+        user_id: ObjectId,
+        actor_id: ObjectId,
+        kind: NotificationKind,
+        tweet_id: Option<ObjectId>,
+    ) -> Self {
+        let id = ObjectId::new();
+        let group_key = if kind.is_aggregated() {
+            let target = tweet_id.map(|t| t.to_hex()).unwrap_or_default();
+            format!("{:?}:{}", kind, target).to_lowercase()
+        } else {
+            id.to_hex()
+        };
+        Notification {
+            id: Some(id),
+            user_id,
+            actor_id,
+            kind,
+            tweet_id,
+            group_key,
+            read: false,
+            created_at: Utc::now(),
+        }
+    }
+}
+
+/// Notifications sharing a `group_key`, as returned by the listing aggregation
+#[derive(Debug, Deserialize)]
+pub struct NotificationGroup {
+    pub id: ObjectId,
+    pub kind: NotificationKind,
+    pub tweet_id: Option<ObjectId>,
+    pub actor_ids: Vec<ObjectId>,
+    pub unread: i32,
+    pub created_at: DateTime<Utc>,
+}
+
+impl NotificationGroup {
+    /// Distinct actors of the group, most recent first
+    pub fn actors(&self) -> Vec<ObjectId> {
+        let mut actors = Vec::<ObjectId>::new();
+        for actor in &self.actor_ids {
+            if !actors.contains(actor) {
+                actors.push(*actor);
+            }
+        }
+        actors
+    }
+
+    /// Transforms <b>NotificationGroup</b> to <b>NotificationDto</b> using mapping,
+    /// naming actors such as "alice and 4 others liked your tweet".
+    pub fn map(&self, usernames: &HashMap<ObjectId, String>) -> NotificationDto {
+        let actors = self.actors();
+        let name = |id: &ObjectId| {
+            usernames
+                .get(id)
+                .map(|u| format!("@{}", u))
+                .unwrap_or_else(|| "Someone".to_string())
+        };
+        let names = match actors.as_slice() {
+            [] => "Someone".to_string(),
+            [first] => name(first),
+            [first, second] => format!("{} and {}", name(first), name(second)),
+            [first, rest @ ..] => format!("{} and {} others", name(first), rest.len()),
+        };
+        NotificationDto {
+            id: self.id.to_hex(),
+            kind: self.kind,
+            tweet_id: self.tweet_id.map(|id| id.to_hex()),
+            actor_ids: actors.iter().map(|id| id.to_hex()).collect(),
+            actor_count: actors.len(),
+            message: format!("{} {}", names, self.kind.action()),
+            read: self.unread == 0,
+            created_at: self.created_at,
+        }
+    }
+}
+
+/// Notification kinds a user wants to receive, all enabled by default
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct NotificationPreferences {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    pub user_id: Option<ObjectId>,
+    #[serde(default = "enabled")]
+    pub like: bool,
+    #[serde(default = "enabled")]
+    pub comment: bool,
+    #[serde(default = "enabled")]
+    pub mention: bool,
+    #[serde(default = "enabled")]
+    pub follow: bool,
+    #[serde(default = "enabled")]
+    pub retweet: bool,
+}
+
+fn enabled() -> bool {
+    true
+}
+
+impl Default for NotificationPreferences {
+    fn default() -> Self {
+        NotificationPreferences {
+            user_id: None,
+            like: true,
+            comment: true,
+            mention: true,
+            follow: true,
+            retweet: true,
+        }
+    }
+}
+
+impl NotificationPreferences {
+    pub fn allows(&self, kind: NotificationKind) -> bool {
+        match kind {
+            NotificationKind::Like => self.like,
+            NotificationKind::Comment => self.comment,
+            NotificationKind::Mention => self.mention,
+            NotificationKind::Follow => self.follow,
+            NotificationKind::Retweet => self.retweet,
+        }
+    }
+}
@@ -0,0 +1,74 @@
+use serde::{Deserialize, Serialize};
+
+const DEFAULT_PAGE_SIZE: u64 = 20;
+const MAX_PAGE_SIZE: u64 = 100;
+
+/// Page based pagination query, `page` starts at 1.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct PageQuery {
+    pub page: Option<u64>,
+    pub limit: Option<u64>,
+}
+
+impl PageQuery {
+    /// Number of items returned per page, capped at `MAX_PAGE_SIZE`
+    pub fn limit(&self) -> u64 {
+        self.limit
+            .unwrap_or(DEFAULT_PAGE_SIZE)
+            .clamp(1, MAX_PAGE_SIZE)
+    }
+
+    /// Number of items to skip to reach the requested page, capped so that huge pages
+    /// cannot overflow or wrap when MongoDB reads it as a signed 64-bit number
+    pub fn skip(&self) -> u64 {
+        (self.page.unwrap_or(1).max(1) - 1)
+            .saturating_mul(self.limit())
+            .min(i64::MAX as u64)
+    }
+}
+
+/// Cursor based pagination query, `cursor` is the `next_cursor` of the previous page.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct CursorQuery {
+    pub cursor: Option<String>,
+    pub limit: Option<u64>,
+}
+
+impl CursorQuery {
+    /// Number of items returned per page, capped at `MAX_PAGE_SIZE`
+    pub fn limit(&self) -> u64 {
+        self.limit
+            .unwrap_or(DEFAULT_PAGE_SIZE)
+            .clamp(1, MAX_PAGE_SIZE)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn page(page: Option<u64>, limit: Option<u64>) -> PageQuery {
+        PageQuery { page, limit }
+    }
+
+    #[test]
+    fn clamps_the_limit() {
+        assert_eq!(page(None, None).limit(), DEFAULT_PAGE_SIZE);
+        assert_eq!(page(None, Some(0)).limit(), 1);
+        assert_eq!(page(None, Some(1000)).limit(), MAX_PAGE_SIZE);
+    }
+
+    #[test]
+    fn skips_the_previous_pages() {
+        assert_eq!(page(None, None).skip(), 0);
+        assert_eq!(page(Some(0), Some(10)).skip(), 0);
+        assert_eq!(page(Some(1), Some(10)).skip(), 0);
+        assert_eq!(page(Some(3), Some(10)).skip(), 20);
+    }
+
+    #[test]
+    fn saturates_huge_pages() {
+        assert_eq!(page(Some(u64::MAX), Some(100)).skip(), i64::MAX as u64);
+        assert_eq!(page(Some(u64::MAX / 2), Some(2)).skip(), i64::MAX as u64);
+    }
+}
@@ -0,0 +1,77 @@
+use chrono::{DateTime, Utc};
+use mongodb::bson::oid::ObjectId;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    config::settings::muted_words_max,
+    errors::error::{FieldError, TweetError},
+};
+
+/// Longest muted word or phrase, in characters
+const MUTED_WORD_MAX_LENGTH: usize = 50;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RelationKind {
+    Block,
+    Mute,
+}
+
+/// A user blocking or muting another user
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Relation {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    pub id: Option<ObjectId>,
+    pub user_id: ObjectId,
+    pub target_id: ObjectId,
+    pub kind: RelationKind,
+    pub created_at: DateTime<Utc>,
+}
+
+impl Relation {
+    pub fn new(user_id: ObjectId, target_id: ObjectId, kind: RelationKind) -> Self {
+        Relation {
+            id: None,
+            user_id,
+            target_id,
+            kind,
+            created_at: Utc::now(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct MutedWordsRequest {
+    pub words: Vec<String>,
+}
+
+impl MutedWordsRequest {
+    /// Returns the trimmed, lowercased and deduplicated words, skipping blank ones
+    pub fn validated_words(&self) -> Result<Vec<String>, TweetError> {
+        let mut words = Vec::<String>::new();
+        for word in &self.words {
+            let word = word.trim().to_lowercase();
+            if word.chars().count() > MUTED_WORD_MAX_LENGTH {
+                return Err(TweetError::UnprocessableEntity(vec![FieldError::new(
+                    "words",
+                    "too_long",
+                    &format!(
+                        "Muted words must not exceed {} characters",
+                        MUTED_WORD_MAX_LENGTH
+                    ),
+                )]));
+            }
+            if !word.is_empty() && !words.contains(&word) {
+                words.push(word);
+            }
+        }
+        if words.len() > muted_words_max() {
+            return Err(TweetError::UnprocessableEntity(vec![FieldError::new(
+                "words",
+                "too_many",
+                &format!("You can mute at most {} words", muted_words_max()),
+            )]));
+        }
+        Ok(words)
+    }
+}
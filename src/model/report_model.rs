@@ -0,0 +1,262 @@
+use chrono::{DateTime, Utc};
+use mongodb::bson::oid::ObjectId;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    config::settings::report_details_max_length,
+    dtos::dto::{ModerationRecordDto, ReportDto},
+    errors::error::{FieldError, TweetError},
+    model::pagination::PageQuery,
+    validation::content::validate_message,
+};
+
+/// What a report is about
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReportTarget {
+    Tweet,
+    Comment,
+    User,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReportReason {
+    Spam,
+    Abuse,
+    Harassment,
+    HateSpeech,
+    Violence,
+    SelfHarm,
+    Misinformation,
+    Other,
+}
+
+/// Where a report is in the moderation workflow
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReportStatus {
+    Open,
+    Triaged,
+    Actioned,
+    Dismissed,
+}
+
+impl ReportStatus {
+    pub fn name(&self) -> &'static str {
+        match self {
+            ReportStatus::Open => "open",
+            ReportStatus::Triaged => "triaged",
+            ReportStatus::Actioned => "actioned",
+            ReportStatus::Dismissed => "dismissed",
+        }
+    }
+
+    /// Statuses a report can move to from this one, actioned and dismissed reports are closed
+    pub fn next(&self) -> &'static [ReportStatus] {
+        match self {
+            ReportStatus::Open => &[
+                ReportStatus::Triaged,
+                ReportStatus::Actioned,
+                ReportStatus::Dismissed,
+            ],
+            ReportStatus::Triaged => &[ReportStatus::Actioned, ReportStatus::Dismissed],
+            ReportStatus::Actioned | ReportStatus::Dismissed => &[],
+        }
+    }
+
+    /// Statuses a report can be in to move to this one
+    pub fn previous(&self) -> Vec<ReportStatus> {
+        [ReportStatus::Open, ReportStatus::Triaged]
+            .into_iter()
+            .filter(|s| s.next().contains(self))
+            .collect()
+    }
+}
+
+/// What a moderator did about the reported content
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ModerationAction {
+    /// Withholds the reported tweet from everyone but its author
+    HideTweet,
+    /// Deletes the reported tweet or comment
+    RemoveContent,
+    /// Suspends the reported user, or the author of the reported content
+    SuspendUser,
+}
+
+/// A status change or action taken on a report, with the acting moderator
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ModerationRecord {
+    pub moderator_id: ObjectId,
+    pub status: ReportStatus,
+    pub action: Option<ModerationAction>,
+    pub note: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// A moderator's decision on a tweet or comment the content filter held for review
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct HeldReview {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    pub id: Option<ObjectId>,
+    pub tweet_id: ObjectId,
+    /// Set when the decision was about one of the tweet's comments
+    pub comment_id: Option<ObjectId>,
+    pub moderator_id: ObjectId,
+    pub approved: bool,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Report {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    pub id: Option<ObjectId>,
+    pub reporter_id: ObjectId,
+    pub target: ReportTarget,
+    pub target_id: ObjectId,
+    /// Tweet the reported comment belongs to
+    pub tweet_id: Option<ObjectId>,
+    /// Author of the reported tweet or comment, or the reported user
+    pub offender_id: Option<ObjectId>,
+    pub reason: ReportReason,
+    pub details: Option<String>,
+    pub status: ReportStatus,
+    pub history: Vec<ModerationRecord>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl Report {
+    /// Transforms <b>Report</b> to <b>ReportDto</b> using mapping.
+    pub fn map(&self) -> ReportDto {
+        ReportDto {
+            id: self.id.unwrap().to_hex(),
+            reporter_id: self.reporter_id.to_hex(),
+            target: self.target,
+            target_id: self.target_id.to_hex(),
+            tweet_id: self.tweet_id.map(|id| id.to_hex()),
+            offender_id: self.offender_id.map(|id| id.to_hex()),
+            reason: self.reason,
+            details: self.details.clone(),
+            status: self.status,
+            history: self
+                .history
+                .iter()
+                .map(|r| ModerationRecordDto {
+                    moderator_id: r.moderator_id.to_hex(),
+                    status: r.status,
+                    action: r.action,
+                    note: r.note.clone(),
+                    created_at: r.created_at,
+                })
+                .collect(),
+            created_at: self.created_at,
+            updated_at: self.updated_at,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct ReportRequest {
+    pub target: ReportTarget,
+    pub target_id: String,
+    /// Required when reporting a comment
+    pub tweet_id: Option<String>,
+    pub reason: ReportReason,
+    pub details: Option<String>,
+}
+
+impl ReportRequest {
+    /// Validates the request and creates an open report, the target is not checked
+    pub fn report(&self, reporter_id: &str) -> Result<Report, TweetError> {
+        let mut errors = Vec::<FieldError>::new();
+        let target_id = ObjectId::parse_str(&self.target_id).ok();
+        if target_id.is_none() {
+            errors.push(FieldError::new(
+                "target_id",
+                "invalid_id",
+                "This field must be a valid id",
+            ));
+        }
+        let tweet_id = self
+            .tweet_id
+            .as_deref()
+            .and_then(|id| ObjectId::parse_str(id).ok());
+        if self.target == ReportTarget::Comment && tweet_id.is_none() {
+            errors.push(FieldError::new(
+                "tweet_id",
+                "required",
+                "The tweet of the reported comment is required",
+            ));
+        }
+        let details = match self.details.as_deref().filter(|d| !d.trim().is_empty()) {
+            Some(details) => {
+                match validate_message("details", Some(details), report_details_max_length()) {
+                    Ok(details) => Some(details),
+                    Err(TweetError::UnprocessableEntity(field_errors)) => {
+                        errors.extend(field_errors);
+                        None
+                    }
+                    Err(err) => return Err(err),
+                }
+            }
+            None => None,
+        };
+        if !errors.is_empty() {
+            return Err(TweetError::UnprocessableEntity(errors));
+        }
+
+        let now = Utc::now();
+        Ok(Report {
+            id: None,
+            reporter_id: ObjectId::parse_str(reporter_id)
+                .map_err(|_| TweetError::BadRequest("Invalid user Id provided".into()))?,
+            target: self.target,
+            target_id: target_id.unwrap(),
+            tweet_id: tweet_id.filter(|_| self.target == ReportTarget::Comment),
+            offender_id: None,
+            reason: self.reason,
+            details,
+            status: ReportStatus::Open,
+            history: vec![],
+            created_at: now,
+            updated_at: now,
+        })
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct ReportQuery {
+    pub status: Option<ReportStatus>,
+    pub page: Option<u64>,
+    pub limit: Option<u64>,
+}
+
+impl ReportQuery {
+    pub fn page_query(&self) -> PageQuery {
+        PageQuery {
+            page: self.page,
+            limit: self.limit,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct ReportStatusRequest {
+    pub status: ReportStatus,
+    pub note: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct ModerationActionRequest {
+    pub action: ModerationAction,
+    pub note: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct HeldReviewRequest {
+    pub comment_id: Option<String>,
+    pub approve: bool,
+}
@@ -0,0 +1,28 @@
+use serde::{Deserialize, Serialize};
+
+use crate::model::pagination::PageQuery;
+
+/// Order of search results
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SearchSort {
+    Relevance,
+    Recent,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct SearchRequest {
+    pub q: String,
+    pub sort: Option<SearchSort>,
+    pub page: Option<u64>,
+    pub limit: Option<u64>,
+}
+
+impl SearchRequest {
+    pub fn page_query(&self) -> PageQuery {
+        PageQuery {
+            page: self.page,
+            limit: self.limit,
+        }
+    }
+}
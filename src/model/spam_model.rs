@@ -0,0 +1,76 @@
+use chrono::{DateTime, Utc};
+use mongodb::bson::oid::ObjectId;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    dtos::dto::SpamEventDto,
+    model::{pagination::PageQuery, report_model::ReportTarget},
+};
+
+/// What the spam heuristics do about a post, ordered from the mildest to the strictest
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SpamDecision {
+    Allow,
+    /// Posted, and logged for a moderator to review
+    Flag,
+    /// Rejected, the user has to slow down
+    Throttle,
+    /// Posted, but the account's tweets are hidden from users not following it
+    ShadowLimit,
+}
+
+/// One heuristic that contributed to the spam score of a post
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct SpamSignal {
+    pub name: String,
+    pub score: u32,
+    pub detail: String,
+}
+
+/// A spam decision other than allow, kept for moderators to review
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct SpamEvent {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    pub id: Option<ObjectId>,
+    pub user_id: ObjectId,
+    pub target: ReportTarget,
+    pub message: String,
+    pub score: u32,
+    pub signals: Vec<SpamSignal>,
+    pub decision: SpamDecision,
+    pub created_at: DateTime<Utc>,
+}
+
+impl SpamEvent {
+    /// Transforms <b>SpamEvent</b> to <b>SpamEventDto</b> using mapping.
+    pub fn map(&self) -> SpamEventDto {
+        SpamEventDto {
+            id: self.id.unwrap().to_hex(),
+            user_id: self.user_id.to_hex(),
+            target: self.target,
+            message: self.message.clone(),
+            score: self.score,
+            signals: self.signals.clone(),
+            decision: self.decision,
+            created_at: self.created_at,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct SpamEventQuery {
+    pub decision: Option<SpamDecision>,
+    pub user_id: Option<String>,
+    pub page: Option<u64>,
+    pub limit: Option<u64>,
+}
+
+impl SpamEventQuery {
+    pub fn page_query(&self) -> PageQuery {
+        PageQuery {
+            page: self.page,
+            limit: self.limit,
+        }
+    }
+}
@@ -2,7 +2,10 @@ use bson::oid::ObjectId;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
-use crate::dtos::dto::CommentDto;
+use crate::{
+    config::settings::comment_max_length, dtos::dto::CommentDto, errors::error::TweetError,
+    validation::content::validate_message,
+};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Comment {
@@ -10,15 +13,26 @@ pub struct Comment {
     pub message: String,
     pub created_at: DateTime<Utc>,
     pub tweet_id: Option<ObjectId>,
+    #[serde(default)]
+    pub user_id: Option<ObjectId>,
+    /// Why the content filter held the comment for review, held comments are not shown
+    #[serde(default)]
+    pub held_reason: Option<String>,
+    /// Id of the reply in the Twitter archive it was imported from
+    #[serde(default)]
+    pub source_id: Option<String>,
 }
 
 impl Comment {
-    pub fn new(tweet_id: &str, message: &str) -> Self {
+    pub fn new(tweet_id: ObjectId, user_id: ObjectId, message: &str) -> Self {
         Self {
             id: Some(ObjectId::new()),
             message: message.to_string(),
             created_at: Utc::now(),
-            tweet_id: Some(ObjectId::parse_str(&tweet_id).unwrap()),
+            tweet_id: Some(tweet_id),
+            user_id: Some(user_id),
+            held_reason: None,
+            source_id: None,
         }
     }
     /// Transforms <b>Comment</b> to <b>CommentDo</b> using mapping.
@@ -28,12 +42,13 @@ impl Comment {
             created_at: self.created_at,
             message: self.message.clone(),
             tweet_id: self.tweet_id.unwrap().to_hex(),
+            user_id: self.user_id.map(|id| id.to_hex()),
         }
     }
 }
 
 pub trait CommentAction {
-    fn comment(&self, tweet_id: &str) -> Option<Comment>;
+    fn comment(&self, tweet_id: &str, user_id: &str) -> Result<Comment, TweetError>;
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -42,10 +57,12 @@ pub struct CommentRequest {
 }
 
 impl CommentAction for CommentRequest {
-    fn comment(&self, tweet_id: &str) -> Option<Comment> {
-        match &self.message {
-            Some(message) => Some(Comment::new(tweet_id, message.as_str())),
-            None => None,
-        }
+    fn comment(&self, tweet_id: &str, user_id: &str) -> Result<Comment, TweetError> {
+        let tweet_id = ObjectId::parse_str(tweet_id)
+            .map_err(|_| TweetError::BadRequest("Invalid tweet Id provided".into()))?;
+        let user_id = ObjectId::parse_str(user_id)
+            .map_err(|_| TweetError::BadRequest("Invalid user Id provided".into()))?;
+        let message = validate_message("message", self.message.as_deref(), comment_max_length())?;
+        Ok(Comment::new(tweet_id, user_id, &message))
     }
 }
@@ -0,0 +1,30 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::dtos::dto::TweetEditDto;
+
+/// A previous version of a tweet message, kept whenever the tweet is edited.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TweetEdit {
+    pub message: String,
+    pub created_at: DateTime<Utc>,
+    pub replaced_at: DateTime<Utc>,
+}
+
+impl TweetEdit {
+    pub fn new(message: &str, created_at: DateTime<Utc>) -> Self {
+        Self {
+            message: message.to_string(),
+            created_at,
+            replaced_at: Utc::now(),
+        }
+    }
+    /// Transforms <b>TweetEdit</b> to <b>TweetEditDto</b> using mapping.
+    pub fn map(&self) -> TweetEditDto {
+        TweetEditDto {
+            message: self.message.clone(),
+            created_at: self.created_at,
+            replaced_at: self.replaced_at,
+        }
+    }
+}
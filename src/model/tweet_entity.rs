@@ -0,0 +1,51 @@
+use mongodb::bson::oid::ObjectId;
+use serde::{Deserialize, Serialize};
+
+use crate::dtos::dto::{EntityDto, TweetEntitiesDto};
+
+/// A hashtag, mention, URL or cashtag found in a tweet message.
+///
+/// `start`/`end` are char offsets and `utf16_start`/`utf16_end` are UTF-16
+/// code unit offsets into the message, both including the leading `#`, `@` or `$`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Entity {
+    pub text: String,
+    pub start: usize,
+    pub end: usize,
+    pub utf16_start: usize,
+    pub utf16_end: usize,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub user_id: Option<ObjectId>,
+}
+
+impl Entity {
+    /// Transforms <b>Entity</b> to <b>EntityDto</b> using mapping.
+    pub fn map(&self) -> EntityDto {
+        EntityDto {
+            text: self.text.clone(),
+            indices: [self.start, self.end],
+            utf16_indices: [self.utf16_start, self.utf16_end],
+            user_id: self.user_id.map(|id| id.to_hex()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TweetEntities {
+    pub hashtags: Vec<Entity>,
+    pub mentions: Vec<Entity>,
+    pub urls: Vec<Entity>,
+    pub cashtags: Vec<Entity>,
+}
+
+impl TweetEntities {
+    /// Transforms <b>TweetEntities</b> to <b>TweetEntitiesDto</b> using mapping.
+    pub fn map(&self) -> TweetEntitiesDto {
+        TweetEntitiesDto {
+            hashtags: self.hashtags.iter().map(|e| e.map()).collect(),
+            mentions: self.mentions.iter().map(|e| e.map()).collect(),
+            urls: self.urls.iter().map(|e| e.map()).collect(),
+            cashtags: self.cashtags.iter().map(|e| e.map()).collect(),
+        }
+    }
+}
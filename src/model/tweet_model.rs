@@ -1,5 +1,17 @@
+use crate::config::settings::{tweet_edit_window, tweet_max_length, tweet_restore_window};
 use crate::dtos::dto::TweetDto;
-use crate::model::{like_model::Like, tweet_comment::Comment};
+use crate::errors::error::TweetError;
+use crate::model::{
+    audience_model::{ReplyPolicy, Visibility},
+    like_model::Like,
+    media_model::Media,
+    tweet_comment::Comment,
+    tweet_edit::TweetEdit,
+    tweet_entity::TweetEntities,
+    tweet_poll::{Poll, PollRequest},
+};
+use crate::text::entities::extract_entities;
+use crate::validation::content::validate_message;
 use chrono::{DateTime, Utc};
 use mongodb::bson::{doc, oid::ObjectId};
 use serde::{Deserialize, Serialize};
@@ -13,6 +25,37 @@ pub struct Tweet {
     pub message: String,
     pub likes: Vec<Like>,
     pub comments: Vec<Comment>,
+    #[serde(default)]
+    pub updated_at: Option<DateTime<Utc>>,
+    /// Incremented on every change, backs ETags and `If-Match`
+    #[serde(default)]
+    pub version: u64,
+    #[serde(default)]
+    pub edit_history: Vec<TweetEdit>,
+    #[serde(default)]
+    pub entities: TweetEntities,
+    #[serde(default)]
+    pub media: Vec<Media>,
+    #[serde(default)]
+    pub poll: Option<Poll>,
+    #[serde(default)]
+    pub visibility: Visibility,
+    #[serde(default)]
+    pub reply_policy: ReplyPolicy,
+    /// Hidden by a moderator from everyone but the author
+    #[serde(default)]
+    pub withheld: bool,
+    /// Why the content filter held the tweet for review, until a moderator reviews it
+    #[serde(default)]
+    pub held_reason: Option<String>,
+    /// Id of the tweet in the Twitter archive it was imported from
+    #[serde(default)]
+    pub source_id: Option<String>,
+    /// When the tweet was deleted, deleted tweets are hidden until restored or purged
+    #[serde(default)]
+    pub deleted_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub deleted_by: Option<ObjectId>,
 }
 
 impl Tweet {
@@ -24,6 +67,19 @@ impl Tweet {
             message: message.to_string(),
             likes: vec![],
             comments: vec![],
+            updated_at: None,
+            version: 0,
+            edit_history: vec![],
+            entities: extract_entities(message),
+            media: vec![],
+            poll: None,
+            visibility: Visibility::Public,
+            reply_policy: ReplyPolicy::Everyone,
+            withheld: false,
+            held_reason: None,
+            source_id: None,
+            deleted_at: None,
+            deleted_by: None,
         }
     }
     /// Transforms <b>Tweet</b> to <b>TweetDto</b> using mapping.
@@ -34,49 +90,139 @@ impl Tweet {
             created_at: self.created_at,
             message: self.message.clone(),
             likes: self.likes.clone().into_iter().map(|l| l.map()).collect(),
-            comments: self.comments.clone().into_iter().map(|c| c.map()).collect(),
+            comments: self
+                .comments
+                .iter()
+                .filter(|c| c.held_reason.is_none())
+                .map(|c| c.map())
+                .collect(),
+            updated_at: self.updated_at,
+            version: self.version,
+            edited: !self.edit_history.is_empty(),
+            edit_count: self.edit_history.len(),
+            entities: self.entities.map(),
+            media: self.media.iter().map(|m| m.map()).collect(),
+            poll: self.poll.as_ref().map(|p| p.map(None)),
+            visibility: self.visibility,
+            reply_policy: self.reply_policy,
+            withheld: self.withheld,
+            is_pinned: false,
+            bookmarked_by_me: false,
+            deleted: false,
         }
     }
 
+    /// Transforms a deleted <b>Tweet</b> to the <b>TweetDto</b> shown in its place.
+    pub fn placeholder(&self) -> TweetDto {
+        TweetDto {
+            id: self.id.unwrap().to_hex(),
+            user_id: self.user_id.unwrap().to_hex(),
+            created_at: self.created_at,
+            message: "This tweet was deleted".into(),
+            likes: vec![],
+            comments: vec![],
+            updated_at: None,
+            version: self.version,
+            edited: false,
+            edit_count: 0,
+            entities: TweetEntities::default().map(),
+            media: vec![],
+            poll: None,
+            visibility: self.visibility,
+            reply_policy: self.reply_policy,
+            withheld: false,
+            is_pinned: false,
+            bookmarked_by_me: false,
+            deleted: true,
+        }
+    }
+
+    /// Checks whether the author deleted the tweet and may still restore it
+    pub fn is_restorable(&self) -> bool {
+        match self.deleted_at {
+            Some(deleted_at) => {
+                self.deleted_by == self.user_id && Utc::now() - deleted_at <= tweet_restore_window()
+            }
+            None => false,
+        }
+    }
+
+    /// Checks whether the tweet was created by the given user
+    pub fn is_author(&self, user_id: &str) -> bool {
+        self.user_id
+            .map(|id| id.to_hex() == user_id)
+            .unwrap_or(false)
+    }
+
+    /// Checks whether the tweet is still within its edit window
+    pub fn is_editable(&self) -> bool {
+        Utc::now() - self.created_at <= tweet_edit_window()
+    }
+
+    /// Replaces the tweet message and its entities, keeping the current version in the edit history
+    pub fn edit(&mut self, message: &str, entities: TweetEntities) {
+        let version_created_at = self.updated_at.unwrap_or(self.created_at);
+        self.edit_history
+            .push(TweetEdit::new(&self.message, version_created_at));
+        self.message = message.to_string();
+        self.entities = entities;
+        self.updated_at = Some(Utc::now());
+        self.version += 1;
+    }
+
     /// Adds like to a tweet
     pub fn add_like(&mut self, like: Like) {
         self.likes.push(like);
+        self.version += 1;
     }
 
     ///Removes like from a tweet
-    pub fn remove_like(&mut self, id: &str) {
-        let _id = ObjectId::parse_str(id).expect("Invalid like id provided");
-        self.likes
-            .retain(|a| !a.id.unwrap().to_hex().eq(&_id.to_hex()));
+    pub fn remove_like(&mut self, id: ObjectId) {
+        self.likes.retain(|l| l.id != Some(id));
+        self.version += 1;
     }
 
     /// Adds comments to tweet
     pub fn add_comment(&mut self, comment: Comment) {
-        self.comments.push(comment)
+        self.comments.push(comment);
+        self.version += 1;
     }
 
     /// Removes comments from a tweet
-    pub fn remove_comment(&mut self, comment_id: &str) {
-        let _id = ObjectId::parse_str(comment_id).expect("Invalid comment id provided");
-        self.comments
-            .retain(|c| !c.id.unwrap().to_hex().eq(&_id.to_hex()));
+    pub fn remove_comment(&mut self, comment_id: ObjectId) {
+        self.comments.retain(|c| c.id != Some(comment_id));
+        self.version += 1;
     }
 }
 
 pub trait TweetActions {
-    fn tweet(&self, user_id: String) -> Option<Tweet>;
+    fn tweet(&self, user_id: String) -> Result<Tweet, TweetError>;
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct TweetRequest {
     pub message: Option<String>,
+    #[serde(default)]
+    pub media_ids: Vec<String>,
+    pub poll: Option<PollRequest>,
+    pub visibility: Option<Visibility>,
+    pub reply_policy: Option<ReplyPolicy>,
+}
+
+impl TweetRequest {
+    /// Validates the request message and returns it normalized
+    pub fn validated_message(&self) -> Result<String, TweetError> {
+        validate_message("message", self.message.as_deref(), tweet_max_length())
+    }
 }
 
 impl TweetActions for TweetRequest {
-    fn tweet(&self, user_id: String) -> Option<Tweet> {
-        match &self.message {
-            Some(message) => Some(Tweet::new(message, &user_id)),
-            None => None,
-        }
+    fn tweet(&self, user_id: String) -> Result<Tweet, TweetError> {
+        let message = self.validated_message()?;
+        let mut tweet = Tweet::new(&message, &user_id);
+        tweet.poll = self.poll.as_ref().map(|p| p.poll()).transpose()?;
+        tweet.visibility = self.visibility.unwrap_or_default();
+        tweet.reply_policy = self.reply_policy.unwrap_or_default();
+        Ok(tweet)
     }
 }
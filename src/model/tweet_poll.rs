@@ -0,0 +1,132 @@
+use bson::oid::ObjectId;
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    dtos::dto::{PollDto, PollOptionDto},
+    errors::error::{FieldError, TweetError},
+    validation::content::validate_message,
+};
+
+const MIN_OPTIONS: usize = 2;
+const MAX_OPTIONS: usize = 4;
+const OPTION_MAX_LENGTH: usize = 25;
+const MIN_DURATION_MINUTES: i64 = 5;
+const MAX_DURATION_MINUTES: i64 = 7 * 24 * 60;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PollOption {
+    pub text: String,
+    pub votes: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PollVote {
+    pub user_id: ObjectId,
+    pub option: usize,
+    pub created_at: DateTime<Utc>,
+}
+
+/// A poll attached to a tweet, with the vote of every user who took part.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Poll {
+    pub options: Vec<PollOption>,
+    pub votes: Vec<PollVote>,
+    pub ends_at: DateTime<Utc>,
+}
+
+impl Poll {
+    pub fn is_closed(&self) -> bool {
+        Utc::now() >= self.ends_at
+    }
+
+    /// Get the option the user voted for
+    pub fn vote_of(&self, user_id: &str) -> Option<usize> {
+        self.votes
+            .iter()
+            .find(|v| v.user_id.to_hex() == user_id)
+            .map(|v| v.option)
+    }
+
+    /// Transforms <b>Poll</b> to <b>PollDto</b> using mapping.
+    /// Tallies are only included once the viewer voted or the poll closed.
+    pub fn map(&self, viewer_id: Option<&str>) -> PollDto {
+        let voted_option = viewer_id.and_then(|id| self.vote_of(id));
+        let revealed = voted_option.is_some() || self.is_closed();
+        PollDto {
+            options: self
+                .options
+                .iter()
+                .map(|o| PollOptionDto {
+                    text: o.text.clone(),
+                    votes: revealed.then_some(o.votes),
+                })
+                .collect(),
+            total_votes: revealed.then_some(self.votes.len() as u64),
+            ends_at: self.ends_at,
+            closed: self.is_closed(),
+            voted_option,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct PollRequest {
+    #[serde(default)]
+    pub options: Vec<String>,
+    pub duration_minutes: Option<i64>,
+}
+
+impl PollRequest {
+    /// Validates the options and duration and creates the poll
+    pub fn poll(&self) -> Result<Poll, TweetError> {
+        let mut errors = Vec::<FieldError>::new();
+        if !(MIN_OPTIONS..=MAX_OPTIONS).contains(&self.options.len()) {
+            errors.push(FieldError::new(
+                "poll.options",
+                "invalid_count",
+                &format!(
+                    "A poll must have between {} and {} options",
+                    MIN_OPTIONS, MAX_OPTIONS
+                ),
+            ));
+        }
+        let mut options = Vec::<PollOption>::new();
+        for (index, option) in self.options.iter().enumerate() {
+            let field = format!("poll.options.{}", index);
+            match validate_message(&field, Some(option), OPTION_MAX_LENGTH) {
+                Ok(text) => options.push(PollOption {
+                    text: text.trim().to_string(),
+                    votes: 0,
+                }),
+                Err(TweetError::UnprocessableEntity(option_errors)) => errors.extend(option_errors),
+                Err(err) => return Err(err),
+            }
+        }
+        let duration = self.duration_minutes.unwrap_or(24 * 60);
+        if !(MIN_DURATION_MINUTES..=MAX_DURATION_MINUTES).contains(&duration) {
+            errors.push(FieldError::new(
+                "poll.duration_minutes",
+                "out_of_range",
+                &format!(
+                    "A poll must last between {} and {} minutes",
+                    MIN_DURATION_MINUTES, MAX_DURATION_MINUTES
+                ),
+            ));
+        }
+
+        if !errors.is_empty() {
+            return Err(TweetError::UnprocessableEntity(errors));
+        }
+        Ok(Poll {
+            options,
+            votes: vec![],
+            ends_at: Utc::now() + Duration::minutes(duration),
+        })
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct VoteRequest {
+    pub option: Option<usize>,
+}
@@ -0,0 +1,110 @@
+use bson::{doc, Document};
+use mongodb::{options::FindOptions, Collection};
+
+use crate::{
+    dtos::dto::AuditEventDto,
+    errors::error::TweetError,
+    model::audit_model::{AuditEvent, AuditQuery},
+};
+
+/// Append only store of audit events, events are never updated or deleted except to
+/// anonymize the events of a deleted user
+pub struct AuditRepo<AuditEvent> {
+    pub collection: Collection<AuditEvent>,
+}
+
+impl AuditRepo<AuditEvent> {
+    /// Appends an event to the audit log. A failure to write is logged rather
+    /// than failing the audited action.
+    pub async fn record(&self, event: AuditEvent) {
+        if let Err(err) = self.collection.insert_one(&event, None).await {
+            log::error!("Failed to write audit event {:?}: {}", event.action, err);
+        }
+    }
+
+    /// Lists one page of the events matching the query, newest first
+    pub async fn events(&self, query: &AuditQuery) -> Result<Vec<AuditEventDto>, TweetError> {
+        let page = query.page_query();
+        let options = FindOptions::builder()
+            .sort(doc! {"created_at": -1})
+            .skip(page.skip())
+            .limit(page.limit() as i64)
+            .build();
+        self.find_events(audit_filter(query), options).await
+    }
+
+    /// Exports every event matching the query as JSON lines, oldest first
+    pub async fn export(&self, query: &AuditQuery) -> Result<String, TweetError> {
+        let options = FindOptions::builder().sort(doc! {"created_at": 1}).build();
+        let events = self.find_events(audit_filter(query), options).await?;
+        let mut lines = String::new();
+        for event in events {
+            let line =
+                serde_json::to_string(&event).map_err(|_| TweetError::InternalServerError)?;
+            lines.push_str(&line);
+            lines.push('\n');
+        }
+        Ok(lines)
+    }
+
+    /// Removes the identity of a deleted user from their events, keeping what happened
+    pub async fn anonymize_user(&self, user_id: &str, email: &str) -> Result<(), TweetError> {
+        self.collection
+            .update_many(
+                doc! {"actor": {"$in": [user_id, email]}},
+                doc! {"$set": {"actor": null, "ip": null, "user_agent": null}},
+                None,
+            )
+            .await
+            .map_err(|_| TweetError::InternalServerError)?;
+        Ok(())
+    }
+
+    async fn find_events(
+        &self,
+        filter: Document,
+        options: FindOptions,
+    ) -> Result<Vec<AuditEventDto>, TweetError> {
+        let mut cursor = self
+            .collection
+            .find(filter, options)
+            .await
+            .map_err(|_| TweetError::InternalServerError)?;
+        let mut events = Vec::<AuditEventDto>::new();
+        while cursor
+            .advance()
+            .await
+            .map_err(|_| TweetError::InternalServerError)?
+        {
+            let event = cursor
+                .deserialize_current()
+                .map_err(|_| TweetError::InternalServerError)?;
+            events.push(event.map());
+        }
+        Ok(events)
+    }
+}
+
+fn audit_filter(query: &AuditQuery) -> Document {
+    let mut filter = doc! {};
+    if let Some(actor) = &query.actor {
+        filter.insert("actor", actor);
+    }
+    if let Some(action) = query.action {
+        filter.insert("action", bson::to_bson(&action).unwrap());
+    }
+    if let Some(outcome) = query.outcome {
+        filter.insert("outcome", bson::to_bson(&outcome).unwrap());
+    }
+    let mut created_at = doc! {};
+    if let Some(since) = query.since {
+        created_at.insert("$gte", bson::to_bson(&since).unwrap());
+    }
+    if let Some(until) = query.until {
+        created_at.insert("$lt", bson::to_bson(&until).unwrap());
+    }
+    if !created_at.is_empty() {
+        filter.insert("created_at", created_at);
+    }
+    filter
+}
@@ -0,0 +1,136 @@
+use bson::{doc, oid::ObjectId, Document};
+use mongodb::{options::FindOptions, Collection};
+
+use crate::{
+    dtos::dto::TweetDto,
+    errors::error::TweetError,
+    model::{bookmark_model::Bookmark, pagination::CursorQuery},
+};
+
+pub struct BookmarkRepo<Bookmark> {
+    pub collection: Collection<Bookmark>,
+}
+
+impl BookmarkRepo<Bookmark> {
+    pub async fn add(&self, user_id: &str, tweet_id: &str) -> Result<(), TweetError> {
+        let user_id = parse_id(user_id, "user")?;
+        let tweet_id = parse_id(tweet_id, "tweet")?;
+        let filter = doc! {"user_id": user_id, "tweet_id": tweet_id};
+        if self.find_bookmarks(filter, None).await?.is_empty() {
+            self.collection
+                .insert_one(Bookmark::new(user_id, tweet_id), None)
+                .await
+                .map_err(|_| TweetError::InternalServerError)?;
+        }
+        Ok(())
+    }
+
+    pub async fn remove(&self, user_id: &str, tweet_id: &str) -> Result<(), TweetError> {
+        let filter = doc! {
+            "user_id": parse_id(user_id, "user")?,
+            "tweet_id": parse_id(tweet_id, "tweet")?
+        };
+        self.collection
+            .delete_one(filter, None)
+            .await
+            .map_err(|_| TweetError::InternalServerError)?;
+        Ok(())
+    }
+
+    /// Get one page of the user's bookmarks, newest first, with the cursor of the next page
+    pub async fn bookmarks(
+        &self,
+        user_id: &str,
+        query: &CursorQuery,
+    ) -> Result<(Vec<Bookmark>, Option<String>), TweetError> {
+        let mut filter = doc! {"user_id": parse_id(user_id, "user")?};
+        if let Some(cursor) = &query.cursor {
+            let cursor = ObjectId::parse_str(cursor)
+                .map_err(|_| TweetError::BadRequest("Invalid cursor provided".into()))?;
+            filter.insert("_id", doc! {"$lt": cursor});
+        }
+        let options = FindOptions::builder()
+            .sort(doc! {"_id": -1})
+            .limit(query.limit() as i64)
+            .build();
+        let bookmarks = self.find_bookmarks(filter, options).await?;
+        let next_cursor = if bookmarks.len() as u64 == query.limit() {
+            bookmarks.last().and_then(|b| b.id).map(|id| id.to_hex())
+        } else {
+            None
+        };
+        Ok((bookmarks, next_cursor))
+    }
+
+    /// Removes the user's bookmarks of tweets that no longer exist
+    pub async fn prune(&self, user_id: &str, tweet_ids: &[ObjectId]) -> Result<(), TweetError> {
+        let filter = doc! {
+            "user_id": parse_id(user_id, "user")?,
+            "tweet_id": {"$in": tweet_ids}
+        };
+        self.collection
+            .delete_many(filter, None)
+            .await
+            .map_err(|_| TweetError::InternalServerError)?;
+        Ok(())
+    }
+
+    /// Sets `bookmarked_by_me` on the tweets the user bookmarked
+    pub async fn mark_bookmarked(
+        &self,
+        user_id: &str,
+        tweets: &mut [TweetDto],
+    ) -> Result<(), TweetError> {
+        let tweet_ids = tweets
+            .iter()
+            .filter_map(|t| ObjectId::parse_str(&t.id).ok())
+            .collect::<Vec<ObjectId>>();
+        let filter = doc! {
+            "user_id": parse_id(user_id, "user")?,
+            "tweet_id": {"$in": tweet_ids}
+        };
+        let bookmarks = self.find_bookmarks(filter, None).await?;
+        for tweet in tweets.iter_mut() {
+            tweet.bookmarked_by_me = bookmarks.iter().any(|b| b.tweet_id.to_hex() == tweet.id);
+        }
+        Ok(())
+    }
+
+    /// Removes every bookmark of a deleted user
+    pub async fn remove_user(&self, user_id: &str) -> Result<(), TweetError> {
+        self.collection
+            .delete_many(doc! {"user_id": parse_id(user_id, "user")?}, None)
+            .await
+            .map_err(|_| TweetError::InternalServerError)?;
+        Ok(())
+    }
+
+    async fn find_bookmarks(
+        &self,
+        filter: Document,
+        options: impl Into<Option<FindOptions>>,
+    ) -> Result<Vec<Bookmark>, TweetError> {
+        let mut cursor = self
+            .collection
+            .find(filter, options)
+            .await
+            .map_err(|_| TweetError::InternalServerError)?;
+        let mut bookmarks = Vec::<Bookmark>::new();
+        while cursor
+            .advance()
+            .await
+            .map_err(|_| TweetError::InternalServerError)?
+        {
+            let bookmark = cursor
+                .deserialize_current()
+                .map_err(|_| TweetError::InternalServerError)?;
+            bookmarks.push(bookmark);
+        }
+        Ok(bookmarks)
+    }
+}
+
+fn parse_id(id: &str, name: &str) -> Result<ObjectId, TweetError> {
+    ObjectId::parse_str(id)
+        .map_err(|_| TweetError::BadRequest(format!("Invalid {} Id provided", name)))
+}
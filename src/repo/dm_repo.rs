@@ -0,0 +1,306 @@
+use std::sync::Arc;
+
+use bson::{doc, oid::ObjectId};
+use chrono::Utc;
+use mongodb::{options::FindOptions, Collection};
+
+use crate::{
+    dtos::dto::{ConversationDto, DirectMessageDto},
+    errors::error::TweetError,
+    model::{
+        dm_model::{Conversation, DirectMessage, ReadReceipt},
+        pagination::PageQuery,
+        relation_model::Relation,
+    },
+    repo::relation_repo::RelationRepo,
+    services::event_bus::{EventBus, EventPayload},
+};
+
+pub struct DmRepo<Conversation> {
+    pub collection: Collection<Conversation>,
+    pub messages: Collection<DirectMessage>,
+    pub events: Arc<EventBus>,
+    pub relations: RelationRepo<Relation>,
+}
+
+impl DmRepo<Conversation> {
+    /// Creates a conversation, reusing the existing one between the same two users
+    pub async fn start_conversation(
+        &self,
+        participant_ids: Vec<ObjectId>,
+    ) -> Result<ConversationDto, TweetError> {
+        if participant_ids.len() == 2 {
+            let filter = doc! {
+                "is_group": false,
+                "participant_ids": {"$all": &participant_ids}
+            };
+            let existing = self
+                .collection
+                .find_one(filter, None)
+                .await
+                .map_err(|_| TweetError::InternalServerError)?;
+            if let Some(conversation) = existing {
+                return Ok(conversation.map());
+            }
+        }
+        let mut conversation = Conversation::new(participant_ids);
+        let result = self
+            .collection
+            .insert_one(&conversation, None)
+            .await
+            .map_err(|_| TweetError::InternalServerError)?;
+        conversation.id = result.inserted_id.as_object_id();
+        Ok(conversation.map())
+    }
+
+    /// Lists the user's conversations, most recently active first
+    pub async fn conversations(
+        &self,
+        user_id: &str,
+        page: &PageQuery,
+    ) -> Result<Vec<ConversationDto>, TweetError> {
+        let filter = doc! {"participant_ids": parse_id(user_id)?};
+        let options = FindOptions::builder()
+            .sort(doc! {"updated_at": -1})
+            .skip(page.skip())
+            .limit(page.limit() as i64)
+            .build();
+        let mut cursor = self
+            .collection
+            .find(filter, options)
+            .await
+            .map_err(|_| TweetError::InternalServerError)?;
+        let mut conversations = Vec::<ConversationDto>::new();
+        while cursor
+            .advance()
+            .await
+            .map_err(|_| TweetError::InternalServerError)?
+        {
+            let conversation = cursor
+                .deserialize_current()
+                .map_err(|_| TweetError::InternalServerError)?;
+            conversations.push(conversation.map());
+        }
+        Ok(conversations)
+    }
+
+    /// Lists a page of messages, newest first, without the ones the user deleted
+    pub async fn messages(
+        &self,
+        conversation_id: &str,
+        user_id: &str,
+        page: &PageQuery,
+    ) -> Result<Vec<DirectMessageDto>, TweetError> {
+        let conversation = self.find_conversation(conversation_id, user_id).await?;
+        let filter = doc! {
+            "conversation_id": conversation.id,
+            "deleted_for": {"$ne": parse_id(user_id)?}
+        };
+        let options = FindOptions::builder()
+            .sort(doc! {"sent_at": -1})
+            .skip(page.skip())
+            .limit(page.limit() as i64)
+            .build();
+        let mut cursor = self
+            .messages
+            .find(filter, options)
+            .await
+            .map_err(|_| TweetError::InternalServerError)?;
+        let mut messages = Vec::<DirectMessageDto>::new();
+        while cursor
+            .advance()
+            .await
+            .map_err(|_| TweetError::InternalServerError)?
+        {
+            let message = cursor
+                .deserialize_current()
+                .map_err(|_| TweetError::InternalServerError)?;
+            messages.push(message.map());
+        }
+        Ok(messages)
+    }
+
+    /// Sends a message, unless the sender and another participant blocked one another
+    pub async fn send_message(
+        &self,
+        conversation_id: &str,
+        user_id: &str,
+        body: &str,
+    ) -> Result<DirectMessageDto, TweetError> {
+        let conversation = self.find_conversation(conversation_id, user_id).await?;
+        for participant_id in &conversation.participant_ids {
+            let participant_id = participant_id.to_hex();
+            if participant_id != user_id
+                && self.relations.is_blocked(user_id, &participant_id).await?
+            {
+                return Err(TweetError::Forbidden(format!(
+                    "User {} does not accept direct messages from you",
+                    participant_id
+                )));
+            }
+        }
+        let conversation_id = conversation.id.unwrap();
+        let message = DirectMessage::new(conversation_id, parse_id(user_id)?, body);
+        self.messages
+            .insert_one(&message, None)
+            .await
+            .map_err(|_| TweetError::InternalServerError)?;
+        self.collection
+            .update_one(
+                doc! {"_id": conversation_id},
+                doc! {"$set": {"updated_at": bson::to_bson(&message.sent_at).unwrap()}},
+                None,
+            )
+            .await
+            .map_err(|_| TweetError::InternalServerError)?;
+
+        let dto = message.map();
+        self.events.publish(EventPayload::DirectMessage {
+            recipient_ids: conversation
+                .participant_ids
+                .iter()
+                .map(|p| p.to_hex())
+                .collect(),
+            message: dto.clone(),
+        });
+        Ok(dto)
+    }
+
+    /// Adds the user's read receipt to every message of the conversation they received
+    pub async fn mark_read(
+        &self,
+        conversation_id: &str,
+        user_id: &str,
+    ) -> Result<String, TweetError> {
+        let conversation = self.find_conversation(conversation_id, user_id).await?;
+        let user_id = parse_id(user_id)?;
+        let receipt = ReadReceipt {
+            user_id,
+            read_at: Utc::now(),
+        };
+        let filter = doc! {
+            "conversation_id": conversation.id,
+            "sender_id": {"$ne": user_id},
+            "read_by.user_id": {"$ne": user_id}
+        };
+        let update = doc! {"$push": {"read_by": bson::to_bson(&receipt).unwrap()}};
+        self.messages
+            .update_many(filter, update, None)
+            .await
+            .map_err(|_| TweetError::InternalServerError)?;
+        Ok("Conversation marked as read".into())
+    }
+
+    /// Hides a message from the user, the other participants still see it
+    pub async fn delete_message(
+        &self,
+        conversation_id: &str,
+        message_id: &str,
+        user_id: &str,
+    ) -> Result<String, TweetError> {
+        let conversation = self.find_conversation(conversation_id, user_id).await?;
+        let message_id = ObjectId::parse_str(message_id)
+            .map_err(|_| TweetError::BadRequest("Invalid message Id provided".into()))?;
+        let filter = doc! {"_id": message_id, "conversation_id": conversation.id};
+        let update = doc! {"$addToSet": {"deleted_for": parse_id(user_id)?}};
+        let result = self
+            .messages
+            .update_one(filter, update, None)
+            .await
+            .map_err(|_| TweetError::InternalServerError)?;
+        if result.matched_count == 0 {
+            return Err(TweetError::NotFound("Message not found".into()));
+        }
+        Ok("Message deleted".into())
+    }
+
+    /// Get every conversation of the user and the messages they can see, for their data export
+    pub async fn export(
+        &self,
+        user_id: &str,
+    ) -> Result<(Vec<ConversationDto>, Vec<DirectMessageDto>), TweetError> {
+        let user_id = parse_id(user_id)?;
+        let mut cursor = self
+            .collection
+            .find(doc! {"participant_ids": user_id}, None)
+            .await
+            .map_err(|_| TweetError::InternalServerError)?;
+        let mut conversations = Vec::<Conversation>::new();
+        while cursor
+            .advance()
+            .await
+            .map_err(|_| TweetError::InternalServerError)?
+        {
+            let conversation = cursor
+                .deserialize_current()
+                .map_err(|_| TweetError::InternalServerError)?;
+            conversations.push(conversation);
+        }
+
+        let conversation_ids = conversations
+            .iter()
+            .filter_map(|c| c.id)
+            .collect::<Vec<ObjectId>>();
+        let filter = doc! {
+            "conversation_id": {"$in": conversation_ids},
+            "deleted_for": {"$ne": user_id}
+        };
+        let options = FindOptions::builder().sort(doc! {"sent_at": 1}).build();
+        let mut cursor = self
+            .messages
+            .find(filter, options)
+            .await
+            .map_err(|_| TweetError::InternalServerError)?;
+        let mut messages = Vec::<DirectMessageDto>::new();
+        while cursor
+            .advance()
+            .await
+            .map_err(|_| TweetError::InternalServerError)?
+        {
+            let message = cursor
+                .deserialize_current()
+                .map_err(|_| TweetError::InternalServerError)?;
+            messages.push(message.map());
+        }
+        Ok((conversations.iter().map(|c| c.map()).collect(), messages))
+    }
+
+    /// Deletes the messages a deleted user sent and removes them from their conversations
+    pub async fn remove_user(&self, user_id: &str) -> Result<(), TweetError> {
+        let user_id = parse_id(user_id)?;
+        self.messages
+            .delete_many(doc! {"sender_id": user_id}, None)
+            .await
+            .map_err(|_| TweetError::InternalServerError)?;
+        self.collection
+            .update_many(
+                doc! {"participant_ids": user_id},
+                doc! {"$pull": {"participant_ids": user_id}},
+                None,
+            )
+            .await
+            .map_err(|_| TweetError::InternalServerError)?;
+        Ok(())
+    }
+
+    /// Get a conversation the user participates in
+    async fn find_conversation(&self, id: &str, user_id: &str) -> Result<Conversation, TweetError> {
+        let id = ObjectId::parse_str(id)
+            .map_err(|_| TweetError::BadRequest("Invalid conversation Id provided".into()))?;
+        let conversation = self
+            .collection
+            .find_one(doc! {"_id": id}, None)
+            .await
+            .map_err(|_| TweetError::InternalServerError)?;
+        match conversation {
+            Some(conversation) if conversation.has_participant(&parse_id(user_id)?) => {
+                Ok(conversation)
+            }
+            _ => Err(TweetError::NotFound("Conversation not found".into())),
+        }
+    }
+}
+
+fn parse_id(id: &str) -> Result<ObjectId, TweetError> {
+    ObjectId::parse_str(id).map_err(|_| TweetError::BadRequest("Invalid user Id provided".into()))
+}
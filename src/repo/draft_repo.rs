@@ -0,0 +1,253 @@
+use bson::{doc, oid::ObjectId, Document};
+use chrono::{Duration, Utc};
+use mongodb::{
+    options::{FindOneAndUpdateOptions, FindOptions, ReturnDocument},
+    Collection,
+};
+
+use crate::{
+    dtos::dto::DraftDto,
+    errors::error::TweetError,
+    model::{
+        draft_model::{Draft, DraftStatus},
+        pagination::PageQuery,
+    },
+};
+
+/// Time after which a draft claimed by a publisher that never finished is claimed again
+const CLAIM_TIMEOUT_MINUTES: i64 = 5;
+
+pub struct DraftRepo<Draft> {
+    pub collection: Collection<Draft>,
+}
+
+impl DraftRepo<Draft> {
+    pub async fn create(&self, mut draft: Draft) -> Result<DraftDto, TweetError> {
+        let result = self
+            .collection
+            .insert_one(&draft, None)
+            .await
+            .map_err(|_| TweetError::InternalServerError)?;
+        draft.id = result.inserted_id.as_object_id();
+        Ok(draft.map())
+    }
+
+    /// Lists the user's unpublished drafts, either the scheduled ones or the others
+    pub async fn drafts(
+        &self,
+        user_id: &str,
+        scheduled: bool,
+        page: &PageQuery,
+    ) -> Result<Vec<DraftDto>, TweetError> {
+        let publish_at = if scheduled {
+            doc! {"$ne": null}
+        } else {
+            doc! {"$eq": null}
+        };
+        let filter = doc! {
+            "user_id": parse_id(user_id, "user")?,
+            "status": {"$ne": status(DraftStatus::Published)},
+            "publish_at": publish_at
+        };
+        let options = FindOptions::builder()
+            .sort(if scheduled {
+                doc! {"publish_at": 1}
+            } else {
+                doc! {"updated_at": -1}
+            })
+            .skip(page.skip())
+            .limit(page.limit() as i64)
+            .build();
+        let mut cursor = self
+            .collection
+            .find(filter, options)
+            .await
+            .map_err(|_| TweetError::InternalServerError)?;
+        let mut drafts = Vec::<DraftDto>::new();
+        while cursor
+            .advance()
+            .await
+            .map_err(|_| TweetError::InternalServerError)?
+        {
+            let draft = cursor
+                .deserialize_current()
+                .map_err(|_| TweetError::InternalServerError)?;
+            drafts.push(draft.map());
+        }
+        Ok(drafts)
+    }
+
+    /// Get a draft of the user
+    pub async fn find_draft(&self, id: &str, user_id: &str) -> Result<Draft, TweetError> {
+        let filter = doc! {"_id": parse_id(id, "draft")?, "user_id": parse_id(user_id, "user")?};
+        let draft = self
+            .collection
+            .find_one(filter, None)
+            .await
+            .map_err(|_| TweetError::InternalServerError)?;
+        match draft {
+            Some(draft) => Ok(draft),
+            None => Err(TweetError::NotFound(format!(
+                "No draft with id {} found",
+                id
+            ))),
+        }
+    }
+
+    /// Replaces the content and schedule of a draft that is not being published
+    pub async fn update(
+        &self,
+        id: &str,
+        user_id: &str,
+        draft: Draft,
+    ) -> Result<DraftDto, TweetError> {
+        let current = self.find_draft(id, user_id).await?;
+        let update = doc! {
+            "$set": {
+                "message": &draft.message,
+                "media_ids": &draft.media_ids,
+                "poll": bson::to_bson(&draft.poll).unwrap(),
+                "visibility": bson::to_bson(&draft.visibility).unwrap(),
+                "reply_policy": bson::to_bson(&draft.reply_policy).unwrap(),
+                "status": status(draft.status),
+                "publish_at": bson::to_bson(&draft.publish_at).unwrap(),
+                "error": null,
+                "updated_at": bson::to_bson(&draft.updated_at).unwrap()
+            }
+        };
+        let options = FindOneAndUpdateOptions::builder()
+            .return_document(ReturnDocument::After)
+            .build();
+        let updated = self
+            .collection
+            .find_one_and_update(editable(current.id), update, options)
+            .await
+            .map_err(|_| TweetError::InternalServerError)?;
+        match updated {
+            Some(draft) => Ok(draft.map()),
+            None => Err(not_editable()),
+        }
+    }
+
+    pub async fn delete(&self, id: &str, user_id: &str) -> Result<(), TweetError> {
+        let current = self.find_draft(id, user_id).await?;
+        let result = self
+            .collection
+            .delete_one(editable(current.id), None)
+            .await
+            .map_err(|_| TweetError::InternalServerError)?;
+        if result.deleted_count == 0 {
+            return Err(not_editable());
+        }
+        Ok(())
+    }
+
+    /// Claims a draft of the user for publishing now
+    pub async fn claim(&self, id: &str, user_id: &str) -> Result<Draft, TweetError> {
+        let current = self.find_draft(id, user_id).await?;
+        match self.claim_one(editable(current.id)).await? {
+            Some(draft) => Ok(draft),
+            None => Err(not_editable()),
+        }
+    }
+
+    /// Claims the next scheduled draft that is due, or one whose publisher never finished
+    pub async fn claim_due(&self) -> Result<Option<Draft>, TweetError> {
+        let now = Utc::now();
+        let timeout = now - Duration::minutes(CLAIM_TIMEOUT_MINUTES);
+        let filter = doc! {
+            "$or": [
+                {
+                    "status": status(DraftStatus::Scheduled),
+                    "publish_at": {"$lte": bson::to_bson(&now).unwrap()}
+                },
+                {
+                    "status": status(DraftStatus::Publishing),
+                    "claimed_at": {"$lte": bson::to_bson(&timeout).unwrap()}
+                }
+            ]
+        };
+        self.claim_one(filter).await
+    }
+
+    pub async fn mark_published(&self, draft: &Draft) -> Result<(), TweetError> {
+        self.set_status(draft, DraftStatus::Published, None).await
+    }
+
+    pub async fn mark_failed(&self, draft: &Draft, error: String) -> Result<(), TweetError> {
+        self.set_status(draft, DraftStatus::Failed, Some(error))
+            .await
+    }
+
+    /// Removes every draft of a deleted user, scheduled ones included
+    pub async fn remove_user(&self, user_id: &str) -> Result<(), TweetError> {
+        self.collection
+            .delete_many(doc! {"user_id": parse_id(user_id, "user")?}, None)
+            .await
+            .map_err(|_| TweetError::InternalServerError)?;
+        Ok(())
+    }
+
+    async fn set_status(
+        &self,
+        draft: &Draft,
+        draft_status: DraftStatus,
+        error: Option<String>,
+    ) -> Result<(), TweetError> {
+        let update = doc! {
+            "$set": {
+                "status": status(draft_status),
+                "error": error,
+                "updated_at": bson::to_bson(&Utc::now()).unwrap()
+            }
+        };
+        self.collection
+            .update_one(doc! {"_id": draft.id}, update, None)
+            .await
+            .map_err(|_| TweetError::InternalServerError)?;
+        Ok(())
+    }
+
+    /// Atomically moves the first draft matching the filter to `Publishing`
+    async fn claim_one(&self, filter: Document) -> Result<Option<Draft>, TweetError> {
+        let update = doc! {
+            "$set": {
+                "status": status(DraftStatus::Publishing),
+                "claimed_at": bson::to_bson(&Utc::now()).unwrap()
+            }
+        };
+        let options = FindOneAndUpdateOptions::builder()
+            .return_document(ReturnDocument::After)
+            .build();
+        self.collection
+            .find_one_and_update(filter, update, options)
+            .await
+            .map_err(|_| TweetError::InternalServerError)
+    }
+}
+
+/// Filter matching the draft only while it can be changed
+fn editable(id: Option<ObjectId>) -> Document {
+    let statuses = [
+        DraftStatus::Draft,
+        DraftStatus::Scheduled,
+        DraftStatus::Failed,
+    ];
+    doc! {
+        "_id": id,
+        "status": {"$in": statuses.iter().map(|s| status(*s)).collect::<Vec<_>>()}
+    }
+}
+
+fn not_editable() -> TweetError {
+    TweetError::Forbidden("This draft is being published or was already published".into())
+}
+
+fn status(status: DraftStatus) -> bson::Bson {
+    bson::to_bson(&status).unwrap()
+}
+
+fn parse_id(id: &str, name: &str) -> Result<ObjectId, TweetError> {
+    ObjectId::parse_str(id)
+        .map_err(|_| TweetError::BadRequest(format!("Invalid {} Id provided", name)))
+}
@@ -0,0 +1,252 @@
+use bson::{doc, oid::ObjectId, Document};
+use mongodb::{options::FindOptions, Collection};
+
+use crate::{
+    errors::error::TweetError,
+    model::{
+        follow_model::{Follow, FollowRequest},
+        notification_model::{Notification, NotificationKind},
+    },
+    repo::notification_repo::NotificationRepo,
+};
+
+#[derive(Clone)]
+pub struct FollowRepo<Follow> {
+    pub collection: Collection<Follow>,
+    pub requests: Collection<FollowRequest>,
+    pub notifications: NotificationRepo<Notification>,
+}
+
+impl FollowRepo<Follow> {
+    pub async fn follow(&self, follower_id: &str, followee_id: &str) -> Result<String, TweetError> {
+        let follower_id = parse_id(follower_id)?;
+        let followee_id = parse_id(followee_id)?;
+        if follower_id == followee_id {
+            return Err(TweetError::BadRequest("You cannot follow yourself".into()));
+        }
+        if self.exists(follower_id, followee_id).await? {
+            return Ok("Already following".into());
+        }
+        self.collection
+            .insert_one(Follow::new(follower_id, followee_id), None)
+            .await
+            .map_err(|_| TweetError::InternalServerError)?;
+        let notification =
+            Notification::new(followee_id, follower_id, NotificationKind::Follow, None);
+        if let Err(err) = self.notifications.notify(notification).await {
+            log::error!("Failed to create notification: {}", err);
+        }
+        Ok("Followed successfully".into())
+    }
+
+    pub async fn unfollow(
+        &self,
+        follower_id: &str,
+        followee_id: &str,
+    ) -> Result<String, TweetError> {
+        let follower_id = parse_id(follower_id)?;
+        let followee_id = parse_id(followee_id)?;
+        let filter = doc! {"follower_id": follower_id, "followee_id": followee_id};
+        self.collection
+            .delete_one(filter, None)
+            .await
+            .map_err(|_| TweetError::InternalServerError)?;
+        let filter = doc! {"requester_id": follower_id, "target_id": followee_id};
+        self.requests
+            .delete_one(filter, None)
+            .await
+            .map_err(|_| TweetError::InternalServerError)?;
+        Ok("Unfollowed successfully".into())
+    }
+
+    /// Asks a protected account for approval to follow it
+    pub async fn request_follow(
+        &self,
+        requester_id: &str,
+        target_id: &str,
+    ) -> Result<String, TweetError> {
+        let requester_id = parse_id(requester_id)?;
+        let target_id = parse_id(target_id)?;
+        if requester_id == target_id {
+            return Err(TweetError::BadRequest("You cannot follow yourself".into()));
+        }
+        if self.exists(requester_id, target_id).await? {
+            return Ok("Already following".into());
+        }
+        let filter = doc! {"requester_id": requester_id, "target_id": target_id};
+        let pending = self
+            .requests
+            .find_one(filter, None)
+            .await
+            .map_err(|_| TweetError::InternalServerError)?;
+        if pending.is_some() {
+            return Ok("Follow request already sent".into());
+        }
+        self.requests
+            .insert_one(FollowRequest::new(requester_id, target_id), None)
+            .await
+            .map_err(|_| TweetError::InternalServerError)?;
+        Ok("Follow request sent".into())
+    }
+
+    /// Get the pending follow requests sent to the user, newest first
+    pub async fn follow_requests(&self, user_id: &str) -> Result<Vec<FollowRequest>, TweetError> {
+        let options = FindOptions::builder().sort(doc! {"created_at": -1}).build();
+        let mut cursor = self
+            .requests
+            .find(doc! {"target_id": parse_id(user_id)?}, options)
+            .await
+            .map_err(|_| TweetError::InternalServerError)?;
+        let mut requests = Vec::<FollowRequest>::new();
+        while cursor
+            .advance()
+            .await
+            .map_err(|_| TweetError::InternalServerError)?
+        {
+            let request = cursor
+                .deserialize_current()
+                .map_err(|_| TweetError::InternalServerError)?;
+            requests.push(request);
+        }
+        Ok(requests)
+    }
+
+    /// Approves a follow request sent to the user, making the requester a follower
+    pub async fn approve_request(
+        &self,
+        user_id: &str,
+        request_id: &str,
+    ) -> Result<String, TweetError> {
+        let request = self.take_request(user_id, request_id).await?;
+        self.follow(&request.requester_id.to_hex(), user_id).await?;
+        Ok("Follow request approved".into())
+    }
+
+    /// Rejects a follow request sent to the user
+    pub async fn reject_request(
+        &self,
+        user_id: &str,
+        request_id: &str,
+    ) -> Result<String, TweetError> {
+        self.take_request(user_id, request_id).await?;
+        Ok("Follow request rejected".into())
+    }
+
+    /// Approves every pending follow request, used when an account stops being protected
+    pub async fn approve_all(&self, user_id: &str) -> Result<(), TweetError> {
+        for request in self.follow_requests(user_id).await? {
+            if let Some(id) = request.id {
+                self.approve_request(user_id, &id.to_hex()).await?;
+            }
+        }
+        Ok(())
+    }
+
+    pub async fn is_following(
+        &self,
+        follower_id: &str,
+        followee_id: &str,
+    ) -> Result<bool, TweetError> {
+        self.exists(parse_id(follower_id)?, parse_id(followee_id)?)
+            .await
+    }
+
+    /// Checks whether both users follow each other
+    pub async fn is_mutual(&self, user_id: &str, other_id: &str) -> Result<bool, TweetError> {
+        Ok(self.is_following(user_id, other_id).await?
+            && self.is_following(other_id, user_id).await?)
+    }
+
+    /// Get the ids of the users following the user
+    pub async fn follower_ids(&self, user_id: &str) -> Result<Vec<ObjectId>, TweetError> {
+        let follows = self
+            .find_follows(doc! {"followee_id": parse_id(user_id)?})
+            .await?;
+        Ok(follows.iter().map(|f| f.follower_id).collect())
+    }
+
+    /// Get the ids of the users the user follows
+    pub async fn following_ids(&self, user_id: &str) -> Result<Vec<ObjectId>, TweetError> {
+        let follows = self
+            .find_follows(doc! {"follower_id": parse_id(user_id)?})
+            .await?;
+        Ok(follows.iter().map(|f| f.followee_id).collect())
+    }
+
+    /// Removes every follow and pending follow request of a deleted user
+    pub async fn remove_user(&self, user_id: &str) -> Result<(), TweetError> {
+        let user_id = parse_id(user_id)?;
+        self.collection
+            .delete_many(
+                doc! {"$or": [{"follower_id": user_id}, {"followee_id": user_id}]},
+                None,
+            )
+            .await
+            .map_err(|_| TweetError::InternalServerError)?;
+        self.requests
+            .delete_many(
+                doc! {"$or": [{"requester_id": user_id}, {"target_id": user_id}]},
+                None,
+            )
+            .await
+            .map_err(|_| TweetError::InternalServerError)?;
+        Ok(())
+    }
+
+    /// Removes a pending request sent to the user and returns it
+    async fn take_request(
+        &self,
+        user_id: &str,
+        request_id: &str,
+    ) -> Result<FollowRequest, TweetError> {
+        let id = ObjectId::parse_str(request_id)
+            .map_err(|_| TweetError::BadRequest("Invalid request Id provided".into()))?;
+        let filter = doc! {"_id": id, "target_id": parse_id(user_id)?};
+        let request = self
+            .requests
+            .find_one_and_delete(filter, None)
+            .await
+            .map_err(|_| TweetError::InternalServerError)?;
+        request.ok_or_else(|| {
+            TweetError::NotFound(format!("No follow request with id {} found", request_id))
+        })
+    }
+
+    async fn exists(
+        &self,
+        follower_id: ObjectId,
+        followee_id: ObjectId,
+    ) -> Result<bool, TweetError> {
+        let filter = doc! {"follower_id": follower_id, "followee_id": followee_id};
+        let follow = self
+            .collection
+            .find_one(filter, None)
+            .await
+            .map_err(|_| TweetError::InternalServerError)?;
+        Ok(follow.is_some())
+    }
+
+    async fn find_follows(&self, filter: Document) -> Result<Vec<Follow>, TweetError> {
+        let mut cursor = self
+            .collection
+            .find(filter, None)
+            .await
+            .map_err(|_| TweetError::InternalServerError)?;
+        let mut follows = Vec::<Follow>::new();
+        while cursor
+            .advance()
+            .await
+            .map_err(|_| TweetError::InternalServerError)?
+        {
+            let follow = cursor
+                .deserialize_current()
+                .map_err(|_| TweetError::InternalServerError)?;
+            follows.push(follow);
+        }
+        Ok(follows)
+    }
+}
+
+fn parse_id(id: &str) -> Result<ObjectId, TweetError> {
+    ObjectId::parse_str(id).map_err(|_| TweetError::BadRequest("Invalid user Id provided".into()))
+}
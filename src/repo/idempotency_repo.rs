@@ -0,0 +1,177 @@
+use bson::{doc, oid::ObjectId, Document};
+use chrono::{DateTime, Utc};
+use mongodb::{
+    options::{FindOneAndUpdateOptions, IndexOptions, ReturnDocument},
+    Collection, IndexModel,
+};
+
+use crate::{
+    config::settings::{idempotency_key_ttl, idempotency_lock_timeout},
+    errors::error::{FieldError, TweetError},
+    model::idempotency_model::{
+        IdempotencyClaim, IdempotencyRecord, StoredResponse, IDEMPOTENCY_KEY_HEADER,
+    },
+};
+
+pub struct IdempotencyRepo<IdempotencyRecord> {
+    pub collection: Collection<IdempotencyRecord>,
+}
+
+impl IdempotencyRepo<IdempotencyRecord> {
+    /// Creates the unique index making a key claimable only once per user
+    pub async fn create_indexes(&self) -> Result<(), TweetError> {
+        let options = IndexOptions::builder().unique(true).build();
+        let index = IndexModel::builder()
+            .keys(doc! {"user_id": 1, "key": 1})
+            .options(options)
+            .build();
+        self.collection
+            .create_index(index, None)
+            .await
+            .map_err(|_| TweetError::InternalServerError)?;
+        Ok(())
+    }
+
+    /// Claims the key for a request, or tells which stored response to send back
+    pub async fn claim(
+        &self,
+        user_id: &str,
+        key: &str,
+        request_hash: &str,
+    ) -> Result<IdempotencyClaim, TweetError> {
+        let user_id = parse_user_id(user_id)?;
+        let now = Utc::now();
+        let update = doc! {
+            "$setOnInsert": {
+                "request_hash": request_hash,
+                "response": null,
+                "locked_at": bson::to_bson(&now).unwrap(),
+                "expires_at": bson::to_bson(&(now + idempotency_key_ttl())).unwrap()
+            }
+        };
+        let options = FindOneAndUpdateOptions::builder()
+            .upsert(true)
+            .return_document(ReturnDocument::Before)
+            .build();
+        let existing = match self
+            .collection
+            .find_one_and_update(key_filter(user_id, key), update, options)
+            .await
+        {
+            Ok(existing) => existing,
+            // A concurrent request inserted the key first
+            Err(_) => match self
+                .collection
+                .find_one(key_filter(user_id, key), None)
+                .await
+            {
+                Ok(Some(record)) => Some(record),
+                _ => return Err(TweetError::InternalServerError),
+            },
+        };
+        let record = match existing {
+            Some(record) => record,
+            None => return Ok(IdempotencyClaim::Acquired),
+        };
+        if record.expires_at <= now {
+            return self.take_over(&record, request_hash).await;
+        }
+        if record.request_hash != request_hash {
+            return Err(TweetError::UnprocessableEntity(vec![FieldError::new(
+                IDEMPOTENCY_KEY_HEADER,
+                "reused",
+                "The idempotency key was already used for a different request",
+            )]));
+        }
+        match record.response {
+            Some(response) => Ok(IdempotencyClaim::Replay(response)),
+            None if record.locked_at <= now - idempotency_lock_timeout() => {
+                self.take_over(&record, request_hash).await
+            }
+            None => Err(in_progress()),
+        }
+    }
+
+    /// Stores the response sent for the request holding the key
+    pub async fn complete(
+        &self,
+        user_id: &str,
+        key: &str,
+        response: &StoredResponse,
+    ) -> Result<(), TweetError> {
+        let user_id = parse_user_id(user_id)?;
+        let update = doc! {"$set": {"response": bson::to_bson(response).unwrap()}};
+        self.collection
+            .update_one(key_filter(user_id, key), update, None)
+            .await
+            .map_err(|_| TweetError::InternalServerError)?;
+        Ok(())
+    }
+
+    /// Frees a key whose request failed so that a retry runs it again
+    pub async fn release(&self, user_id: &str, key: &str) -> Result<(), TweetError> {
+        let user_id = parse_user_id(user_id)?;
+        let mut filter = key_filter(user_id, key);
+        filter.insert("response", bson::Bson::Null);
+        self.collection
+            .delete_one(filter, None)
+            .await
+            .map_err(|_| TweetError::InternalServerError)?;
+        Ok(())
+    }
+
+    /// Deletes the keys that expired before `now`, returning how many were deleted
+    pub async fn purge_expired(&self, now: DateTime<Utc>) -> Result<u64, TweetError> {
+        let filter = doc! {"expires_at": {"$lte": bson::to_bson(&now).unwrap()}};
+        let result = self
+            .collection
+            .delete_many(filter, None)
+            .await
+            .map_err(|_| TweetError::InternalServerError)?;
+        Ok(result.deleted_count)
+    }
+
+    /// Claims an expired or abandoned key, unless another request claimed it first
+    async fn take_over(
+        &self,
+        record: &IdempotencyRecord,
+        request_hash: &str,
+    ) -> Result<IdempotencyClaim, TweetError> {
+        let now = Utc::now();
+        let filter = doc! {
+            "_id": record.id,
+            "locked_at": bson::to_bson(&record.locked_at).unwrap()
+        };
+        let update = doc! {
+            "$set": {
+                "request_hash": request_hash,
+                "response": null,
+                "locked_at": bson::to_bson(&now).unwrap(),
+                "expires_at": bson::to_bson(&(now + idempotency_key_ttl())).unwrap()
+            }
+        };
+        let result = self
+            .collection
+            .update_one(filter, update, None)
+            .await
+            .map_err(|_| TweetError::InternalServerError)?;
+        if result.modified_count == 1 {
+            Ok(IdempotencyClaim::Acquired)
+        } else {
+            Err(in_progress())
+        }
+    }
+}
+
+fn key_filter(user_id: ObjectId, key: &str) -> Document {
+    doc! {"user_id": user_id, "key": key}
+}
+
+fn in_progress() -> TweetError {
+    TweetError::Conflict("A request with this idempotency key is still in progress".into())
+}
+
+fn parse_user_id(user_id: &str) -> Result<ObjectId, TweetError> {
+    ObjectId::parse_str(user_id)
+        .map_err(|_| TweetError::Unauthorized("authentication error occurred".into()))
+}
@@ -0,0 +1,206 @@
+use bson::{doc, oid::ObjectId, Document};
+use chrono::Utc;
+use mongodb::{options::FindOptions, Collection};
+
+use crate::{
+    config::settings::list_max_members,
+    dtos::dto::ListDto,
+    errors::error::{FieldError, TweetError},
+    model::{list_model::UserList, pagination::PageQuery},
+};
+
+pub struct ListRepo<UserList> {
+    pub collection: Collection<UserList>,
+}
+
+impl ListRepo<UserList> {
+    pub async fn create(&self, mut list: UserList) -> Result<ListDto, TweetError> {
+        let result = self
+            .collection
+            .insert_one(&list, None)
+            .await
+            .map_err(|_| TweetError::InternalServerError)?;
+        list.id = result.inserted_id.as_object_id();
+        Ok(list.map())
+    }
+
+    /// Lists the lists owned by a user, only the public ones unless the viewer is the owner
+    pub async fn lists(
+        &self,
+        owner_id: &str,
+        viewer_id: &str,
+        page: &PageQuery,
+    ) -> Result<Vec<ListDto>, TweetError> {
+        let mut filter = doc! {"owner_id": parse_id(owner_id, "user")?};
+        if owner_id != viewer_id {
+            filter.insert("private", false);
+        }
+        let options = FindOptions::builder()
+            .sort(doc! {"created_at": -1})
+            .skip(page.skip())
+            .limit(page.limit() as i64)
+            .build();
+        let mut cursor = self
+            .collection
+            .find(filter, options)
+            .await
+            .map_err(|_| TweetError::InternalServerError)?;
+        let mut lists = Vec::<ListDto>::new();
+        while cursor
+            .advance()
+            .await
+            .map_err(|_| TweetError::InternalServerError)?
+        {
+            let list = cursor
+                .deserialize_current()
+                .map_err(|_| TweetError::InternalServerError)?;
+            lists.push(list.map());
+        }
+        Ok(lists)
+    }
+
+    /// Get a list the viewer can see, private lists are not found for anyone but their owner
+    pub async fn find_list(&self, id: &str, viewer_id: &str) -> Result<UserList, TweetError> {
+        let viewer_id = parse_id(viewer_id, "user")?;
+        match self.find_one(doc! {"_id": parse_id(id, "list")?}).await? {
+            Some(list) if list.is_visible_to(&viewer_id) => Ok(list),
+            _ => Err(not_found(id)),
+        }
+    }
+
+    /// Replaces the name, description and privacy of a list of the owner
+    pub async fn update(
+        &self,
+        id: &str,
+        owner_id: &str,
+        list: &UserList,
+    ) -> Result<ListDto, TweetError> {
+        let filter = owned(id, owner_id)?;
+        let update = doc! {
+            "$set": {
+                "name": &list.name,
+                "description": &list.description,
+                "private": list.private,
+                "updated_at": bson::to_bson(&Utc::now()).unwrap()
+            }
+        };
+        self.collection
+            .update_one(filter.clone(), update, None)
+            .await
+            .map_err(|_| TweetError::InternalServerError)?;
+        match self.find_one(filter).await? {
+            Some(list) => Ok(list.map()),
+            None => Err(not_found(id)),
+        }
+    }
+
+    pub async fn delete(&self, id: &str, owner_id: &str) -> Result<String, TweetError> {
+        let result = self
+            .collection
+            .delete_one(owned(id, owner_id)?, None)
+            .await
+            .map_err(|_| TweetError::InternalServerError)?;
+        if result.deleted_count == 0 {
+            return Err(not_found(id));
+        }
+        Ok("List deleted".into())
+    }
+
+    /// Adds an account to a list of the owner, up to `list_max_members`
+    pub async fn add_member(
+        &self,
+        id: &str,
+        owner_id: &str,
+        member_id: ObjectId,
+    ) -> Result<ListDto, TweetError> {
+        let filter = owned(id, owner_id)?;
+        let list = match self.find_one(filter.clone()).await? {
+            Some(list) => list,
+            None => return Err(not_found(id)),
+        };
+        if !list.member_ids.contains(&member_id) && list.member_ids.len() >= list_max_members() {
+            return Err(TweetError::UnprocessableEntity(vec![FieldError::new(
+                "user_id",
+                "too_many_members",
+                &format!(
+                    "A list cannot have more than {} members",
+                    list_max_members()
+                ),
+            )]));
+        }
+        self.update_members(id, filter, doc! {"$addToSet": {"member_ids": member_id}})
+            .await
+    }
+
+    /// Removes an account from a list of the owner
+    pub async fn remove_member(
+        &self,
+        id: &str,
+        owner_id: &str,
+        member_id: &str,
+    ) -> Result<ListDto, TweetError> {
+        let filter = owned(id, owner_id)?;
+        let member_id = parse_id(member_id, "user")?;
+        self.update_members(id, filter, doc! {"$pull": {"member_ids": member_id}})
+            .await
+    }
+
+    /// Deletes the lists of a deleted user and removes them from other lists
+    pub async fn remove_user(&self, user_id: &str) -> Result<(), TweetError> {
+        let user_id = parse_id(user_id, "user")?;
+        self.collection
+            .delete_many(doc! {"owner_id": user_id}, None)
+            .await
+            .map_err(|_| TweetError::InternalServerError)?;
+        self.collection
+            .update_many(
+                doc! {"member_ids": user_id},
+                doc! {"$pull": {"member_ids": user_id}},
+                None,
+            )
+            .await
+            .map_err(|_| TweetError::InternalServerError)?;
+        Ok(())
+    }
+
+    async fn update_members(
+        &self,
+        id: &str,
+        filter: Document,
+        mut update: Document,
+    ) -> Result<ListDto, TweetError> {
+        update.insert(
+            "$set",
+            doc! {"updated_at": bson::to_bson(&Utc::now()).unwrap()},
+        );
+        self.collection
+            .update_one(filter.clone(), update, None)
+            .await
+            .map_err(|_| TweetError::InternalServerError)?;
+        match self.find_one(filter).await? {
+            Some(list) => Ok(list.map()),
+            None => Err(not_found(id)),
+        }
+    }
+
+    async fn find_one(&self, filter: Document) -> Result<Option<UserList>, TweetError> {
+        self.collection
+            .find_one(filter, None)
+            .await
+            .map_err(|_| TweetError::InternalServerError)
+    }
+}
+
+/// Filter matching a list only when it belongs to the owner
+fn owned(id: &str, owner_id: &str) -> Result<Document, TweetError> {
+    Ok(doc! {"_id": parse_id(id, "list")?, "owner_id": parse_id(owner_id, "user")?})
+}
+
+fn not_found(id: &str) -> TweetError {
+    TweetError::NotFound(format!("No list with id {} found", id))
+}
+
+fn parse_id(id: &str, name: &str) -> Result<ObjectId, TweetError> {
+    ObjectId::parse_str(id)
+        .map_err(|_| TweetError::BadRequest(format!("Invalid {} Id provided", name)))
+}
@@ -0,0 +1,158 @@
+use bson::{doc, oid::ObjectId};
+use mongodb::Collection;
+
+use crate::{errors::error::TweetError, model::media_model::Media};
+
+pub struct MediaRepo<Media> {
+    pub collection: Collection<Media>,
+}
+
+impl MediaRepo<Media> {
+    pub async fn create(&self, mut media: Media) -> Result<Media, TweetError> {
+        let result = self
+            .collection
+            .insert_one(&media, None)
+            .await
+            .map_err(|_| TweetError::InternalServerError)?;
+        media.id = result.inserted_id.as_object_id();
+        Ok(media)
+    }
+
+    pub async fn find(&self, id: &str) -> Result<Media, TweetError> {
+        let _id = ObjectId::parse_str(id)
+            .map_err(|_| TweetError::BadRequest("Invalid media Id provided".into()))?;
+        let media = self
+            .collection
+            .find_one(doc! {"_id": _id}, None)
+            .await
+            .map_err(|_| TweetError::InternalServerError)?;
+        match media {
+            Some(media) => Ok(media),
+            None => Err(TweetError::NotFound(format!(
+                "No media with id {} found",
+                id
+            ))),
+        }
+    }
+
+    /// Updates the alt text of media the user uploaded and has not attached yet
+    pub async fn set_alt_text(
+        &self,
+        id: &str,
+        user_id: &str,
+        alt_text: Option<String>,
+    ) -> Result<Media, TweetError> {
+        let mut media = self.find_unattached(id, user_id).await?;
+        self.collection
+            .update_one(
+                doc! {"_id": media.id},
+                doc! {"$set": {"alt_text": &alt_text}},
+                None,
+            )
+            .await
+            .map_err(|_| TweetError::InternalServerError)?;
+        media.alt_text = alt_text;
+        Ok(media)
+    }
+
+    /// Marks the user's uploads as attached to a tweet, returning them in the given order.
+    /// Each upload can only be attached once, when one of them was attached concurrently
+    /// the others are released again.
+    pub async fn attach(&self, ids: &[String], user_id: &str) -> Result<Vec<Media>, TweetError> {
+        let mut media = Vec::<Media>::new();
+        for id in ids {
+            media.push(self.find_unattached(id, user_id).await?);
+        }
+        let mut attached = Vec::<ObjectId>::new();
+        for item in media.iter_mut() {
+            let id = item.id.unwrap();
+            let result = self
+                .collection
+                .update_one(
+                    doc! {"_id": id, "attached": false},
+                    doc! {"$set": {"attached": true}},
+                    None,
+                )
+                .await
+                .map_err(|_| TweetError::InternalServerError);
+            let claimed = match result {
+                Ok(result) => result.modified_count == 1,
+                Err(err) => {
+                    self.detach(&attached).await?;
+                    return Err(err);
+                }
+            };
+            if !claimed {
+                self.detach(&attached).await?;
+                return Err(TweetError::Conflict(format!(
+                    "Media {} is already attached to a tweet",
+                    id
+                )));
+            }
+            attached.push(id);
+            item.attached = true;
+        }
+        Ok(media)
+    }
+
+    /// Releases uploads attached to a tweet that could not be created, so they can be reused
+    pub async fn detach(&self, ids: &[ObjectId]) -> Result<(), TweetError> {
+        if ids.is_empty() {
+            return Ok(());
+        }
+        self.collection
+            .update_many(
+                doc! {"_id": {"$in": ids}},
+                doc! {"$set": {"attached": false}},
+                None,
+            )
+            .await
+            .map_err(|_| TweetError::InternalServerError)?;
+        Ok(())
+    }
+
+    /// Removes every upload of a deleted user, returning the keys of their stored files
+    pub async fn remove_user(&self, user_id: &str) -> Result<Vec<String>, TweetError> {
+        let user_id = ObjectId::parse_str(user_id)
+            .map_err(|_| TweetError::BadRequest("Invalid user Id provided".into()))?;
+        let filter = doc! {"user_id": user_id};
+        let mut cursor = self
+            .collection
+            .find(filter.clone(), None)
+            .await
+            .map_err(|_| TweetError::InternalServerError)?;
+        let mut keys = Vec::<String>::new();
+        while cursor
+            .advance()
+            .await
+            .map_err(|_| TweetError::InternalServerError)?
+        {
+            let media = cursor
+                .deserialize_current()
+                .map_err(|_| TweetError::InternalServerError)?;
+            keys.push(media.key);
+            keys.extend(media.thumbnail_key);
+        }
+        self.collection
+            .delete_many(filter, None)
+            .await
+            .map_err(|_| TweetError::InternalServerError)?;
+        Ok(keys)
+    }
+
+    async fn find_unattached(&self, id: &str, user_id: &str) -> Result<Media, TweetError> {
+        let media = self.find(id).await?;
+        if media.user_id.to_hex() != user_id {
+            return Err(TweetError::Forbidden(
+                "Only the uploader can use this media".into(),
+            ));
+        }
+        if media.attached {
+            return Err(TweetError::BadRequest(format!(
+                "Media {} is already attached to a tweet",
+                id
+            )));
+        }
+        Ok(media)
+    }
+}
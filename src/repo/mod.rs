@@ -1,2 +1,14 @@
+pub mod audit_repo;
+pub mod bookmark_repo;
+pub mod dm_repo;
+pub mod draft_repo;
+pub mod follow_repo;
+pub mod idempotency_repo;
+pub mod list_repo;
+pub mod media_repo;
+pub mod notification_repo;
+pub mod relation_repo;
+pub mod report_repo;
+pub mod spam_repo;
 pub mod tweet_repo;
 pub mod user_repo;
@@ -0,0 +1,190 @@
+use std::sync::Arc;
+
+use bson::{doc, oid::ObjectId};
+use mongodb::{options::ReplaceOptions, Collection};
+
+use crate::{
+    errors::error::TweetError,
+    model::{
+        notification_model::{Notification, NotificationGroup, NotificationPreferences},
+        pagination::PageQuery,
+        relation_model::Relation,
+    },
+    repo::relation_repo::RelationRepo,
+    services::event_bus::{EventBus, EventPayload},
+};
+
+#[derive(Clone)]
+pub struct NotificationRepo<Notification> {
+    pub collection: Collection<Notification>,
+    pub preferences: Collection<NotificationPreferences>,
+    pub events: Arc<EventBus>,
+    pub relations: RelationRepo<Relation>,
+}
+
+impl NotificationRepo<Notification> {
+    /// Stores a notification unless it is about the user's own action, the user opted out
+    /// or the user blocked or muted the actor
+    pub async fn notify(&self, notification: Notification) -> Result<(), TweetError> {
+        if notification.user_id == notification.actor_id {
+            return Ok(());
+        }
+        let user_id = notification.user_id.to_hex();
+        if self
+            .relations
+            .hides(&user_id, &notification.actor_id.to_hex())
+            .await?
+        {
+            return Ok(());
+        }
+        let preferences = self.preferences(&user_id).await?;
+        if !preferences.allows(notification.kind) {
+            return Ok(());
+        }
+        self.collection
+            .insert_one(&notification, None)
+            .await
+            .map_err(|_| TweetError::InternalServerError)?;
+        self.events.publish(EventPayload::Notification {
+            user_id,
+            kind: notification.kind,
+            actor_id: notification.actor_id.to_hex(),
+            tweet_id: notification.tweet_id.map(|id| id.to_hex()),
+        });
+        Ok(())
+    }
+
+    /// Lists a page of the user's notifications, grouped by `group_key`, newest first
+    pub async fn notifications(
+        &self,
+        user_id: &str,
+        page: &PageQuery,
+    ) -> Result<Vec<NotificationGroup>, TweetError> {
+        let user_id = parse_user_id(user_id)?;
+        let pipeline = vec![
+            doc! {"$match": {"user_id": user_id}},
+            doc! {"$sort": {"created_at": -1}},
+            doc! {"$group": {
+                "_id": "$group_key",
+                "id": {"$first": "$_id"},
+                "kind": {"$first": "$kind"},
+                "tweet_id": {"$first": "$tweet_id"},
+                "actor_ids": {"$push": "$actor_id"},
+                "unread": {"$sum": {"$cond": [{"$eq": ["$read", false]}, 1, 0]}},
+                "created_at": {"$first": "$created_at"}
+            }},
+            doc! {"$sort": {"created_at": -1}},
+            doc! {"$skip": page.skip() as i64},
+            doc! {"$limit": page.limit() as i64},
+        ];
+        let mut cursor = self
+            .collection
+            .aggregate(pipeline, None)
+            .await
+            .map_err(|_| TweetError::InternalServerError)?;
+        let mut groups = Vec::<NotificationGroup>::new();
+        while cursor
+            .advance()
+            .await
+            .map_err(|_| TweetError::InternalServerError)?
+        {
+            let document = cursor
+                .deserialize_current()
+                .map_err(|_| TweetError::InternalServerError)?;
+            let group = bson::from_document::<NotificationGroup>(document)
+                .map_err(|_| TweetError::InternalServerError)?;
+            groups.push(group);
+        }
+        Ok(groups)
+    }
+
+    pub async fn unread_count(&self, user_id: &str) -> Result<u64, TweetError> {
+        let filter = doc! {"user_id": parse_user_id(user_id)?, "read": false};
+        self.collection
+            .count_documents(filter, None)
+            .await
+            .map_err(|_| TweetError::InternalServerError)
+    }
+
+    /// Marks a notification, and every notification grouped with it, as read
+    pub async fn mark_read(&self, user_id: &str, id: &str) -> Result<(), TweetError> {
+        let user_id = parse_user_id(user_id)?;
+        let id = ObjectId::parse_str(id)
+            .map_err(|_| TweetError::BadRequest("Invalid notification Id provided".into()))?;
+        let notification = self
+            .collection
+            .find_one(doc! {"_id": id, "user_id": user_id}, None)
+            .await
+            .map_err(|_| TweetError::InternalServerError)?;
+        let notification = match notification {
+            Some(notification) => notification,
+            None => return Err(TweetError::NotFound("Notification not found".into())),
+        };
+        let filter = doc! {"user_id": user_id, "group_key": notification.group_key};
+        self.collection
+            .update_many(filter, doc! {"$set": {"read": true}}, None)
+            .await
+            .map_err(|_| TweetError::InternalServerError)?;
+        Ok(())
+    }
+
+    pub async fn mark_all_read(&self, user_id: &str) -> Result<(), TweetError> {
+        let filter = doc! {"user_id": parse_user_id(user_id)?, "read": false};
+        self.collection
+            .update_many(filter, doc! {"$set": {"read": true}}, None)
+            .await
+            .map_err(|_| TweetError::InternalServerError)?;
+        Ok(())
+    }
+
+    /// Get the user's notification preferences, defaulting to every kind enabled
+    pub async fn preferences(&self, user_id: &str) -> Result<NotificationPreferences, TweetError> {
+        let user_id = parse_user_id(user_id)?;
+        let preferences = self
+            .preferences
+            .find_one(doc! {"_id": user_id}, None)
+            .await
+            .map_err(|_| TweetError::InternalServerError)?;
+        Ok(preferences.unwrap_or(NotificationPreferences {
+            user_id: Some(user_id),
+            ..Default::default()
+        }))
+    }
+
+    pub async fn update_preferences(
+        &self,
+        user_id: &str,
+        mut preferences: NotificationPreferences,
+    ) -> Result<NotificationPreferences, TweetError> {
+        let user_id = parse_user_id(user_id)?;
+        preferences.user_id = Some(user_id);
+        let options = ReplaceOptions::builder().upsert(true).build();
+        self.preferences
+            .replace_one(doc! {"_id": user_id}, &preferences, options)
+            .await
+            .map_err(|_| TweetError::InternalServerError)?;
+        Ok(preferences)
+    }
+
+    /// Removes the notifications and preferences of a deleted user, and the ones they caused
+    pub async fn remove_user(&self, user_id: &str) -> Result<(), TweetError> {
+        let user_id = parse_user_id(user_id)?;
+        self.collection
+            .delete_many(
+                doc! {"$or": [{"user_id": user_id}, {"actor_id": user_id}]},
+                None,
+            )
+            .await
+            .map_err(|_| TweetError::InternalServerError)?;
+        self.preferences
+            .delete_one(doc! {"_id": user_id}, None)
+            .await
+            .map_err(|_| TweetError::InternalServerError)?;
+        Ok(())
+    }
+}
+
+fn parse_user_id(user_id: &str) -> Result<ObjectId, TweetError> {
+    ObjectId::parse_str(user_id)
+        .map_err(|_| TweetError::Unauthorized("authentication error occurred".into()))
+}
@@ -0,0 +1,155 @@
+use bson::{doc, oid::ObjectId, Document};
+use mongodb::Collection;
+
+use crate::{
+    errors::error::TweetError,
+    model::relation_model::{Relation, RelationKind},
+};
+
+#[derive(Clone)]
+pub struct RelationRepo<Relation> {
+    pub collection: Collection<Relation>,
+}
+
+impl RelationRepo<Relation> {
+    /// Blocks or mutes the target user
+    pub async fn add(
+        &self,
+        user_id: &str,
+        target_id: &str,
+        kind: RelationKind,
+    ) -> Result<(), TweetError> {
+        let user_id = parse_id(user_id)?;
+        let target_id = parse_id(target_id)?;
+        if user_id == target_id {
+            return Err(TweetError::BadRequest(
+                "You cannot block or mute yourself".into(),
+            ));
+        }
+        let filter = doc! {"user_id": user_id, "target_id": target_id, "kind": kind_name(kind)};
+        if self.find_relations(filter).await?.is_empty() {
+            self.collection
+                .insert_one(Relation::new(user_id, target_id, kind), None)
+                .await
+                .map_err(|_| TweetError::InternalServerError)?;
+        }
+        Ok(())
+    }
+
+    /// Undoes a block or mute of the target user
+    pub async fn remove(
+        &self,
+        user_id: &str,
+        target_id: &str,
+        kind: RelationKind,
+    ) -> Result<(), TweetError> {
+        let filter = doc! {
+            "user_id": parse_id(user_id)?,
+            "target_id": parse_id(target_id)?,
+            "kind": kind_name(kind)
+        };
+        self.collection
+            .delete_one(filter, None)
+            .await
+            .map_err(|_| TweetError::InternalServerError)?;
+        Ok(())
+    }
+
+    /// Get the ids of the users the user blocked or muted
+    pub async fn target_ids(
+        &self,
+        user_id: &str,
+        kind: RelationKind,
+    ) -> Result<Vec<ObjectId>, TweetError> {
+        let filter = doc! {"user_id": parse_id(user_id)?, "kind": kind_name(kind)};
+        let relations = self.find_relations(filter).await?;
+        Ok(relations.iter().map(|r| r.target_id).collect())
+    }
+
+    /// Checks whether either user has blocked the other
+    pub async fn is_blocked(&self, user_id: &str, other_id: &str) -> Result<bool, TweetError> {
+        let user_id = parse_id(user_id)?;
+        let other_id = parse_id(other_id)?;
+        let filter = doc! {
+            "kind": kind_name(RelationKind::Block),
+            "$or": [
+                {"user_id": user_id, "target_id": other_id},
+                {"user_id": other_id, "target_id": user_id}
+            ]
+        };
+        Ok(!self.find_relations(filter).await?.is_empty())
+    }
+
+    /// Checks whether the user blocked or muted the actor, so their activity is hidden
+    pub async fn hides(&self, user_id: &str, actor_id: &str) -> Result<bool, TweetError> {
+        let filter = doc! {"user_id": parse_id(user_id)?, "target_id": parse_id(actor_id)?};
+        Ok(!self.find_relations(filter).await?.is_empty())
+    }
+
+    /// Get the ids of the users whose tweets the viewer must not see:
+    /// everyone the viewer blocked or muted, and everyone who blocked the viewer
+    pub async fn hidden_ids(&self, viewer_id: &str) -> Result<Vec<ObjectId>, TweetError> {
+        let viewer_id = parse_id(viewer_id)?;
+        let filter = doc! {
+            "$or": [
+                {"user_id": viewer_id},
+                {"target_id": viewer_id, "kind": kind_name(RelationKind::Block)}
+            ]
+        };
+        let relations = self.find_relations(filter).await?;
+        let mut ids = relations
+            .iter()
+            .map(|r| {
+                if r.user_id == viewer_id {
+                    r.target_id
+                } else {
+                    r.user_id
+                }
+            })
+            .collect::<Vec<ObjectId>>();
+        ids.sort();
+        ids.dedup();
+        Ok(ids)
+    }
+
+    /// Removes the blocks and mutes of a deleted user, and the ones targeting them
+    pub async fn remove_user(&self, user_id: &str) -> Result<(), TweetError> {
+        let user_id = parse_id(user_id)?;
+        self.collection
+            .delete_many(
+                doc! {"$or": [{"user_id": user_id}, {"target_id": user_id}]},
+                None,
+            )
+            .await
+            .map_err(|_| TweetError::InternalServerError)?;
+        Ok(())
+    }
+
+    async fn find_relations(&self, filter: Document) -> Result<Vec<Relation>, TweetError> {
+        let mut cursor = self
+            .collection
+            .find(filter, None)
+            .await
+            .map_err(|_| TweetError::InternalServerError)?;
+        let mut relations = Vec::<Relation>::new();
+        while cursor
+            .advance()
+            .await
+            .map_err(|_| TweetError::InternalServerError)?
+        {
+            let relation = cursor
+                .deserialize_current()
+                .map_err(|_| TweetError::InternalServerError)?;
+            relations.push(relation);
+        }
+        Ok(relations)
+    }
+}
+
+fn kind_name(kind: RelationKind) -> bson::Bson {
+    bson::to_bson(&kind).unwrap()
+}
+
+fn parse_id(id: &str) -> Result<ObjectId, TweetError> {
+    ObjectId::parse_str(id).map_err(|_| TweetError::BadRequest("Invalid user Id provided".into()))
+}
@@ -0,0 +1,170 @@
+use bson::{doc, oid::ObjectId};
+use chrono::Utc;
+use mongodb::{options::FindOptions, Collection};
+
+use crate::{
+    dtos::dto::ReportDto,
+    errors::error::{FieldError, TweetError},
+    model::{
+        pagination::PageQuery,
+        report_model::{HeldReview, ModerationRecord, Report, ReportStatus},
+    },
+};
+
+pub struct ReportRepo<Report> {
+    pub collection: Collection<Report>,
+    pub held_reviews: Collection<HeldReview>,
+}
+
+impl ReportRepo<Report> {
+    pub async fn create(&self, mut report: Report) -> Result<ReportDto, TweetError> {
+        let result = self
+            .collection
+            .insert_one(&report, None)
+            .await
+            .map_err(|_| TweetError::InternalServerError)?;
+        report.id = result.inserted_id.as_object_id();
+        Ok(report.map())
+    }
+
+    /// Lists reports oldest first, the moderation queue when filtered on open reports
+    pub async fn reports(
+        &self,
+        status: Option<ReportStatus>,
+        page: &PageQuery,
+    ) -> Result<Vec<ReportDto>, TweetError> {
+        let filter = match status {
+            Some(status) => doc! {"status": bson::to_bson(&status).unwrap()},
+            None => doc! {},
+        };
+        let options = FindOptions::builder()
+            .sort(doc! {"created_at": 1})
+            .skip(page.skip())
+            .limit(page.limit() as i64)
+            .build();
+        let mut cursor = self
+            .collection
+            .find(filter, options)
+            .await
+            .map_err(|_| TweetError::InternalServerError)?;
+        let mut reports = Vec::<ReportDto>::new();
+        while cursor
+            .advance()
+            .await
+            .map_err(|_| TweetError::InternalServerError)?
+        {
+            let report = cursor
+                .deserialize_current()
+                .map_err(|_| TweetError::InternalServerError)?;
+            reports.push(report.map());
+        }
+        Ok(reports)
+    }
+
+    pub async fn find_report(&self, id: &str) -> Result<Report, TweetError> {
+        let report = self
+            .collection
+            .find_one(doc! {"_id": parse_id(id)?}, None)
+            .await
+            .map_err(|_| TweetError::InternalServerError)?;
+        match report {
+            Some(report) => Ok(report),
+            None => Err(TweetError::NotFound(format!(
+                "No report with id {} found",
+                id
+            ))),
+        }
+    }
+
+    /// Moves a report to the status of the record and appends the record to its history,
+    /// failing when the report already moved to a status the transition is not allowed from
+    pub async fn record(
+        &self,
+        id: &str,
+        record: ModerationRecord,
+    ) -> Result<ReportDto, TweetError> {
+        let status = record.status;
+        let previous = status
+            .previous()
+            .iter()
+            .map(|s| bson::to_bson(s).unwrap())
+            .collect::<Vec<_>>();
+        let filter = doc! {"_id": parse_id(id)?, "status": {"$in": previous}};
+        let update = doc! {
+            "$set": {
+                "status": bson::to_bson(&status).unwrap(),
+                "updated_at": bson::to_bson(&Utc::now()).unwrap()
+            },
+            "$push": {"history": bson::to_bson(&record).unwrap()}
+        };
+        let result = self
+            .collection
+            .update_one(filter, update, None)
+            .await
+            .map_err(|_| TweetError::InternalServerError)?;
+        let report = self.find_report(id).await?;
+        if result.matched_count == 0 {
+            return Err(invalid_transition(report.status, status));
+        }
+        Ok(report.map())
+    }
+
+    /// Stores a moderator's decision on held content
+    pub async fn record_held_review(
+        &self,
+        tweet_id: &str,
+        comment_id: Option<&str>,
+        moderator_id: ObjectId,
+        approved: bool,
+    ) -> Result<(), TweetError> {
+        let review = HeldReview {
+            id: None,
+            tweet_id: ObjectId::parse_str(tweet_id)
+                .map_err(|_| TweetError::BadRequest("Invalid tweet Id provided".into()))?,
+            comment_id: comment_id
+                .map(ObjectId::parse_str)
+                .transpose()
+                .map_err(|_| TweetError::BadRequest("Invalid comment Id provided".into()))?,
+            moderator_id,
+            approved,
+            created_at: Utc::now(),
+        };
+        self.held_reviews
+            .insert_one(review, None)
+            .await
+            .map_err(|_| TweetError::InternalServerError)?;
+        Ok(())
+    }
+
+    /// Removes the reports a deleted user filed and drops them as the offender of the others
+    pub async fn remove_user(&self, user_id: &str) -> Result<(), TweetError> {
+        let user_id = ObjectId::parse_str(user_id)
+            .map_err(|_| TweetError::BadRequest("Invalid user Id provided".into()))?;
+        self.collection
+            .delete_many(doc! {"reporter_id": user_id}, None)
+            .await
+            .map_err(|_| TweetError::InternalServerError)?;
+        self.collection
+            .update_many(
+                doc! {"offender_id": user_id},
+                doc! {"$set": {"offender_id": null}},
+                None,
+            )
+            .await
+            .map_err(|_| TweetError::InternalServerError)?;
+        Ok(())
+    }
+}
+
+/// Error for a status change the workflow does not allow
+pub fn invalid_transition(from: ReportStatus, to: ReportStatus) -> TweetError {
+    TweetError::UnprocessableEntity(vec![FieldError::new(
+        "status",
+        "invalid_transition",
+        &format!("A report cannot move from {} to {}", from.name(), to.name()),
+    )])
+}
+
+fn parse_id(id: &str) -> Result<ObjectId, TweetError> {
+    ObjectId::parse_str(id).map_err(|_| TweetError::BadRequest("Invalid report Id provided".into()))
+}
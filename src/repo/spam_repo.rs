@@ -0,0 +1,69 @@
+use bson::{doc, oid::ObjectId};
+use mongodb::{options::FindOptions, Collection};
+
+use crate::{
+    dtos::dto::SpamEventDto,
+    errors::error::TweetError,
+    model::spam_model::{SpamEvent, SpamEventQuery},
+};
+
+pub struct SpamRepo<SpamEvent> {
+    pub collection: Collection<SpamEvent>,
+}
+
+impl SpamRepo<SpamEvent> {
+    pub async fn log(&self, event: &SpamEvent) -> Result<(), TweetError> {
+        self.collection
+            .insert_one(event, None)
+            .await
+            .map_err(|_| TweetError::InternalServerError)?;
+        Ok(())
+    }
+
+    /// Lists logged spam decisions, newest first
+    pub async fn events(&self, query: &SpamEventQuery) -> Result<Vec<SpamEventDto>, TweetError> {
+        let mut filter = doc! {};
+        if let Some(decision) = query.decision {
+            filter.insert("decision", bson::to_bson(&decision).unwrap());
+        }
+        if let Some(user_id) = &query.user_id {
+            let user_id = ObjectId::parse_str(user_id)
+                .map_err(|_| TweetError::BadRequest("Invalid user Id provided".into()))?;
+            filter.insert("user_id", user_id);
+        }
+        let page = query.page_query();
+        let options = FindOptions::builder()
+            .sort(doc! {"created_at": -1})
+            .skip(page.skip())
+            .limit(page.limit() as i64)
+            .build();
+        let mut cursor = self
+            .collection
+            .find(filter, options)
+            .await
+            .map_err(|_| TweetError::InternalServerError)?;
+        let mut events = Vec::<SpamEventDto>::new();
+        while cursor
+            .advance()
+            .await
+            .map_err(|_| TweetError::InternalServerError)?
+        {
+            let event = cursor
+                .deserialize_current()
+                .map_err(|_| TweetError::InternalServerError)?;
+            events.push(event.map());
+        }
+        Ok(events)
+    }
+
+    /// Removes the spam decisions logged for a deleted user
+    pub async fn remove_user(&self, user_id: &str) -> Result<(), TweetError> {
+        let user_id = ObjectId::parse_str(user_id)
+            .map_err(|_| TweetError::BadRequest("Invalid user Id provided".into()))?;
+        self.collection
+            .delete_many(doc! {"user_id": user_id}, None)
+            .await
+            .map_err(|_| TweetError::InternalServerError)?;
+        Ok(())
+    }
+}
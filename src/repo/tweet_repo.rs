@@ -1,146 +1,1305 @@
+use std::{collections::HashMap, sync::Arc};
+
+use chrono::{DateTime, Utc};
 use mongodb::{
-    bson::{doc, extjson::de::Error, oid::ObjectId},
-    results::DeleteResult,
-    Collection,
+    bson::{self, doc, oid::ObjectId, Document},
+    options::FindOptions,
+    Collection, IndexModel,
 };
 
 use crate::model::{
-    docs::update_tweet_document, like_model::Like, tweet_comment::Comment, tweet_model::Tweet,
+    audience_model::{Audience, ReplyPolicy},
+    auth_model::User,
+    follow_model::Follow,
+    like_model::Like,
+    media_model::Media,
+    notification_model::{Notification, NotificationKind},
+    pagination::PageQuery,
+    relation_model::Relation,
+    search_model::SearchSort,
+    tweet_comment::Comment,
+    tweet_entity::TweetEntities,
+    tweet_model::Tweet,
+    tweet_poll::PollVote,
+};
+use crate::{
+    config::settings::tweet_restore_window,
+    dtos::dto::{CommentDto, DeletedTweetDto, HeldContentDto, TweetDto, TweetEditDto},
+    errors::error::{FieldError, TweetError},
+    repo::{
+        follow_repo::FollowRepo, notification_repo::NotificationRepo, relation_repo::RelationRepo,
+        user_repo::UserRepo,
+    },
+    services::event_bus::{EventBus, EventPayload},
+    text::search_query::SearchQuery,
 };
-use crate::{dtos::dto::TweetDto, errors::error::TweetError};
 
 pub struct TweetRepo<Tweet> {
     pub collection: Collection<Tweet>,
+    pub notifications: NotificationRepo<Notification>,
+    pub events: Arc<EventBus>,
+    pub relations: RelationRepo<Relation>,
+    pub follows: FollowRepo<Follow>,
+    pub users: UserRepo<User>,
 }
 
 impl TweetRepo<Tweet> {
     pub async fn create_tweet(&self, tweet: Tweet) -> Result<TweetDto, TweetError> {
+        let author_id = tweet.user_id.filter(|_| !tweet.withheld);
+        let mentioned = tweet
+            .entities
+            .mentions
+            .iter()
+            .filter_map(|m| m.user_id)
+            .collect::<Vec<ObjectId>>();
         let _tweet = self
             .collection
             .insert_one(tweet, None)
             .await
-            .map_err(|_| TweetError::InternalServerError)
-            .ok()
-            .unwrap();
+            .map_err(|_| TweetError::InternalServerError)?;
 
         let id = match _tweet.inserted_id.as_object_id() {
             Some(id) => id.to_hex(),
             None => return Err(TweetError::BadRequest("Error reading inserted id".into())),
         };
 
-        let dto = self.get_tweet(&id).await.unwrap();
+        let dto = self.get_tweet(&id).await?;
+        self.events
+            .publish(EventPayload::TweetCreated(Box::new(dto.clone())));
+        if let Some(author_id) = author_id {
+            for user_id in mentioned {
+                let tweet_id = _tweet.inserted_id.as_object_id();
+                self.notify(Notification::new(
+                    user_id,
+                    author_id,
+                    NotificationKind::Mention,
+                    tweet_id,
+                ))
+                .await;
+            }
+        }
         return Ok(dto);
     }
 
-    pub async fn all_tweets(&self, _user_id: &str) -> Result<Vec<TweetDto>, Error> {
-        let user_id = ObjectId::parse_str(_user_id).expect("Invalid user_id");
-        let filter = doc! {"user_id": user_id};
-        let mut _tweets = self
+    /// Inserts an imported tweet as is, without notifying the users it mentions
+    pub async fn import_tweet(&self, mut tweet: Tweet) -> Result<Tweet, TweetError> {
+        let result = self
             .collection
-            .find(filter, None)
+            .insert_one(&tweet, None)
             .await
-            .ok()
-            .expect("Failed to retrieve all likes");
-        let mut tweets = Vec::<Tweet>::new();
-        while _tweets.advance().await.unwrap() {
-            tweets.push(_tweets.deserialize_current().unwrap());
+            .map_err(|_| TweetError::InternalServerError)?;
+        tweet.id = result.inserted_id.as_object_id();
+        Ok(tweet)
+    }
+
+    /// Adds an imported reply to a tweet as one of its comments
+    pub async fn import_comment(
+        &self,
+        tweet_id: ObjectId,
+        comment: Comment,
+    ) -> Result<(), TweetError> {
+        self.collection
+            .update_one(
+                doc! {"_id": tweet_id},
+                doc! {
+                    "$push": {"comments": bson::to_bson(&comment).unwrap()},
+                    "$inc": {"version": 1}
+                },
+                None,
+            )
+            .await
+            .map_err(|_| TweetError::InternalServerError)?;
+        Ok(())
+    }
+
+    /// Get the archive ids of the tweets and replies imported for the user,
+    /// with the id of the tweet each one was imported as or on
+    pub async fn imported_sources(
+        &self,
+        user_id: &str,
+    ) -> Result<HashMap<String, ObjectId>, TweetError> {
+        let user_id = parse_user_id(user_id)?;
+        let filter = doc! {
+            "$or": [
+                {"user_id": user_id, "source_id": {"$ne": null}},
+                {"comments": {"$elemMatch": {"user_id": user_id, "source_id": {"$ne": null}}}}
+            ]
+        };
+        let mut sources = HashMap::<String, ObjectId>::new();
+        for tweet in self.find_stored_tweets(filter, None).await? {
+            let id = match tweet.id {
+                Some(id) => id,
+                None => continue,
+            };
+            if tweet.user_id == Some(user_id) {
+                sources.extend(tweet.source_id.map(|source_id| (source_id, id)));
+            }
+            for comment in tweet.comments {
+                if comment.user_id == Some(user_id) {
+                    sources.extend(comment.source_id.map(|source_id| (source_id, id)));
+                }
+            }
+        }
+        Ok(sources)
+    }
+
+    /// Get the user's tweets, with the pinned tweet first
+    pub async fn all_tweets(&self, user_id: &str) -> Result<Vec<TweetDto>, TweetError> {
+        let user = self.users.find_user(user_id).await?;
+        let mut tweets = self.find_tweets(doc! {"user_id": user.id}, None).await?;
+        if let Some(index) = tweets
+            .iter()
+            .position(|t| t.id.is_some() && t.id == user.pinned_tweet_id)
+        {
+            let pinned = tweets.remove(index);
+            tweets.insert(0, pinned);
         }
         let dto = tweets
-            .into_iter()
-            .map(|t| t.map())
+            .iter()
+            .map(|t| {
+                let mut dto = t.map();
+                dto.is_pinned = t.id.is_some() && t.id == user.pinned_tweet_id;
+                dto
+            })
             .collect::<Vec<TweetDto>>();
         Ok(dto)
     }
 
-    pub async fn get_tweet(&self, id: &str) -> Result<TweetDto, Error> {
-        let _id = ObjectId::parse_str(id).expect("Invalid tweet Id provided");
-        let filter = doc! {"_id": _id};
-        let _tweet = self
+    /// Get one page of the tweets on a user's profile the viewer can see,
+    /// the pinned tweet leading the first page
+    pub async fn profile_tweets(
+        &self,
+        user_id: &str,
+        viewer_id: &str,
+        page: &PageQuery,
+    ) -> Result<Vec<TweetDto>, TweetError> {
+        let user = self.users.find_user(user_id).await?;
+        let audience = self.audience(viewer_id).await?;
+        let filter = doc! {
+            "user_id": user.id,
+            "_id": {"$ne": user.pinned_tweet_id},
+            "$and": [audience.filter()]
+        };
+        let options = FindOptions::builder()
+            .sort(doc! {"created_at": -1})
+            .skip(page.skip())
+            .limit(page.limit() as i64)
+            .build();
+        let mut tweets = self.find_tweets(filter, options).await?;
+        if let Some(pinned_id) = user.pinned_tweet_id.filter(|_| page.skip() == 0) {
+            let filter = doc! {"_id": pinned_id, "$and": [audience.filter()]};
+            let pinned = self.find_tweets(filter, None).await?;
+            tweets.splice(0..0, pinned);
+        }
+        Ok(tweets
+            .iter()
+            .map(|t| {
+                let mut dto = t.map();
+                dto.is_pinned = t.id.is_some() && t.id == user.pinned_tweet_id;
+                dto
+            })
+            .collect())
+    }
+
+    /// Pins the tweet to its author's profile, replacing the previously pinned tweet
+    pub async fn pin_tweet(&self, id: &str, user_id: &str) -> Result<TweetDto, TweetError> {
+        let tweet = self.find_tweet(id).await?;
+        if !tweet.is_author(user_id) {
+            return Err(TweetError::Forbidden(
+                "Only the author can pin this tweet".into(),
+            ));
+        }
+        self.users.set_pinned(user_id, tweet.id).await?;
+        let mut dto = tweet.map();
+        dto.is_pinned = true;
+        Ok(dto)
+    }
+
+    /// Unpins the tweet from its author's profile
+    pub async fn unpin_tweet(&self, id: &str, user_id: &str) -> Result<TweetDto, TweetError> {
+        let tweet = self.find_tweet(id).await?;
+        if !tweet.is_author(user_id) {
+            return Err(TweetError::Forbidden(
+                "Only the author can unpin this tweet".into(),
+            ));
+        }
+        let user = self.users.find_user(user_id).await?;
+        if user.pinned_tweet_id == tweet.id {
+            self.users.set_pinned(user_id, None).await?;
+        }
+        Ok(tweet.map())
+    }
+
+    pub async fn get_tweet(&self, id: &str) -> Result<TweetDto, TweetError> {
+        let tweet = self.find_tweet(id).await?;
+        Ok(tweet.map())
+    }
+
+    /// Get a tweet for a viewer, hiding tweets the viewer is not in the audience of
+    pub async fn view_tweet(&self, id: &str, viewer_id: &str) -> Result<TweetDto, TweetError> {
+        let tweet = self.find_visible_tweet(id, viewer_id).await?;
+        Ok(tweet.map())
+    }
+
+    /// Checks that the viewer may see the media: uploaders always can, others only
+    /// through a tweet it is attached to that they may see
+    pub async fn check_media_visible(
+        &self,
+        media: &Media,
+        viewer_id: &str,
+    ) -> Result<(), TweetError> {
+        if media.user_id.to_hex() == viewer_id {
+            return Ok(());
+        }
+        let not_found =
+            || TweetError::NotFound(format!("No media with id {} found", media.id.unwrap()));
+        if !media.attached {
+            return Err(not_found());
+        }
+        let filter = doc! {"media._id": media.id};
+        let tweet_id = match self.find_tweets(filter, None).await?.pop() {
+            Some(tweet) => tweet.id.unwrap().to_hex(),
+            None => return Err(not_found()),
+        };
+        self.find_visible_tweet(&tweet_id, viewer_id)
+            .await
+            .map(|_| ())
+            .map_err(|err| match err {
+                TweetError::NotFound(_) => not_found(),
+                err => err,
+            })
+    }
+
+    /// Edits a tweet, only if it is still at `expected_version` when one is given
+    pub async fn edit_tweet(
+        &self,
+        id: &str,
+        user_id: &str,
+        message: &str,
+        entities: TweetEntities,
+        expected_version: Option<u64>,
+    ) -> Result<TweetDto, TweetError> {
+        let mut tweet = self.find_tweet(id).await?;
+        if !tweet.is_author(user_id) {
+            return Err(TweetError::Forbidden(
+                "Only the author can edit this tweet".into(),
+            ));
+        }
+        if !tweet.is_editable() {
+            return Err(TweetError::Forbidden(
+                "The edit window for this tweet has expired".into(),
+            ));
+        }
+        let query = version_filter(tweet.id, expected_version);
+        tweet.edit(message, entities);
+        let update = doc! {
+            "$set": {
+                "message": &tweet.message,
+                "entities": bson::to_bson(&tweet.entities).unwrap(),
+                "updated_at": bson::to_bson(&tweet.updated_at).unwrap()
+            },
+            "$push": {"edit_history": bson::to_bson(tweet.edit_history.last().unwrap()).unwrap()},
+            "$inc": {"version": 1}
+        };
+
+        let result = self
             .collection
-            .find(filter, None)
+            .update_one(query, update, None)
             .await
-            .ok()
-            .expect("Failed to retrieve like");
-        let tweet_dto: TweetDto = _tweet.deserialize_current().unwrap().map();
-        Ok(tweet_dto)
+            .map_err(|_| TweetError::InternalServerError)?;
+        if result.matched_count == 0 {
+            return Err(tweet_changed());
+        }
+        Ok(tweet.map())
     }
 
-    pub async fn delete_tweet(&self, id: &str) -> Result<DeleteResult, Error> {
-        let _id = ObjectId::parse_str(id).expect("Invalid like Id provided");
-        let filter = doc! {"_id": _id};
-        let _tweet = self
+    pub async fn tweet_history(
+        &self,
+        id: &str,
+        viewer_id: &str,
+    ) -> Result<Vec<TweetEditDto>, TweetError> {
+        let tweet = self.find_visible_tweet(id, viewer_id).await?;
+        let history = tweet
+            .edit_history
+            .iter()
+            .map(|e| e.map())
+            .collect::<Vec<TweetEditDto>>();
+        Ok(history)
+    }
+
+    pub async fn hashtag_tweets(
+        &self,
+        tag: &str,
+        viewer_id: &str,
+        page: &PageQuery,
+    ) -> Result<Vec<TweetDto>, TweetError> {
+        let filter = doc! {
+            "entities.hashtags.text": {
+                "$regex": format!("^{}$", regex::escape(tag)),
+                "$options": "i"
+            }
+        };
+        self.visible_tweets(filter, viewer_id, page).await
+    }
+
+    /// Get one page of the tweets of the given authors the viewer can see, newest first
+    pub async fn member_tweets(
+        &self,
+        member_ids: &[ObjectId],
+        viewer_id: &str,
+        page: &PageQuery,
+    ) -> Result<Vec<TweetDto>, TweetError> {
+        let filter = doc! {"user_id": {"$in": member_ids}};
+        self.visible_tweets(filter, viewer_id, page).await
+    }
+
+    /// Get how many tweets and comments the user posted since `since`,
+    /// and the messages of the user's tweets posted since `messages_since`
+    pub async fn recent_activity(
+        &self,
+        user_id: &str,
+        since: DateTime<Utc>,
+        messages_since: DateTime<Utc>,
+    ) -> Result<(usize, Vec<String>), TweetError> {
+        let user_id = parse_user_id(user_id)?;
+        let filter = doc! {
+            "user_id": user_id,
+            "created_at": {"$gte": bson::to_bson(&since.min(messages_since)).unwrap()}
+        };
+        let tweets = self.find_tweets(filter, None).await?;
+        let tweet_count = tweets.iter().filter(|t| t.created_at >= since).count();
+
+        let filter = doc! {
+            "comments": {"$elemMatch": {
+                "user_id": user_id,
+                "created_at": {"$gte": bson::to_bson(&since).unwrap()}
+            }}
+        };
+        let commented = self.find_tweets(filter, None).await?;
+        let comment_count = commented
+            .iter()
+            .flat_map(|t| &t.comments)
+            .filter(|c| c.user_id == Some(user_id) && c.created_at >= since)
+            .count();
+
+        let messages = tweets
+            .into_iter()
+            .filter(|t| t.created_at >= messages_since)
+            .map(|t| t.message)
+            .collect();
+        Ok((tweet_count + comment_count, messages))
+    }
+
+    /// Get every tweet of the user for their data export, newest first
+    pub async fn account_tweets(&self, user_id: &str) -> Result<Vec<TweetDto>, TweetError> {
+        let filter = doc! {"user_id": parse_user_id(user_id)?};
+        let options = FindOptions::builder().sort(doc! {"created_at": -1}).build();
+        let tweets = self.find_tweets(filter, options).await?;
+        Ok(tweets.iter().map(|t| t.map()).collect())
+    }
+
+    /// Get every comment of the user for their data export
+    pub async fn account_comments(&self, user_id: &str) -> Result<Vec<CommentDto>, TweetError> {
+        let user_id = parse_user_id(user_id)?;
+        let tweets = self
+            .find_tweets(doc! {"comments.user_id": user_id}, None)
+            .await?;
+        Ok(tweets
+            .iter()
+            .flat_map(|t| &t.comments)
+            .filter(|c| c.user_id == Some(user_id))
+            .map(|c| c.map())
+            .collect())
+    }
+
+    /// Get the ids of the tweets the user liked for their data export
+    pub async fn liked_tweet_ids(&self, user_id: &str) -> Result<Vec<String>, TweetError> {
+        let filter = doc! {"likes.user_id": parse_user_id(user_id)?};
+        let tweets = self.find_tweets(filter, None).await?;
+        Ok(tweets
+            .iter()
+            .filter_map(|t| t.id)
+            .map(|id| id.to_hex())
+            .collect())
+    }
+
+    /// Deletes the user's tweets and removes their comments and likes from other tweets,
+    /// returning the ids of the deleted tweets
+    pub async fn erase_user(&self, user_id: &str) -> Result<Vec<String>, TweetError> {
+        let user_id = parse_user_id(user_id)?;
+        let tweets = self.find_tweets(doc! {"user_id": user_id}, None).await?;
+        self.collection
+            .delete_many(doc! {"user_id": user_id}, None)
+            .await
+            .map_err(|_| TweetError::InternalServerError)?;
+        self.collection
+            .update_many(
+                doc! {"$or": [{"comments.user_id": user_id}, {"likes.user_id": user_id}]},
+                doc! {
+                    "$pull": {"comments": {"user_id": user_id}, "likes": {"user_id": user_id}},
+                    "$inc": {"version": 1}
+                },
+                None,
+            )
+            .await
+            .map_err(|_| TweetError::InternalServerError)?;
+        Ok(tweets
+            .iter()
+            .filter_map(|t| t.id)
+            .map(|id| id.to_hex())
+            .collect())
+    }
+
+    /// Get the hashtags of tweets created since the given time, with their creation time.
+    /// Trends are global, so only public tweets everyone can see are counted
+    pub async fn hashtags_since(
+        &self,
+        since: DateTime<Utc>,
+    ) -> Result<Vec<(String, DateTime<Utc>)>, TweetError> {
+        let mut hidden_authors = self.users.protected_ids().await?;
+        hidden_authors.extend(self.users.limited_ids().await?);
+        let filter = doc! {
+            "created_at": {"$gte": bson::to_bson(&since).unwrap()},
+            "entities.hashtags.0": {"$exists": true},
+            "visibility": {"$in": [null, "public"]},
+            "withheld": {"$ne": true},
+            "held_reason": null,
+            "user_id": {"$nin": hidden_authors}
+        };
+        let tweets = self.find_tweets(filter, None).await?;
+        let hashtags = tweets
+            .iter()
+            .flat_map(|t| {
+                t.entities
+                    .hashtags
+                    .iter()
+                    .map(|h| (h.text.to_lowercase(), t.created_at))
+            })
+            .collect();
+        Ok(hashtags)
+    }
+
+    /// Creates the text index `search_tweets` relies on
+    pub async fn create_text_index(&self) -> Result<(), TweetError> {
+        let index = IndexModel::builder().keys(doc! {"message": "text"}).build();
+        self.collection
+            .create_index(index, None)
+            .await
+            .map_err(|_| TweetError::InternalServerError)?;
+        Ok(())
+    }
+
+    /// Searches tweets using the text index on `message`
+    pub async fn search_tweets(
+        &self,
+        query: &SearchQuery,
+        author_id: Option<ObjectId>,
+        audience: &Audience,
+        sort: SearchSort,
+        page: &PageQuery,
+    ) -> Result<Vec<TweetDto>, TweetError> {
+        let mut filter = doc! {};
+        let mut conditions = vec![audience.filter()];
+        if query.has_text() {
+            let mut search = query.terms.join(" ");
+            for phrase in &query.phrases {
+                search.push_str(&format!(" \"{}\"", phrase));
+            }
+            filter.insert("$text", doc! {"$search": search});
+        }
+        if let Some(author_id) = author_id {
+            filter.insert("user_id", author_id);
+        }
+        conditions.extend(
+            query
+                .hashtags
+                .iter()
+                .map(|tag| doc! {"entities.hashtags.text": {"$regex": format!("^{}$", regex::escape(tag)), "$options": "i"}}),
+        );
+        filter.insert("$and", conditions);
+        let mut created_at = doc! {};
+        if let Some(since) = query.since {
+            created_at.insert("$gte", bson::to_bson(&since).unwrap());
+        }
+        if let Some(until) = query.until {
+            created_at.insert("$lt", bson::to_bson(&until).unwrap());
+        }
+        if !created_at.is_empty() {
+            filter.insert("created_at", created_at);
+        }
+        if query.has_media {
+            filter.insert("media.0", doc! {"$exists": true});
+        }
+
+        let by_relevance = sort == SearchSort::Relevance && query.has_text();
+        let options = FindOptions::builder()
+            .projection(by_relevance.then(|| doc! {"score": {"$meta": "textScore"}}))
+            .sort(if by_relevance {
+                doc! {"score": {"$meta": "textScore"}, "created_at": -1}
+            } else {
+                doc! {"created_at": -1}
+            })
+            .skip(page.skip())
+            .limit(page.limit() as i64)
+            .build();
+        let tweets = self.find_tweets(filter, options).await?;
+        Ok(tweets.iter().map(|t| t.map()).collect())
+    }
+
+    /// Get the tweets referenced by ids, keeping the order of `ids`.
+    /// Deleted tweets are replaced by a placeholder until they are purged.
+    pub async fn referenced_tweets(&self, ids: &[ObjectId]) -> Result<Vec<TweetDto>, TweetError> {
+        let filter = doc! {"_id": {"$in": ids}};
+        let tweets = self.find_stored_tweets(filter, None).await?;
+        let mut dtos = tweets
+            .iter()
+            .map(|t| match t.deleted_at {
+                Some(_) => t.placeholder(),
+                None => t.map(),
+            })
+            .collect::<Vec<TweetDto>>();
+        dtos.sort_by_key(|t| ids.iter().position(|id| id.to_hex() == t.id));
+        Ok(dtos)
+    }
+
+    /// Get tweets by ids, keeping the order of `ids`
+    pub async fn tweets_by_ids(&self, ids: &[ObjectId]) -> Result<Vec<TweetDto>, TweetError> {
+        let filter = doc! {"_id": {"$in": ids}};
+        let tweets = self.find_tweets(filter, None).await?;
+        let mut dtos = tweets.iter().map(|t| t.map()).collect::<Vec<TweetDto>>();
+        dtos.sort_by_key(|t| ids.iter().position(|id| id.to_hex() == t.id));
+        Ok(dtos)
+    }
+
+    /// Get every stored tweet, used to build in-memory indexes
+    pub async fn every_tweet(&self) -> Result<Vec<TweetDto>, TweetError> {
+        let tweets = self.find_tweets(doc! {}, None).await?;
+        Ok(tweets.iter().map(|t| t.map()).collect())
+    }
+
+    /// Marks a tweet deleted, it is hidden from every read until restored or purged.
+    /// Only deletes it if it is still at `expected_version` when one is given
+    pub async fn delete_tweet(
+        &self,
+        id: &str,
+        deleted_by: &str,
+        expected_version: Option<u64>,
+    ) -> Result<DeletedTweetDto, TweetError> {
+        let tweet = self.find_tweet(id).await?;
+        let deleted_by = parse_user_id(deleted_by)?;
+        let deleted_at = Utc::now();
+        let result = self
             .collection
-            .delete_one(filter, None)
+            .update_one(
+                version_filter(tweet.id, expected_version),
+                doc! {
+                    "$set": {
+                        "deleted_at": bson::to_bson(&deleted_at).unwrap(),
+                        "deleted_by": deleted_by
+                    },
+                    "$inc": {"version": 1}
+                },
+                None,
+            )
             .await
-            .ok()
-            .expect("Failed to retrieve tweet");
-        Ok(_tweet)
+            .map_err(|_| TweetError::InternalServerError)?;
+        if result.matched_count == 0 {
+            return Err(tweet_changed());
+        }
+        if let Err(err) = self.users.unpin_tweet(tweet.id.unwrap()).await {
+            log::error!("Failed to unpin deleted tweet: {}", err);
+        }
+        Ok(DeletedTweetDto {
+            id: id.to_string(),
+            deleted_at,
+            restorable_until: deleted_at + tweet_restore_window(),
+        })
     }
 
-    pub async fn create_like(&self, tweet_id: &str) -> Result<TweetDto, Error> {
-        let _id = ObjectId::parse_str(tweet_id).expect("Invalid tweet Id provided");
-        let tweet_dto = self.get_tweet(tweet_id).await.unwrap();
-        let mut tweet = tweet_dto.to_tweet();
-        tweet.add_like(Like::new(tweet_id));
-        let query = doc! {"_id": _id };
-        let _like = self
+    /// Deletes a tweet on behalf of its author
+    pub async fn delete_own_tweet(
+        &self,
+        id: &str,
+        user_id: &str,
+        expected_version: Option<u64>,
+    ) -> Result<DeletedTweetDto, TweetError> {
+        if !self.find_tweet(id).await?.is_author(user_id) {
+            return Err(TweetError::Forbidden(
+                "Only the author can delete this tweet".into(),
+            ));
+        }
+        self.delete_tweet(id, user_id, expected_version).await
+    }
+
+    /// Restores a tweet its author deleted, within the restore window
+    pub async fn restore_tweet(&self, id: &str, user_id: &str) -> Result<TweetDto, TweetError> {
+        let _id = ObjectId::parse_str(id)
+            .map_err(|_| TweetError::BadRequest("Invalid tweet Id provided".into()))?;
+        let filter = doc! {"_id": _id, "deleted_at": {"$ne": null}};
+        let mut tweet = match self.find_stored_tweets(filter, None).await?.pop() {
+            Some(tweet) if tweet.is_author(user_id) => tweet,
+            _ => {
+                return Err(TweetError::NotFound(format!(
+                    "No deleted tweet with id {} found",
+                    id
+                )))
+            }
+        };
+        if !tweet.is_restorable() {
+            return Err(TweetError::Forbidden(
+                "This tweet can no longer be restored".into(),
+            ));
+        }
+        self.collection
+            .update_one(
+                doc! {"_id": _id},
+                doc! {
+                    "$set": {"deleted_at": null, "deleted_by": null},
+                    "$inc": {"version": 1}
+                },
+                None,
+            )
+            .await
+            .map_err(|_| TweetError::InternalServerError)?;
+        tweet.deleted_at = None;
+        tweet.deleted_by = None;
+        tweet.version += 1;
+        Ok(tweet.map())
+    }
+
+    /// Removes the tweets deleted before the given time for good, returning how many were purged
+    pub async fn purge_deleted(&self, before: DateTime<Utc>) -> Result<u64, TweetError> {
+        let filter = doc! {"deleted_at": {"$ne": null, "$lte": bson::to_bson(&before).unwrap()}};
+        let result = self
+            .collection
+            .delete_many(filter, None)
+            .await
+            .map_err(|_| TweetError::InternalServerError)?;
+        Ok(result.deleted_count)
+    }
+
+    /// Likes a tweet, a user can like a tweet only once
+    pub async fn create_like(&self, tweet_id: &str, user_id: &str) -> Result<TweetDto, TweetError> {
+        let _id = ObjectId::parse_str(tweet_id)
+            .map_err(|_| TweetError::BadRequest("Invalid tweet Id provided".into()))?;
+        let liker_id = parse_user_id(user_id)?;
+        let mut tweet = self.find_visible_tweet(tweet_id, user_id).await?;
+        let like = Like::new(tweet_id, user_id);
+        let notification = tweet
+            .user_id
+            .zip(like.user_id)
+            .map(|(author_id, actor_id)| {
+                Notification::new(author_id, actor_id, NotificationKind::Like, Some(_id))
+            });
+        // The filter keeps a concurrent like from the same user from being added twice
+        let query = doc! {"_id": _id, "likes.user_id": {"$ne": liker_id}};
+        let update = doc! {
+            "$push": {"likes": bson::to_bson(&like).unwrap()},
+            "$inc": {"version": 1}
+        };
+        let result = self
             .collection
-            .update_one(query, update_tweet_document(&tweet), None)
+            .update_one(query, update, None)
             .await
-            .ok()
-            .expect("Error creating like");
+            .map_err(|_| TweetError::InternalServerError)?;
+        if result.matched_count == 0 {
+            return Err(TweetError::Conflict("You already liked this tweet".into()));
+        }
+        tweet.add_like(like);
+        self.publish_stats(&tweet);
+        if let Some(notification) = notification {
+            self.notify(notification).await;
+        }
         Ok(tweet.map())
     }
 
-    pub async fn remove_like(&self, tweet_id: &str, id: &str) -> Result<TweetDto, Error> {
-        let _id = ObjectId::parse_str(tweet_id).expect("Invalid tweet Id provided");
-        let tweet_dto = self.get_tweet(tweet_id).await.unwrap();
-        let mut tweet = tweet_dto.to_tweet();
-        tweet.remove_like(id);
+    /// Removes a like, only its owner or the tweet author may do so
+    pub async fn remove_like(
+        &self,
+        tweet_id: &str,
+        like_id: &str,
+        user_id: &str,
+    ) -> Result<TweetDto, TweetError> {
+        let like_id = ObjectId::parse_str(like_id)
+            .map_err(|_| TweetError::BadRequest("Invalid like Id provided".into()))?;
+        let mut tweet = self.find_visible_tweet(tweet_id, user_id).await?;
+        let like = match tweet.likes.iter().find(|l| l.id == Some(like_id)) {
+            Some(like) => like,
+            None => {
+                return Err(TweetError::NotFound(format!(
+                    "No like with id {} found",
+                    like_id
+                )))
+            }
+        };
+        if !is_owner(like.user_id, user_id) && !tweet.is_author(user_id) {
+            return Err(TweetError::Forbidden(
+                "Only the owner of the like or the tweet author can remove it".into(),
+            ));
+        }
+        tweet.remove_like(like_id);
 
-        let query = doc! {"_id": _id };
-        let _like = self
+        let query = doc! {"_id": tweet.id, "likes._id": like_id};
+        let update = doc! {"$pull": {"likes": {"_id": like_id}}, "$inc": {"version": 1}};
+        let result = self
             .collection
-            .update_one(query, update_tweet_document(&tweet), None)
+            .update_one(query, update, None)
             .await
-            .ok()
-            .expect("Error removing like");
+            .map_err(|_| TweetError::InternalServerError)?;
+        if result.matched_count == 0 {
+            return Err(TweetError::NotFound(format!(
+                "No like with id {} found",
+                like_id
+            )));
+        }
+        self.publish_stats(&tweet);
         Ok(tweet.map())
     }
 
-    pub async fn add_comment(&self, tweet_id: &str, message: &str) -> Result<TweetDto, Error> {
-        let _id = ObjectId::parse_str(tweet_id).expect("Invalid tweet Id provided");
-        let tweet_dto = self.get_tweet(tweet_id).await.unwrap();
-        let mut tweet = tweet_dto.to_tweet();
-        tweet.add_comment(Comment::new(tweet_id, message));
+    pub async fn add_comment(
+        &self,
+        tweet_id: &str,
+        comment: Comment,
+    ) -> Result<TweetDto, TweetError> {
+        let _id = ObjectId::parse_str(tweet_id)
+            .map_err(|_| TweetError::BadRequest("Invalid tweet Id provided".into()))?;
+        let mut tweet = match comment.user_id {
+            Some(user_id) => {
+                let tweet = self.find_visible_tweet(tweet_id, &user_id.to_hex()).await?;
+                if !self.can_reply(&tweet, user_id).await? {
+                    return Err(TweetError::Forbidden(
+                        "You cannot reply to this tweet".into(),
+                    ));
+                }
+                tweet
+            }
+            None => self.find_tweet(tweet_id).await?,
+        };
+        let notification = tweet
+            .user_id
+            .zip(comment.user_id)
+            .filter(|_| comment.held_reason.is_none())
+            .map(|(author_id, actor_id)| {
+                Notification::new(author_id, actor_id, NotificationKind::Comment, Some(_id))
+            });
+        let update = doc! {
+            "$push": {"comments": bson::to_bson(&comment).unwrap()},
+            "$inc": {"version": 1}
+        };
+        tweet.add_comment(comment);
 
         let query = doc! {"_id": _id };
-        let _like = self
-            .collection
-            .update_one(query, update_tweet_document(&tweet), None)
+        self.collection
+            .update_one(query, update, None)
             .await
-            .ok()
-            .expect("Error adding comment");
+            .map_err(|_| TweetError::InternalServerError)?;
+        self.publish_stats(&tweet);
+        if let Some(notification) = notification {
+            self.notify(notification).await;
+        }
         Ok(tweet.map())
     }
 
+    /// Removes a comment whoever wrote it, used by moderators
     pub async fn remove_comment(
         &self,
         tweet_id: &str,
         comment_id: &str,
-    ) -> Result<TweetDto, Error> {
-        let _id = ObjectId::parse_str(tweet_id).expect("Invalid tweet Id provided");
-        let tweet_dto = self.get_tweet(tweet_id).await.unwrap();
-        let mut tweet = tweet_dto.to_tweet();
+    ) -> Result<TweetDto, TweetError> {
+        let comment_id = parse_comment_id(comment_id)?;
+        let mut tweet = self.find_tweet(tweet_id).await?;
+        if !tweet.comments.iter().any(|c| c.id == Some(comment_id)) {
+            return Err(comment_not_found(comment_id));
+        }
         tweet.remove_comment(comment_id);
 
-        let query = doc! {"_id": _id };
-        let _like = self
+        let query = doc! {"_id": tweet.id, "comments.id": comment_id};
+        let update = doc! {"$pull": {"comments": {"id": comment_id}}, "$inc": {"version": 1}};
+        let result = self
             .collection
-            .update_one(query, update_tweet_document(&tweet), None)
+            .update_one(query, update, None)
             .await
-            .ok()
-            .expect("Error removing comment");
+            .map_err(|_| TweetError::InternalServerError)?;
+        if result.matched_count == 0 {
+            return Err(comment_not_found(comment_id));
+        }
+        self.publish_stats(&tweet);
         Ok(tweet.map())
     }
+
+    /// Removes a comment on behalf of its author or the tweet author
+    pub async fn remove_own_comment(
+        &self,
+        tweet_id: &str,
+        comment_id: &str,
+        user_id: &str,
+    ) -> Result<TweetDto, TweetError> {
+        let id = parse_comment_id(comment_id)?;
+        let tweet = self.find_visible_tweet(tweet_id, user_id).await?;
+        let comment = match tweet.comments.iter().find(|c| c.id == Some(id)) {
+            Some(comment) => comment,
+            None => return Err(comment_not_found(id)),
+        };
+        if !is_owner(comment.user_id, user_id) && !tweet.is_author(user_id) {
+            return Err(TweetError::Forbidden(
+                "Only the comment author or the tweet author can delete this comment".into(),
+            ));
+        }
+        self.remove_comment(tweet_id, comment_id).await
+    }
+
+    /// Withholds a tweet from everyone but its author, or releases it
+    pub async fn set_withheld(&self, id: &str, withheld: bool) -> Result<TweetDto, TweetError> {
+        let mut tweet = self.find_tweet(id).await?;
+        self.collection
+            .update_one(
+                doc! {"_id": tweet.id},
+                doc! {"$set": {"withheld": withheld}, "$inc": {"version": 1}},
+                None,
+            )
+            .await
+            .map_err(|_| TweetError::InternalServerError)?;
+        tweet.withheld = withheld;
+        tweet.version += 1;
+        Ok(tweet.map())
+    }
+
+    /// Withholds a tweet the content filter held until a moderator reviews it
+    pub async fn hold_tweet(&self, id: &str, reason: &str) -> Result<TweetDto, TweetError> {
+        let mut tweet = self.find_tweet(id).await?;
+        self.collection
+            .update_one(
+                doc! {"_id": tweet.id},
+                doc! {
+                    "$set": {"withheld": true, "held_reason": reason},
+                    "$inc": {"version": 1}
+                },
+                None,
+            )
+            .await
+            .map_err(|_| TweetError::InternalServerError)?;
+        tweet.withheld = true;
+        tweet.held_reason = Some(reason.to_string());
+        tweet.version += 1;
+        Ok(tweet.map())
+    }
+
+    /// Get the tweets and comments the content filter held for review, oldest first
+    pub async fn held_content(&self, page: &PageQuery) -> Result<Vec<HeldContentDto>, TweetError> {
+        let filter = doc! {
+            "$or": [
+                {"held_reason": {"$ne": null}},
+                {"comments.held_reason": {"$ne": null}}
+            ]
+        };
+        let options = FindOptions::builder()
+            .sort(doc! {"created_at": 1})
+            .skip(page.skip())
+            .limit(page.limit() as i64)
+            .build();
+        let tweets = self.find_tweets(filter, options).await?;
+        let mut held = Vec::<HeldContentDto>::new();
+        for tweet in tweets {
+            let tweet_id = tweet.id.unwrap().to_hex();
+            if let Some(reason) = &tweet.held_reason {
+                held.push(HeldContentDto {
+                    tweet_id: tweet_id.clone(),
+                    comment_id: None,
+                    user_id: tweet.user_id.map(|id| id.to_hex()),
+                    message: tweet.message.clone(),
+                    reason: reason.clone(),
+                    created_at: tweet.created_at,
+                });
+            }
+            for comment in &tweet.comments {
+                if let Some(reason) = &comment.held_reason {
+                    held.push(HeldContentDto {
+                        tweet_id: tweet_id.clone(),
+                        comment_id: comment.id.map(|id| id.to_hex()),
+                        user_id: comment.user_id.map(|id| id.to_hex()),
+                        message: comment.message.clone(),
+                        reason: reason.clone(),
+                        created_at: comment.created_at,
+                    });
+                }
+            }
+        }
+        Ok(held)
+    }
+
+    /// Publishes or removes a held tweet, or one of its held comments
+    pub async fn review_held(
+        &self,
+        tweet_id: &str,
+        comment_id: Option<&str>,
+        approve: bool,
+        moderator_id: &str,
+    ) -> Result<String, TweetError> {
+        let _id = ObjectId::parse_str(tweet_id)
+            .map_err(|_| TweetError::BadRequest("Invalid tweet Id provided".into()))?;
+        let (filter, update) = match comment_id {
+            None if approve => (
+                doc! {"_id": _id, "held_reason": {"$ne": null}},
+                doc! {
+                    "$set": {"withheld": false, "held_reason": null},
+                    "$inc": {"version": 1}
+                },
+            ),
+            // Removed like any other tweet, so replies keep a placeholder until it is purged
+            None => {
+                if self.find_tweet(tweet_id).await?.held_reason.is_none() {
+                    return Err(TweetError::NotFound("No held tweet found".into()));
+                }
+                self.delete_tweet(tweet_id, moderator_id, None).await?;
+                return Ok("Held tweet removed".into());
+            }
+            Some(comment_id) => {
+                let comment_id = ObjectId::parse_str(comment_id)
+                    .map_err(|_| TweetError::BadRequest("Invalid comment Id provided".into()))?;
+                let filter = doc! {
+                    "_id": _id,
+                    "comments": {"$elemMatch": {"id": comment_id, "held_reason": {"$ne": null}}}
+                };
+                if approve {
+                    (
+                        filter,
+                        doc! {"$set": {"comments.$.held_reason": null}, "$inc": {"version": 1}},
+                    )
+                } else {
+                    (
+                        filter,
+                        doc! {"$pull": {"comments": {"id": comment_id}}, "$inc": {"version": 1}},
+                    )
+                }
+            }
+        };
+        let result = self
+            .collection
+            .update_one(filter, update, None)
+            .await
+            .map_err(|_| TweetError::InternalServerError)?;
+        if result.matched_count == 0 {
+            return Err(TweetError::NotFound("No held content found".into()));
+        }
+        Ok(if approve {
+            "Held content published".into()
+        } else {
+            "Held content removed".into()
+        })
+    }
+
+    /// Records the user's vote, allowing a single vote per user while the poll is open
+    pub async fn vote_poll(
+        &self,
+        tweet_id: &str,
+        user_id: &str,
+        option: usize,
+    ) -> Result<TweetDto, TweetError> {
+        let tweet = self.find_visible_tweet(tweet_id, user_id).await?;
+        let poll = match &tweet.poll {
+            Some(poll) => poll,
+            None => return Err(TweetError::NotFound("This tweet has no poll".into())),
+        };
+        if poll.is_closed() {
+            return Err(TweetError::Forbidden("This poll has closed".into()));
+        }
+        if option >= poll.options.len() {
+            return Err(TweetError::UnprocessableEntity(vec![FieldError::new(
+                "option",
+                "invalid_option",
+                &format!(
+                    "The option must be between 0 and {}",
+                    poll.options.len() - 1
+                ),
+            )]));
+        }
+
+        let voter_id = ObjectId::parse_str(user_id)
+            .map_err(|_| TweetError::BadRequest("Invalid user Id provided".into()))?;
+        let vote = PollVote {
+            user_id: voter_id,
+            option,
+            created_at: Utc::now(),
+        };
+        let filter = doc! {"_id": tweet.id, "poll.votes.user_id": {"$ne": voter_id}};
+        let update = doc! {
+            "$push": {"poll.votes": bson::to_bson(&vote).unwrap()},
+            "$inc": {format!("poll.options.{}.votes", option): 1, "version": 1}
+        };
+        let result = self
+            .collection
+            .update_one(filter, update, None)
+            .await
+            .map_err(|_| TweetError::InternalServerError)?;
+        if result.matched_count == 0 {
+            return Err(TweetError::Forbidden(
+                "You already voted in this poll".into(),
+            ));
+        }
+
+        let tweet = self.find_tweet(tweet_id).await?;
+        let mut dto = tweet.map();
+        dto.poll = tweet.poll.map(|p| p.map(Some(user_id)));
+        Ok(dto)
+    }
+
+    /// Includes the poll tallies of the tweets the viewer voted on
+    pub async fn reveal_polls(
+        &self,
+        viewer_id: &str,
+        tweets: &mut [TweetDto],
+    ) -> Result<(), TweetError> {
+        let hidden = tweets
+            .iter()
+            .filter(|t| t.poll.as_ref().is_some_and(|p| p.total_votes.is_none()))
+            .filter_map(|t| ObjectId::parse_str(&t.id).ok())
+            .collect::<Vec<ObjectId>>();
+        if hidden.is_empty() {
+            return Ok(());
+        }
+        let viewer = ObjectId::parse_str(viewer_id)
+            .map_err(|_| TweetError::BadRequest("Invalid user Id provided".into()))?;
+        let filter = doc! {"_id": {"$in": hidden}, "poll.votes.user_id": viewer};
+        for voted in self.find_tweets(filter, None).await? {
+            let id = voted.id.map(|id| id.to_hex());
+            if let Some(tweet) = tweets.iter_mut().find(|t| Some(&t.id) == id.as_ref()) {
+                tweet.poll = voted.poll.as_ref().map(|p| p.map(Some(viewer_id)));
+            }
+        }
+        Ok(())
+    }
+
+    /// Get who the viewer follows, which accounts are protected and which
+    /// authors the viewer blocked, muted or was blocked by
+    pub async fn audience(&self, viewer_id: &str) -> Result<Audience, TweetError> {
+        let to_hex = |ids: Vec<ObjectId>| ids.iter().map(|id| id.to_hex()).collect();
+        let mut audience = Audience::new(viewer_id);
+        audience.following = to_hex(self.follows.following_ids(viewer_id).await?);
+        audience.protected = to_hex(self.users.protected_ids().await?);
+        audience.hidden = to_hex(self.relations.hidden_ids(viewer_id).await?);
+        for id in self.users.limited_ids().await? {
+            let id = id.to_hex();
+            if id != viewer_id && !audience.following.contains(&id) {
+                audience.hidden.insert(id);
+            }
+        }
+        audience.mute_words(&self.users.find_user(viewer_id).await?.muted_words);
+        Ok(audience)
+    }
+
+    /// Keeps the ids of the tweets the audience may see, in the order of `ids`.
+    /// Invalid ids and deleted tweets are left out
+    pub async fn visible_tweet_ids(
+        &self,
+        ids: &[String],
+        audience: &Audience,
+    ) -> Result<Vec<String>, TweetError> {
+        let object_ids = ids
+            .iter()
+            .filter_map(|id| ObjectId::parse_str(id).ok())
+            .collect::<Vec<ObjectId>>();
+        if object_ids.is_empty() {
+            return Ok(vec![]);
+        }
+        let visible = self
+            .tweets_by_ids(&object_ids)
+            .await?
+            .into_iter()
+            .filter(|tweet| audience.allows_tweet(tweet))
+            .map(|tweet| tweet.id)
+            .collect::<Vec<String>>();
+        Ok(ids
+            .iter()
+            .filter(|id| visible.contains(id))
+            .cloned()
+            .collect())
+    }
+
+    /// Checks the reply policy of a tweet, the author can always reply
+    async fn can_reply(&self, tweet: &Tweet, user_id: ObjectId) -> Result<bool, TweetError> {
+        let author_id = match tweet.user_id {
+            Some(author_id) if author_id != user_id => author_id,
+            _ => return Ok(true),
+        };
+        Ok(match tweet.reply_policy {
+            ReplyPolicy::Everyone => true,
+            ReplyPolicy::Following => {
+                self.follows
+                    .is_following(&author_id.to_hex(), &user_id.to_hex())
+                    .await?
+            }
+            ReplyPolicy::Mentioned => tweet
+                .entities
+                .mentions
+                .iter()
+                .any(|m| m.user_id == Some(user_id)),
+        })
+    }
+
+    /// Publishes the like and comment counts of a tweet to clients viewing it
+    fn publish_stats(&self, tweet: &Tweet) {
+        if let Some(id) = tweet.id {
+            self.events.publish(EventPayload::TweetStats {
+                tweet_id: id.to_hex(),
+                like_count: tweet.likes.len(),
+                comment_count: tweet.comments.len(),
+            });
+        }
+    }
+
+    /// Sends a notification, logging failures instead of failing the calling operation
+    async fn notify(&self, notification: Notification) {
+        if let Err(err) = self.notifications.notify(notification).await {
+            log::error!("Failed to create notification: {}", err);
+        }
+    }
+
+    /// Get one page of the tweets matching the filter the viewer can see, newest first
+    async fn visible_tweets(
+        &self,
+        mut filter: Document,
+        viewer_id: &str,
+        page: &PageQuery,
+    ) -> Result<Vec<TweetDto>, TweetError> {
+        let audience = self.audience(viewer_id).await?;
+        filter.insert("$and", vec![audience.filter()]);
+        let options = FindOptions::builder()
+            .sort(doc! {"created_at": -1})
+            .skip(page.skip())
+            .limit(page.limit() as i64)
+            .build();
+        let tweets = self.find_tweets(filter, options).await?;
+        Ok(tweets.iter().map(|t| t.map()).collect())
+    }
+
+    /// Get tweet models matching the filter, leaving out deleted tweets
+    async fn find_tweets(
+        &self,
+        mut filter: Document,
+        options: impl Into<Option<FindOptions>>,
+    ) -> Result<Vec<Tweet>, TweetError> {
+        filter.insert("deleted_at", bson::Bson::Null);
+        self.find_stored_tweets(filter, options).await
+    }
+
+    /// Get tweet models matching the filter, deleted tweets included
+    async fn find_stored_tweets(
+        &self,
+        filter: Document,
+        options: impl Into<Option<FindOptions>>,
+    ) -> Result<Vec<Tweet>, TweetError> {
+        let mut cursor = self
+            .collection
+            .find(filter, options)
+            .await
+            .map_err(|_| TweetError::InternalServerError)?;
+        let mut tweets = Vec::<Tweet>::new();
+        while cursor
+            .advance()
+            .await
+            .map_err(|_| TweetError::InternalServerError)?
+        {
+            let tweet = cursor
+                .deserialize_current()
+                .map_err(|_| TweetError::InternalServerError)?;
+            tweets.push(tweet);
+        }
+        Ok(tweets)
+    }
+
+    /// Get tweet model by id, as not found when the viewer is not in its audience
+    /// or the viewer and the author blocked each other
+    async fn find_visible_tweet(&self, id: &str, viewer_id: &str) -> Result<Tweet, TweetError> {
+        let tweet = self.find_tweet(id).await?;
+        let author_id = match tweet.user_id {
+            Some(author_id) => author_id.to_hex(),
+            None => return Ok(tweet),
+        };
+        if author_id == viewer_id {
+            return Ok(tweet);
+        }
+        if tweet.withheld {
+            return Err(TweetError::NotFound(format!(
+                "No tweet with id {} found",
+                id
+            )));
+        }
+        let mut audience = Audience::new(viewer_id);
+        if self.relations.is_blocked(viewer_id, &author_id).await? {
+            audience.hidden.insert(author_id.clone());
+        }
+        if self.follows.is_following(viewer_id, &author_id).await? {
+            audience.following.insert(author_id.clone());
+        }
+        match self.users.find_user(&author_id).await {
+            Ok(author) if author.protected => {
+                audience.protected.insert(author_id.clone());
+            }
+            Ok(_) | Err(TweetError::NotFound(_)) => {}
+            Err(err) => return Err(err),
+        }
+        let mentioned = tweet
+            .entities
+            .mentions
+            .iter()
+            .filter_map(|m| m.user_id.map(|id| id.to_hex()))
+            .collect::<Vec<String>>();
+        if !audience.allows(&author_id, tweet.visibility, &mentioned) {
+            return Err(TweetError::NotFound(format!(
+                "No tweet with id {} found",
+                id
+            )));
+        }
+        Ok(tweet)
+    }
+
+    /// Get tweet model by id
+    async fn find_tweet(&self, id: &str) -> Result<Tweet, TweetError> {
+        let _id = ObjectId::parse_str(id)
+            .map_err(|_| TweetError::BadRequest("Invalid tweet Id provided".into()))?;
+        let filter = doc! {"_id": _id, "deleted_at": null};
+        let tweet = self
+            .collection
+            .find_one(filter, None)
+            .await
+            .map_err(|_| TweetError::InternalServerError)?;
+        match tweet {
+            Some(tweet) => Ok(tweet),
+            None => Err(TweetError::NotFound(format!(
+                "No tweet with id {} found",
+                id
+            ))),
+        }
+    }
+}
+
+/// Matches the tweet, and only at `expected_version` when one is given
+fn version_filter(id: Option<ObjectId>, expected_version: Option<u64>) -> Document {
+    let mut filter = doc! {"_id": id};
+    if let Some(version) = expected_version {
+        filter.insert("version", bson::to_bson(&version).unwrap());
+    }
+    filter
+}
+
+/// Checks whether the like or comment was made by the user
+fn is_owner(owner_id: Option<ObjectId>, user_id: &str) -> bool {
+    owner_id.map(|id| id.to_hex() == user_id).unwrap_or(false)
+}
+
+fn parse_comment_id(id: &str) -> Result<ObjectId, TweetError> {
+    ObjectId::parse_str(id)
+        .map_err(|_| TweetError::BadRequest("Invalid comment Id provided".into()))
+}
+
+fn comment_not_found(id: ObjectId) -> TweetError {
+    TweetError::NotFound(format!("No comment with id {} found", id))
+}
+
+fn tweet_changed() -> TweetError {
+    TweetError::PreconditionFailed("The tweet was changed since it was last read".into())
+}
+
+fn parse_user_id(id: &str) -> Result<ObjectId, TweetError> {
+    ObjectId::parse_str(id).map_err(|_| TweetError::BadRequest("Invalid user Id provided".into()))
 }
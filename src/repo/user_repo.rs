@@ -1,14 +1,35 @@
 use crate::{
     auths::auth::{AuthData, ChangePasswordRequest},
-    dtos::dto::UserDto,
+    config::settings::{account_deletion_grace_period, restricted_ids_cache_seconds},
+    dtos::dto::{UserDto, UserProfileDto},
     errors::error::TweetError,
-    model::{auth_model::User, docs::update_user_document},
+    model::{
+        auth_model::User, docs::update_user_document, pagination::PageQuery,
+        tweet_entity::TweetEntities,
+    },
 };
-use bson::{doc, oid::ObjectId};
-use mongodb::{Collection, Cursor};
+use bson::{doc, oid::ObjectId, Document};
+use chrono::{DateTime, Utc};
+use mongodb::{options::FindOptions, Collection, Cursor};
+use std::{
+    collections::HashMap,
+    sync::{Arc, RwLock},
+    time::{Duration, Instant},
+};
+
+/// Ids of the protected and shadow limited accounts. Every timeline, search and stream
+/// request needs them, so they are cached for a short while.
+#[derive(Default)]
+pub struct RestrictedIds {
+    protected: Vec<ObjectId>,
+    limited: Vec<ObjectId>,
+    loaded_at: Option<Instant>,
+}
 
+#[derive(Clone)]
 pub struct UserRepo<User> {
     pub collection: Collection<User>,
+    pub restricted: Arc<RwLock<RestrictedIds>>,
 }
 
 impl UserRepo<User> {
@@ -25,6 +46,19 @@ impl UserRepo<User> {
             }
             Err(_) => return Err(TweetError::InternalServerError),
         };
+        if let Some(username) = &user.username {
+            let existing = self
+                .collection
+                .find_one(doc! {"username": username}, None)
+                .await
+                .map_err(|_| TweetError::InternalServerError)?;
+            if existing.is_some() {
+                return Err(TweetError::BadRequest(format!(
+                    "Username {} is already taken",
+                    username
+                )));
+            }
+        }
         let _user = self
             .collection
             .insert_one(user, None)
@@ -60,6 +94,13 @@ impl UserRepo<User> {
                         None => return Err(TweetError::Unauthorized("authentication failed, please check that email and/or password are correct".into()))
                         
                     };
+                    if _user.suspended {
+                        return Err(TweetError::Forbidden("This account is suspended".into()));
+                    }
+                    // Signing in during the grace period keeps the account
+                    if _user.deletion_requested_at.is_some() {
+                        self.cancel_deletion(_user.id.unwrap()).await?;
+                    }
                     return Ok(token);
                 }
                 return Err(TweetError::Unauthorized(
@@ -117,6 +158,357 @@ impl UserRepo<User> {
         };
     } 
 
+    /// Resolves mentioned usernames to the ids of existing users
+    pub async fn resolve_mentions(&self, entities: &mut TweetEntities) -> Result<(), TweetError> {
+        let usernames = entities
+            .mentions
+            .iter()
+            .map(|m| m.text.to_lowercase())
+            .collect::<Vec<String>>();
+        if usernames.is_empty() {
+            return Ok(());
+        }
+        let filter = doc! {"username": {"$in": usernames}};
+        let mut cursor = self
+            .collection
+            .find(filter, None)
+            .await
+            .map_err(|_| TweetError::InternalServerError)?;
+        let mut user_ids = HashMap::<String, ObjectId>::new();
+        while cursor
+            .advance()
+            .await
+            .map_err(|_| TweetError::InternalServerError)?
+        {
+            let user = cursor
+                .deserialize_current()
+                .map_err(|_| TweetError::InternalServerError)?;
+            if let (Some(username), Some(id)) = (user.username, user.id) {
+                user_ids.insert(username, id);
+            }
+        }
+        for mention in entities.mentions.iter_mut() {
+            mention.user_id = user_ids.get(&mention.text.to_lowercase()).copied();
+        }
+        Ok(())
+    }
+
+    /// Get user by id
+    pub async fn find_user(&self, id: &str) -> Result<User, TweetError> {
+        let _id = ObjectId::parse_str(id)
+            .map_err(|_| TweetError::BadRequest("Invalid user Id provided".into()))?;
+        let user = self
+            .collection
+            .find_one(doc! {"_id": _id}, None)
+            .await
+            .map_err(|_| TweetError::InternalServerError)?;
+        match user {
+            Some(user) => Ok(user),
+            None => Err(TweetError::NotFound(format!(
+                "No user with id {} found",
+                id
+            ))),
+        }
+    }
+
+    /// Get the public profiles of the given users
+    pub async fn profiles(&self, ids: &[ObjectId]) -> Result<Vec<UserProfileDto>, TweetError> {
+        let filter = doc! {"_id": {"$in": ids}};
+        let mut cursor = self
+            .collection
+            .find(filter, None)
+            .await
+            .map_err(|_| TweetError::InternalServerError)?;
+        let mut profiles = Vec::<UserProfileDto>::new();
+        while cursor
+            .advance()
+            .await
+            .map_err(|_| TweetError::InternalServerError)?
+        {
+            let user = cursor
+                .deserialize_current()
+                .map_err(|_| TweetError::InternalServerError)?;
+            profiles.push(user.map());
+        }
+        Ok(profiles)
+    }
+
+    /// Allows or disallows direct messages from users who are not mutual follows
+    pub async fn set_open_dms(&self, id: &str, open_dms: bool) -> Result<String, TweetError> {
+        let user = self.find_user(id).await?;
+        self.collection
+            .update_one(
+                doc! {"_id": user.id},
+                doc! {"$set": {"open_dms": open_dms}},
+                None,
+            )
+            .await
+            .map_err(|_| TweetError::InternalServerError)?;
+        Ok("Direct message settings updated".into())
+    }
+
+    /// Protects or unprotects the user's tweets, protected tweets are only visible to followers
+    pub async fn set_protected(&self, id: &str, protected: bool) -> Result<String, TweetError> {
+        let user = self.find_user(id).await?;
+        self.collection
+            .update_one(
+                doc! {"_id": user.id},
+                doc! {"$set": {"protected": protected}},
+                None,
+            )
+            .await
+            .map_err(|_| TweetError::InternalServerError)?;
+        self.forget_restricted_ids();
+        Ok("Privacy settings updated".into())
+    }
+
+    /// Suspends or reinstates an account, suspended users cannot sign in or tweet.
+    /// Suspending also revokes the tokens the user already holds
+    pub async fn set_suspended(&self, id: &str, suspended: bool) -> Result<(), TweetError> {
+        let user = self.find_user(id).await?;
+        let mut update = doc! {"$set": {"suspended": suspended}};
+        if suspended {
+            update.insert("$inc", doc! {"token_generation": 1});
+        }
+        self.collection
+            .update_one(doc! {"_id": user.id}, update, None)
+            .await
+            .map_err(|_| TweetError::InternalServerError)?;
+        Ok(())
+    }
+
+    /// Schedules the deletion of the account and revokes its tokens, returns when it is erased
+    pub async fn request_deletion(
+        &self,
+        id: &str,
+        password: &str,
+    ) -> Result<DateTime<Utc>, TweetError> {
+        let user = self.find_user(id).await?;
+        if !user.verify_password(password) {
+            return Err(TweetError::BadRequest("Invalid password provided.".into()));
+        }
+        let now = bson::to_bson(&Utc::now()).unwrap();
+        self.collection
+            .update_one(
+                doc! {"_id": user.id},
+                doc! {
+                    "$set": {"deletion_requested_at": &now},
+                    "$inc": {"token_generation": 1}
+                },
+                None,
+            )
+            .await
+            .map_err(|_| TweetError::InternalServerError)?;
+        Ok(Utc::now() + account_deletion_grace_period())
+    }
+
+    /// Cancels a pending deletion of the account
+    pub async fn cancel_deletion(&self, id: ObjectId) -> Result<(), TweetError> {
+        self.collection
+            .update_one(
+                doc! {"_id": id},
+                doc! {"$set": {"deletion_requested_at": null}},
+                None,
+            )
+            .await
+            .map_err(|_| TweetError::InternalServerError)?;
+        Ok(())
+    }
+
+    /// Get the ids of the accounts whose deletion was requested before the given time
+    pub async fn due_deletions(&self, before: DateTime<Utc>) -> Result<Vec<ObjectId>, TweetError> {
+        let filter = doc! {"deletion_requested_at": {"$lte": bson::to_bson(&before).unwrap()}};
+        self.ids_matching(filter).await
+    }
+
+    pub async fn delete_user(&self, id: ObjectId) -> Result<(), TweetError> {
+        self.collection
+            .delete_one(doc! {"_id": id}, None)
+            .await
+            .map_err(|_| TweetError::InternalServerError)?;
+        self.forget_restricted_ids();
+        Ok(())
+    }
+
+    /// Shadow limits an account or lifts the limit
+    pub async fn set_limited(&self, id: &str, limited: bool) -> Result<(), TweetError> {
+        let user = self.find_user(id).await?;
+        self.collection
+            .update_one(
+                doc! {"_id": user.id},
+                doc! {"$set": {"limited": limited}},
+                None,
+            )
+            .await
+            .map_err(|_| TweetError::InternalServerError)?;
+        self.forget_restricted_ids();
+        Ok(())
+    }
+
+    /// Replaces the words hiding tweets from the user's listings
+    pub async fn set_muted_words(&self, id: &str, words: &[String]) -> Result<(), TweetError> {
+        let user = self.find_user(id).await?;
+        self.collection
+            .update_one(
+                doc! {"_id": user.id},
+                doc! {"$set": {"muted_words": words}},
+                None,
+            )
+            .await
+            .map_err(|_| TweetError::InternalServerError)?;
+        Ok(())
+    }
+
+    /// Pins one of the user's tweets to their profile, or unpins it with `None`
+    pub async fn set_pinned(&self, id: &str, tweet_id: Option<ObjectId>) -> Result<(), TweetError> {
+        let user = self.find_user(id).await?;
+        self.collection
+            .update_one(
+                doc! {"_id": user.id},
+                doc! {"$set": {"pinned_tweet_id": tweet_id}},
+                None,
+            )
+            .await
+            .map_err(|_| TweetError::InternalServerError)?;
+        Ok(())
+    }
+
+    /// Unpins a tweet from the profile it is pinned to, used when the tweet is deleted
+    pub async fn unpin_tweet(&self, tweet_id: ObjectId) -> Result<(), TweetError> {
+        self.collection
+            .update_many(
+                doc! {"pinned_tweet_id": tweet_id},
+                doc! {"$set": {"pinned_tweet_id": null}},
+                None,
+            )
+            .await
+            .map_err(|_| TweetError::InternalServerError)?;
+        Ok(())
+    }
+
+    /// Get the ids of the protected accounts
+    pub async fn protected_ids(&self) -> Result<Vec<ObjectId>, TweetError> {
+        Ok(self.restricted_ids().await?.0)
+    }
+
+    /// Get the ids of the shadow limited accounts
+    pub async fn limited_ids(&self) -> Result<Vec<ObjectId>, TweetError> {
+        Ok(self.restricted_ids().await?.1)
+    }
+
+    /// Get the ids of the protected and of the shadow limited accounts, cached until they
+    /// change or `restricted_ids_cache_seconds` pass
+    async fn restricted_ids(&self) -> Result<(Vec<ObjectId>, Vec<ObjectId>), TweetError> {
+        let ttl = Duration::from_secs(restricted_ids_cache_seconds());
+        {
+            let cached = self.restricted.read().unwrap();
+            if cached.loaded_at.is_some_and(|at| at.elapsed() < ttl) {
+                return Ok((cached.protected.clone(), cached.limited.clone()));
+            }
+        }
+        let protected = self.ids_matching(doc! {"protected": true}).await?;
+        let limited = self.ids_matching(doc! {"limited": true}).await?;
+        *self.restricted.write().unwrap() = RestrictedIds {
+            protected: protected.clone(),
+            limited: limited.clone(),
+            loaded_at: Some(Instant::now()),
+        };
+        Ok((protected, limited))
+    }
+
+    fn forget_restricted_ids(&self) {
+        self.restricted.write().unwrap().loaded_at = None;
+    }
+
+    async fn ids_matching(&self, filter: Document) -> Result<Vec<ObjectId>, TweetError> {
+        let mut cursor = self
+            .collection
+            .find(filter, None)
+            .await
+            .map_err(|_| TweetError::InternalServerError)?;
+        let mut ids = Vec::<ObjectId>::new();
+        while cursor
+            .advance()
+            .await
+            .map_err(|_| TweetError::InternalServerError)?
+        {
+            let user = cursor
+                .deserialize_current()
+                .map_err(|_| TweetError::InternalServerError)?;
+            ids.extend(user.id);
+        }
+        Ok(ids)
+    }
+
+    /// Get the usernames of the given users, skipping users without one
+    pub async fn usernames(
+        &self,
+        ids: &[ObjectId],
+    ) -> Result<HashMap<ObjectId, String>, TweetError> {
+        let filter = doc! {"_id": {"$in": ids}};
+        let mut cursor = self
+            .collection
+            .find(filter, None)
+            .await
+            .map_err(|_| TweetError::InternalServerError)?;
+        let mut usernames = HashMap::<ObjectId, String>::new();
+        while cursor
+            .advance()
+            .await
+            .map_err(|_| TweetError::InternalServerError)?
+        {
+            let user = cursor
+                .deserialize_current()
+                .map_err(|_| TweetError::InternalServerError)?;
+            if let (Some(id), Some(username)) = (user.id, user.username) {
+                usernames.insert(id, username);
+            }
+        }
+        Ok(usernames)
+    }
+
+    /// Get user by username
+    pub async fn find_by_username(&self, username: &str) -> Result<Option<User>, TweetError> {
+        let filter = doc! {"username": username.to_lowercase()};
+        self.collection
+            .find_one(filter, None)
+            .await
+            .map_err(|_| TweetError::InternalServerError)
+    }
+
+    /// Searches users whose username starts with the given prefix
+    pub async fn search_users(
+        &self,
+        prefix: &str,
+        page: &PageQuery,
+    ) -> Result<Vec<UserProfileDto>, TweetError> {
+        let filter = doc! {
+            "username": {"$regex": format!("^{}", regex::escape(&prefix.to_lowercase()))}
+        };
+        let options = FindOptions::builder()
+            .sort(doc! {"username": 1})
+            .skip(page.skip())
+            .limit(page.limit() as i64)
+            .build();
+        let mut cursor = self
+            .collection
+            .find(filter, options)
+            .await
+            .map_err(|_| TweetError::InternalServerError)?;
+        let mut users = Vec::<UserProfileDto>::new();
+        while cursor
+            .advance()
+            .await
+            .map_err(|_| TweetError::InternalServerError)?
+        {
+            let user = cursor
+                .deserialize_current()
+                .map_err(|_| TweetError::InternalServerError)?;
+            users.push(user.map());
+        }
+        Ok(users)
+    }
+
     /// Get user by email address
     async fn get_user_by_email(&self, email: &str) -> Result<Cursor<User>, TweetError> {
         let filter = doc! {"email": &email};
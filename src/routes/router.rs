@@ -3,11 +3,48 @@ use actix_web_httpauth::middleware::HttpAuthentication;
 
 use crate::{
     api::{
+        audit_api::{export_audit_events, list_audit_events},
+        bookmark_api::{add_bookmark, list_bookmarks, remove_bookmark},
+        dm_api::{
+            delete_message, list_conversations, list_messages, mark_conversation_read,
+            send_message, start_conversation, update_dm_settings,
+        },
+        draft_api::{
+            cancel_scheduled, create_draft, delete_draft, get_draft, list_drafts, list_scheduled,
+            publish_draft, schedule_tweet, update_draft, update_scheduled,
+        },
+        follow_api::{
+            approve_follow_request, follow_requests, follow_user, followers, following,
+            reject_follow_request, unfollow_user, update_privacy,
+        },
+        hashtag_api::{hashtag_tweets, trends},
+        import_api::import_archive,
         like_api::{minus_one, plus_one},
+        list_api::{
+            add_list_member, create_list, delete_list, get_list, list_members, list_timeline,
+            my_lists, remove_list_member, update_list, user_lists,
+        },
+        media_api::{get_media, get_media_thumbnail, update_media, upload_media},
+        notification_api::{
+            get_preferences, list_notifications, mark_all_read, mark_read, update_preferences,
+        },
+        relation_api::{
+            block_user, list_blocks, list_mutes, mute_user, muted_words, unblock_user, unmute_user,
+            update_muted_words,
+        },
+        report_api::{
+            create_report, get_report, list_held, list_reports, review_held, take_action,
+            update_report_status,
+        },
+        search_api::{search, search_users},
+        spam_api::{lift_limit, list_spam_events},
+        stream_api::{stream_events, stream_ws},
         tweet_api::{
-            add_comment, create_tweet, delete_comment, delete_tweet, get_tweet, list_tweets,
+            add_comment, create_tweet, delete_comment, delete_tweet, edit_tweet, get_tweet,
+            list_tweets, pin_tweet, restore_tweet, tweet_history, unpin_tweet, user_tweets,
+            vote_poll,
         },
-        user_api::{change_password, login, register, signout},
+        user_api::{change_password, delete_account, export_account, login, register, signout},
     },
     auths::auth_middleware::validator,
 };
@@ -22,12 +59,94 @@ pub fn init(config: &mut web::ServiceConfig) {
             .service(create_tweet)
             .service(list_tweets)
             .service(get_tweet)
+            .service(edit_tweet)
+            .service(tweet_history)
+            .service(pin_tweet)
+            .service(unpin_tweet)
+            .service(user_tweets)
             .service(delete_tweet)
+            .service(restore_tweet)
             .service(plus_one)
             .service(minus_one)
             .service(add_comment)
             .service(delete_comment)
+            .service(hashtag_tweets)
+            .service(trends)
+            .service(search)
+            .service(search_users)
+            .service(list_notifications)
+            .service(mark_all_read)
+            .service(mark_read)
+            .service(get_preferences)
+            .service(update_preferences)
+            .service(stream_events)
+            .service(stream_ws)
+            .service(follow_user)
+            .service(unfollow_user)
+            .service(followers)
+            .service(following)
+            .service(follow_requests)
+            .service(approve_follow_request)
+            .service(reject_follow_request)
+            .service(update_privacy)
+            .service(list_conversations)
+            .service(start_conversation)
+            .service(list_messages)
+            .service(send_message)
+            .service(mark_conversation_read)
+            .service(delete_message)
+            .service(update_dm_settings)
+            .service(block_user)
+            .service(unblock_user)
+            .service(list_blocks)
+            .service(mute_user)
+            .service(unmute_user)
+            .service(list_mutes)
+            .service(muted_words)
+            .service(update_muted_words)
+            .service(add_bookmark)
+            .service(remove_bookmark)
+            .service(list_bookmarks)
+            .service(upload_media)
+            .service(update_media)
+            .service(get_media)
+            .service(get_media_thumbnail)
+            .service(vote_poll)
+            .service(create_draft)
+            .service(list_drafts)
+            .service(get_draft)
+            .service(update_draft)
+            .service(delete_draft)
+            .service(publish_draft)
+            .service(schedule_tweet)
+            .service(list_scheduled)
+            .service(update_scheduled)
+            .service(cancel_scheduled)
+            .service(create_list)
+            .service(my_lists)
+            .service(user_lists)
+            .service(get_list)
+            .service(update_list)
+            .service(delete_list)
+            .service(list_members)
+            .service(add_list_member)
+            .service(remove_list_member)
+            .service(list_timeline)
+            .service(create_report)
+            .service(list_reports)
+            .service(get_report)
+            .service(update_report_status)
+            .service(take_action)
+            .service(list_held)
+            .service(review_held)
+            .service(list_spam_events)
+            .service(lift_limit)
+            .service(list_audit_events)
+            .service(export_audit_events)
+            .service(import_archive)
+            .service(export_account)
+            .service(delete_account)
             .service(change_password)
             .service(signout),
     );
-}
\ No newline at end of file
+}
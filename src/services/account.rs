@@ -0,0 +1,108 @@
+use actix_web::web::Data;
+use bson::oid::ObjectId;
+use chrono::Utc;
+
+use crate::{
+    config::settings::account_deletion_grace_period,
+    dtos::dto::AccountExportDto,
+    errors::error::TweetError,
+    model::{
+        audit_model::AuditEvent, auth_model::User, bookmark_model::Bookmark,
+        dm_model::Conversation, draft_model::Draft, follow_model::Follow, list_model::UserList,
+        media_model::Media, notification_model::Notification, relation_model::Relation,
+        report_model::Report, tweet_model::Tweet,
+    },
+    repo::{
+        audit_repo::AuditRepo, bookmark_repo::BookmarkRepo, dm_repo::DmRepo, draft_repo::DraftRepo,
+        follow_repo::FollowRepo, list_repo::ListRepo, media_repo::MediaRepo,
+        notification_repo::NotificationRepo, relation_repo::RelationRepo, report_repo::ReportRepo,
+        tweet_repo::TweetRepo, user_repo::UserRepo,
+    },
+    services::{media_store::MediaStore, search::SearchService, spam::SpamGuard},
+};
+
+/// Exports the data of an account and erases accounts once their deletion grace period is over.
+pub struct AccountService {
+    pub users: Data<UserRepo<User>>,
+    pub tweets: Data<TweetRepo<Tweet>>,
+    pub follows: Data<FollowRepo<Follow>>,
+    pub dms: Data<DmRepo<Conversation>>,
+    pub bookmarks: Data<BookmarkRepo<Bookmark>>,
+    pub relations: Data<RelationRepo<Relation>>,
+    pub lists: Data<ListRepo<UserList>>,
+    pub notifications: Data<NotificationRepo<Notification>>,
+    pub search: Data<SearchService>,
+    pub drafts: Data<DraftRepo<Draft>>,
+    pub media: Data<MediaRepo<Media>>,
+    pub media_store: Data<dyn MediaStore>,
+    pub reports: Data<ReportRepo<Report>>,
+    pub spam: Data<SpamGuard>,
+    pub audit: Data<AuditRepo<AuditEvent>>,
+}
+
+impl AccountService {
+    /// Collects the profile, tweets, comments, likes, follows and direct messages of the user
+    pub async fn export(&self, user_id: &str) -> Result<AccountExportDto, TweetError> {
+        let user = self.users.find_user(user_id).await?;
+        let to_hex = |ids: Vec<ObjectId>| ids.iter().map(|id| id.to_hex()).collect();
+        let (conversations, messages) = self.dms.export(user_id).await?;
+        Ok(AccountExportDto {
+            profile: user.map(),
+            email: user.email,
+            tweets: self.tweets.account_tweets(user_id).await?,
+            comments: self.tweets.account_comments(user_id).await?,
+            liked_tweet_ids: self.tweets.liked_tweet_ids(user_id).await?,
+            follower_ids: to_hex(self.follows.follower_ids(user_id).await?),
+            following_ids: to_hex(self.follows.following_ids(user_id).await?),
+            conversations,
+            messages,
+            exported_at: Utc::now(),
+        })
+    }
+
+    /// Erases the accounts whose grace period is over, returning how many were erased. An
+    /// account that fails is logged and retried on the next run
+    pub async fn purge_due(&self) -> Result<usize, TweetError> {
+        let due = self
+            .users
+            .due_deletions(Utc::now() - account_deletion_grace_period())
+            .await?;
+        let mut erased = 0;
+        for id in &due {
+            if let Err(err) = self.erase(&id.to_hex()).await {
+                log::error!("Failed to erase account {}: {}", id, err);
+                continue;
+            }
+            erased += 1;
+        }
+        Ok(erased)
+    }
+
+    /// Removes the user's tweets, comments, likes, relationships, drafts and media, drops
+    /// them from reports, spam and audit records, then removes the user
+    pub async fn erase(&self, user_id: &str) -> Result<(), TweetError> {
+        let user = self.users.find_user(user_id).await?;
+        for tweet_id in self.tweets.erase_user(user_id).await? {
+            self.search.remove_tweet(&tweet_id);
+        }
+        self.follows.remove_user(user_id).await?;
+        self.dms.remove_user(user_id).await?;
+        self.bookmarks.remove_user(user_id).await?;
+        self.relations.remove_user(user_id).await?;
+        self.lists.remove_user(user_id).await?;
+        self.notifications.remove_user(user_id).await?;
+        self.drafts.remove_user(user_id).await?;
+        for key in self.media.remove_user(user_id).await? {
+            // The record is gone already, a file left behind is only wasted space
+            if let Err(err) = self.media_store.delete(&key) {
+                log::error!("Failed to delete media file {}: {}", key, err);
+            }
+        }
+        self.reports.remove_user(user_id).await?;
+        self.spam.events.remove_user(user_id).await?;
+        self.audit.anonymize_user(user_id, &user.email).await?;
+        self.users.delete_user(user.id.unwrap()).await?;
+        log::info!("Erased account {}", user_id);
+        Ok(())
+    }
+}
@@ -0,0 +1,139 @@
+use actix_web::{
+    http::header::{self, CacheControl, CacheDirective, EntityTag, Header, IfMatch, IfNoneMatch},
+    HttpRequest, HttpResponse,
+};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+
+use crate::errors::error::TweetError;
+
+/// Strong ETag of a response body, tweets carry their version so it changes with every write
+pub fn etag<T: Serialize>(body: &T) -> EntityTag {
+    let digest = Sha256::digest(serde_json::to_vec(body).unwrap_or_default());
+    let tag = digest
+        .iter()
+        .take(16)
+        .map(|byte| format!("{:02x}", byte))
+        .collect();
+    EntityTag::new_strong(tag)
+}
+
+/// Sends the body with its ETag, or 304 Not Modified when it matches `If-None-Match`
+pub fn cached_json<T: Serialize>(request: &HttpRequest, body: &T) -> HttpResponse {
+    let tag = etag(body);
+    let fresh = match IfNoneMatch::parse(request) {
+        Ok(IfNoneMatch::Any) => true,
+        Ok(IfNoneMatch::Items(tags)) => tags.iter().any(|t| t.weak_eq(&tag)),
+        Err(_) => false,
+    };
+    // Bodies depend on the viewer, shared caches must not keep them
+    let cache_control = CacheControl(vec![CacheDirective::Private, CacheDirective::NoCache]);
+    if fresh {
+        HttpResponse::NotModified()
+            .insert_header(header::ETag(tag))
+            .insert_header(cache_control)
+            .finish()
+    } else {
+        HttpResponse::Ok()
+            .insert_header(header::ETag(tag))
+            .insert_header(cache_control)
+            .json(body)
+    }
+}
+
+/// Checks whether the client sent an `If-Match` precondition
+pub fn has_if_match(request: &HttpRequest) -> bool {
+    request.headers().contains_key(header::IF_MATCH)
+}
+
+/// Checks the `If-Match` precondition against the current body, passes when there is none
+pub fn if_match<T: Serialize>(request: &HttpRequest, current: &T) -> Result<(), TweetError> {
+    if !has_if_match(request) {
+        return Ok(());
+    }
+    let tag = etag(current);
+    match IfMatch::parse(request) {
+        Ok(IfMatch::Any) => Ok(()),
+        Ok(IfMatch::Items(tags)) if tags.iter().any(|t| t.strong_eq(&tag)) => Ok(()),
+        _ => Err(TweetError::PreconditionFailed(
+            "The resource was changed since it was last read".into(),
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use actix_web::{http::StatusCode, test::TestRequest};
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn derives_a_strong_tag_from_the_body() {
+        let tag = etag(&json!({"id": "1", "version": 3}));
+        assert!(!tag.weak);
+        assert_eq!(tag.tag().len(), 32);
+        assert_eq!(tag, etag(&json!({"id": "1", "version": 3})));
+        assert_ne!(tag, etag(&json!({"id": "1", "version": 4})));
+    }
+
+    #[test]
+    fn sends_not_modified_for_a_matching_tag() {
+        let body = json!({"message": "hi"});
+        let tag = etag(&body);
+        let request = TestRequest::default()
+            .insert_header((header::IF_NONE_MATCH, tag.to_string()))
+            .to_http_request();
+        let response = cached_json(&request, &body);
+        assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+        assert_eq!(
+            response.headers().get(header::ETAG).unwrap(),
+            tag.to_string().as_str()
+        );
+
+        // Weak comparison applies to If-None-Match
+        let weak = format!("W/\"{}\"", tag.tag());
+        let request = TestRequest::default()
+            .insert_header((header::IF_NONE_MATCH, weak))
+            .to_http_request();
+        assert_eq!(
+            cached_json(&request, &body).status(),
+            StatusCode::NOT_MODIFIED
+        );
+    }
+
+    #[test]
+    fn sends_the_body_when_the_tag_differs() {
+        let request = TestRequest::default()
+            .insert_header((header::IF_NONE_MATCH, "\"stale\""))
+            .to_http_request();
+        let response = cached_json(&request, &json!({"message": "hi"}));
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            response.headers().get(header::CACHE_CONTROL).unwrap(),
+            "private, no-cache"
+        );
+    }
+
+    #[test]
+    fn checks_if_match_strongly() {
+        let body = json!({"version": 1});
+        let tag = etag(&body);
+        let request = |value: String| {
+            TestRequest::default()
+                .insert_header((header::IF_MATCH, value))
+                .to_http_request()
+        };
+        assert!(if_match(&TestRequest::default().to_http_request(), &body).is_ok());
+        assert!(if_match(&request(tag.to_string()), &body).is_ok());
+        assert!(if_match(&request("*".into()), &body).is_ok());
+        assert!(matches!(
+            if_match(&request(format!("W/\"{}\"", tag.tag())), &body),
+            Err(TweetError::PreconditionFailed(_))
+        ));
+        assert!(matches!(
+            if_match(&request(etag(&json!({"version": 0})).to_string()), &body),
+            Err(TweetError::PreconditionFailed(_))
+        ));
+    }
+}
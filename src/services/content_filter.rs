@@ -0,0 +1,185 @@
+use std::{
+    fs,
+    path::PathBuf,
+    sync::{Arc, RwLock},
+    time::SystemTime,
+};
+
+use regex::{Regex, RegexBuilder};
+
+use crate::errors::error::{FieldError, TweetError};
+
+/// What happens to content a policy matched, ordered from the mildest to the strictest
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum FilterAction {
+    Allow,
+    /// Stored but hidden from everyone but its author until a moderator reviews it
+    Hold,
+    Reject,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PolicyDecision {
+    pub action: FilterAction,
+    pub reason: Option<String>,
+}
+
+impl PolicyDecision {
+    pub fn allow() -> Self {
+        PolicyDecision {
+            action: FilterAction::Allow,
+            reason: None,
+        }
+    }
+}
+
+/// A rule content must pass before it is stored
+pub trait ContentPolicy: Send + Sync {
+    fn evaluate(&self, text: &str) -> PolicyDecision;
+
+    /// Reloads the policy configuration when it changed, called periodically
+    fn reload(&self) {}
+}
+
+/// Runs every policy on user content, the strictest decision wins.
+pub struct ContentFilter {
+    policies: Vec<Arc<dyn ContentPolicy>>,
+}
+
+impl ContentFilter {
+    pub fn new(policies: Vec<Arc<dyn ContentPolicy>>) -> Self {
+        ContentFilter { policies }
+    }
+
+    /// Checks the text of a field, rejected content is returned as a validation error
+    /// and held content as `FilterAction::Hold` with the reason
+    pub fn check(&self, field: &str, text: &str) -> Result<PolicyDecision, TweetError> {
+        let decision = self
+            .policies
+            .iter()
+            .map(|policy| policy.evaluate(text))
+            .max_by_key(|decision| decision.action)
+            .unwrap_or_else(PolicyDecision::allow);
+        if decision.action == FilterAction::Reject {
+            return Err(TweetError::UnprocessableEntity(vec![FieldError::new(
+                field,
+                "blocked_content",
+                &format!(
+                    "This field contains content that is not allowed: {}",
+                    decision.reason.unwrap_or_default()
+                ),
+            )]));
+        }
+        Ok(decision)
+    }
+
+    pub fn reload(&self) {
+        self.policies.iter().for_each(|policy| policy.reload());
+    }
+}
+
+struct BlockRule {
+    pattern: Regex,
+    action: FilterAction,
+    source: String,
+}
+
+/// Word and regex blocklist read from a file, reloaded when the file changes.
+///
+/// Each line is `reject <word>`, `hold <word>`, `reject /<regex>/` or
+/// `hold /<regex>/`. Words match whole words case-insensitively, blank
+/// lines and lines starting with `#` are ignored.
+pub struct FileBlocklist {
+    path: PathBuf,
+    rules: RwLock<Vec<BlockRule>>,
+    modified: RwLock<Option<SystemTime>>,
+}
+
+impl FileBlocklist {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        let blocklist = FileBlocklist {
+            path: path.into(),
+            rules: RwLock::new(vec![]),
+            modified: RwLock::new(None),
+        };
+        blocklist.reload();
+        blocklist
+    }
+
+    fn parse(content: &str) -> Vec<BlockRule> {
+        content
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty() && !line.trim().starts_with('#'))
+            .filter_map(|(number, line)| {
+                let rule = Self::parse_line(line.trim());
+                if rule.is_none() {
+                    log::warn!("Ignoring invalid blocklist rule on line {}", number + 1);
+                }
+                rule
+            })
+            .collect()
+    }
+
+    fn parse_line(line: &str) -> Option<BlockRule> {
+        let (action, value) = line.split_once(char::is_whitespace)?;
+        let action = match action {
+            "reject" => FilterAction::Reject,
+            "hold" => FilterAction::Hold,
+            _ => return None,
+        };
+        let value = value.trim();
+        let pattern = match value.strip_prefix('/').and_then(|v| v.strip_suffix('/')) {
+            Some(regex) if !regex.is_empty() => regex.to_string(),
+            _ if !value.is_empty() => format!(r"\b{}\b", regex::escape(value)),
+            _ => return None,
+        };
+        let pattern = RegexBuilder::new(&pattern)
+            .case_insensitive(true)
+            .build()
+            .ok()?;
+        Some(BlockRule {
+            pattern,
+            action,
+            source: value.to_string(),
+        })
+    }
+}
+
+impl ContentPolicy for FileBlocklist {
+    fn evaluate(&self, text: &str) -> PolicyDecision {
+        self.rules
+            .read()
+            .unwrap()
+            .iter()
+            .filter(|rule| rule.pattern.is_match(text))
+            .max_by_key(|rule| rule.action)
+            .map(|rule| PolicyDecision {
+                action: rule.action,
+                reason: Some(format!("matched blocklist rule {}", rule.source)),
+            })
+            .unwrap_or_else(PolicyDecision::allow)
+    }
+
+    fn reload(&self) {
+        let modified = fs::metadata(&self.path).and_then(|m| m.modified()).ok();
+        if modified == *self.modified.read().unwrap() {
+            return;
+        }
+        let rules = match modified.map(|_| fs::read_to_string(&self.path)) {
+            Some(Ok(content)) => Self::parse(&content),
+            Some(Err(err)) => {
+                log::error!("Failed to read blocklist {}: {}", self.path.display(), err);
+                return;
+            }
+            None => vec![],
+        };
+        log::info!(
+            "Loaded {} blocklist rules from {}",
+            rules.len(),
+            self.path.display()
+        );
+        *self.rules.write().unwrap() = rules;
+        *self.modified.write().unwrap() = modified;
+    }
+}
@@ -0,0 +1,188 @@
+use std::{collections::VecDeque, sync::Mutex};
+
+use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast;
+
+use crate::{
+    dtos::dto::{DirectMessageDto, TweetDto},
+    model::{audience_model::Audience, notification_model::NotificationKind},
+};
+
+/// Something that happened, pushed to connected streaming clients
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", content = "data", rename_all = "snake_case")]
+pub enum EventPayload {
+    /// A new tweet to insert in timelines
+    TweetCreated(Box<TweetDto>),
+    /// Like or comment counts of a tweet changed, sent to clients viewing it
+    TweetStats {
+        tweet_id: String,
+        like_count: usize,
+        comment_count: usize,
+    },
+    /// A new notification, sent only to its recipient
+    Notification {
+        #[serde(skip)]
+        user_id: String,
+        kind: NotificationKind,
+        actor_id: String,
+        tweet_id: Option<String>,
+    },
+    /// A new direct message, sent to the conversation participants
+    DirectMessage {
+        #[serde(skip)]
+        recipient_ids: Vec<String>,
+        message: DirectMessageDto,
+    },
+    /// Events were missed, the client should refetch its state
+    Reset,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Event {
+    pub id: u64,
+    #[serde(flatten)]
+    pub payload: EventPayload,
+}
+
+impl Event {
+    /// Name of the event, used as the SSE `event` field
+    pub fn name(&self) -> &'static str {
+        match self.payload {
+            EventPayload::TweetCreated(_) => "tweet_created",
+            EventPayload::TweetStats { .. } => "tweet_stats",
+            EventPayload::Notification { .. } => "notification",
+            EventPayload::DirectMessage { .. } => "direct_message",
+            EventPayload::Reset => "reset",
+        }
+    }
+
+    /// Checks whether a subscriber should receive this event
+    pub fn is_visible_to(&self, subscriber: &Subscriber) -> bool {
+        match &self.payload {
+            EventPayload::TweetCreated(tweet) => subscriber.audience.allows_tweet(tweet),
+            EventPayload::Reset => true,
+            EventPayload::TweetStats { tweet_id, .. } => subscriber.watched.contains(tweet_id),
+            EventPayload::Notification { user_id, .. } => &subscriber.user_id == user_id,
+            EventPayload::DirectMessage { recipient_ids, .. } => {
+                recipient_ids.contains(&subscriber.user_id)
+            }
+        }
+    }
+}
+
+const MAX_WATCHED_TWEETS: usize = 100;
+
+/// A connected client, the tweets it is currently viewing and the
+/// audiences it belongs to
+#[derive(Debug, Clone, Default)]
+pub struct Subscriber {
+    pub user_id: String,
+    pub watched: Vec<String>,
+    pub audience: Audience,
+}
+
+/// Message a WebSocket client sends to change the tweets it is viewing
+#[derive(Debug, Default, Deserialize)]
+pub struct WatchRequest {
+    #[serde(default)]
+    pub watch: Vec<String>,
+    #[serde(default)]
+    pub unwatch: Vec<String>,
+}
+
+impl Subscriber {
+    pub fn new(user_id: String, watched: Vec<String>) -> Self {
+        let mut subscriber = Subscriber {
+            audience: Audience::new(&user_id),
+            user_id,
+            watched: vec![],
+        };
+        subscriber.apply(WatchRequest {
+            watch: watched,
+            unwatch: vec![],
+        });
+        subscriber
+    }
+
+    /// Updates the viewed tweets, keeping at most `MAX_WATCHED_TWEETS`
+    pub fn apply(&mut self, request: WatchRequest) {
+        self.watched.retain(|id| !request.unwatch.contains(id));
+        for id in request.watch {
+            if !self.watched.contains(&id) {
+                self.watched.push(id);
+            }
+        }
+        if self.watched.len() > MAX_WATCHED_TWEETS {
+            let excess = self.watched.len() - MAX_WATCHED_TWEETS;
+            self.watched.drain(..excess);
+        }
+    }
+}
+
+/// Events replayed on connection and the receiver for live events
+pub struct Subscription {
+    pub replay: Vec<Event>,
+    pub receiver: broadcast::Receiver<Event>,
+}
+
+/// In-process publish/subscribe bus the repositories publish their writes to.
+///
+/// Recent events are kept so reconnecting clients can resume from their
+/// last event id. Slow subscribers that fall behind the channel capacity
+/// skip the missed events and receive a `Reset` instead.
+pub struct EventBus {
+    sender: broadcast::Sender<Event>,
+    history: Mutex<VecDeque<Event>>,
+    capacity: usize,
+}
+
+impl EventBus {
+    pub fn new(capacity: usize) -> Self {
+        let (sender, _) = broadcast::channel(capacity);
+        EventBus {
+            sender,
+            history: Mutex::new(VecDeque::with_capacity(capacity)),
+            capacity,
+        }
+    }
+
+    pub fn publish(&self, payload: EventPayload) {
+        let mut history = self.history.lock().unwrap();
+        let id = history.back().map(|e| e.id + 1).unwrap_or(1);
+        let event = Event { id, payload };
+        if history.len() == self.capacity {
+            history.pop_front();
+        }
+        history.push_back(event.clone());
+        // Sending only fails when nobody is subscribed
+        let _ = self.sender.send(event);
+    }
+
+    /// Subscribes to live events, replaying the ones published after `last_event_id`
+    pub fn subscribe(&self, last_event_id: Option<u64>) -> Subscription {
+        let history = self.history.lock().unwrap();
+        let receiver = self.sender.subscribe();
+        let replay = match last_event_id {
+            None => vec![],
+            Some(last_id) => {
+                let oldest = history.front().map(|e| e.id).unwrap_or(1);
+                let newest = history.back().map(|e| e.id).unwrap_or(0);
+                if last_id + 1 < oldest || last_id > newest {
+                    vec![self.reset(newest)]
+                } else {
+                    history.iter().filter(|e| e.id > last_id).cloned().collect()
+                }
+            }
+        };
+        Subscription { replay, receiver }
+    }
+
+    /// Builds a reset event carrying the id of the last event, so clients resume from there
+    pub fn reset(&self, last_id: u64) -> Event {
+        Event {
+            id: last_id,
+            payload: EventPayload::Reset,
+        }
+    }
+}
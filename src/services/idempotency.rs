@@ -0,0 +1,226 @@
+use std::future::Future;
+
+use actix_web::{
+    body,
+    http::{header, StatusCode},
+    HttpRequest, HttpResponse, HttpResponseBuilder, ResponseError,
+};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+
+use crate::{
+    errors::error::TweetError,
+    model::idempotency_model::{
+        IdempotencyClaim, IdempotencyRecord, StoredResponse, IDEMPOTENCY_KEY_HEADER,
+        IDEMPOTENCY_KEY_MAX_LENGTH, IDEMPOTENT_REPLAYED_HEADER,
+    },
+    repo::idempotency_repo::IdempotencyRepo,
+};
+
+/// Makes writes safe to retry by replaying the response stored for an `Idempotency-Key`
+pub struct IdempotencyService {
+    pub records: IdempotencyRepo<IdempotencyRecord>,
+}
+
+impl IdempotencyService {
+    pub async fn init(&self) -> Result<(), TweetError> {
+        self.records.create_indexes().await
+    }
+
+    /// Runs `handler` at most once per idempotency key of the user, requests without the
+    /// header always run
+    pub async fn run<P, F, Fut>(
+        &self,
+        request: &HttpRequest,
+        user_id: &str,
+        payload: &P,
+        handler: F,
+    ) -> HttpResponse
+    where
+        P: Serialize,
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = HttpResponse>,
+    {
+        let key = match idempotency_key(request) {
+            Ok(Some(key)) => key,
+            Ok(None) => return handler().await,
+            Err(err) => return err.error_response(),
+        };
+        match self
+            .records
+            .claim(user_id, &key, &request_hash(request, payload))
+            .await
+        {
+            Ok(IdempotencyClaim::Acquired) => {}
+            Ok(IdempotencyClaim::Replay(stored)) => return replay(stored),
+            Err(err) => return err.error_response(),
+        }
+
+        let response = handler().await;
+        let status = response.status();
+        // Failures the client is expected to retry are not replayed
+        if status.is_server_error() || status == StatusCode::TOO_MANY_REQUESTS {
+            self.release(user_id, &key).await;
+            return response;
+        }
+        let content_type = response
+            .headers()
+            .get(header::CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .map(String::from);
+        let bytes = match body::to_bytes(response.into_body()).await {
+            Ok(bytes) => bytes,
+            Err(_) => {
+                self.release(user_id, &key).await;
+                return TweetError::InternalServerError.error_response();
+            }
+        };
+        let stored = StoredResponse {
+            status: status.as_u16(),
+            content_type,
+            body: String::from_utf8_lossy(&bytes).into_owned(),
+        };
+        if let Err(err) = self.records.complete(user_id, &key, &stored).await {
+            log::error!(
+                "Failed to store response for idempotency key {}: {}",
+                key,
+                err
+            );
+        }
+        builder(status, stored.content_type.as_deref()).body(bytes)
+    }
+
+    async fn release(&self, user_id: &str, key: &str) {
+        if let Err(err) = self.records.release(user_id, key).await {
+            log::error!("Failed to release idempotency key {}: {}", key, err);
+        }
+    }
+}
+
+/// Reads the `Idempotency-Key` header, if the client sent one
+fn idempotency_key(request: &HttpRequest) -> Result<Option<String>, TweetError> {
+    let value = match request.headers().get(IDEMPOTENCY_KEY_HEADER) {
+        Some(value) => value,
+        None => return Ok(None),
+    };
+    match value.to_str().map(str::trim) {
+        Ok(key) if !key.is_empty() && key.len() <= IDEMPOTENCY_KEY_MAX_LENGTH => {
+            Ok(Some(key.to_string()))
+        }
+        _ => Err(TweetError::BadRequest(format!(
+            "{} must be between 1 and {} visible characters",
+            IDEMPOTENCY_KEY_HEADER, IDEMPOTENCY_KEY_MAX_LENGTH
+        ))),
+    }
+}
+
+/// Fingerprints the route and payload, so a key reused for another request is caught
+fn request_hash<P: Serialize>(request: &HttpRequest, payload: &P) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(request.method().as_str());
+    hasher.update(request.path());
+    hasher.update(serde_json::to_vec(payload).unwrap_or_default());
+    hasher
+        .finalize()
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+fn replay(stored: StoredResponse) -> HttpResponse {
+    let status = StatusCode::from_u16(stored.status).unwrap_or(StatusCode::OK);
+    builder(status, stored.content_type.as_deref())
+        .insert_header((IDEMPOTENT_REPLAYED_HEADER, "true"))
+        .body(stored.body)
+}
+
+fn builder(status: StatusCode, content_type: Option<&str>) -> HttpResponseBuilder {
+    let mut builder = HttpResponse::build(status);
+    if let Some(content_type) = content_type {
+        builder.content_type(content_type);
+    }
+    builder
+}
+
+#[cfg(test)]
+mod tests {
+    use actix_web::{http::Method, test::TestRequest};
+    use serde_json::json;
+
+    use super::*;
+
+    fn with_key(key: &str) -> HttpRequest {
+        TestRequest::default()
+            .insert_header((IDEMPOTENCY_KEY_HEADER, key))
+            .to_http_request()
+    }
+
+    #[test]
+    fn reads_the_trimmed_key() {
+        let key = idempotency_key(&with_key("  abc-123 ")).unwrap();
+        assert_eq!(key.as_deref(), Some("abc-123"));
+        let missing = TestRequest::default().to_http_request();
+        assert_eq!(idempotency_key(&missing).unwrap(), None);
+    }
+
+    #[test]
+    fn rejects_blank_and_oversized_keys() {
+        assert!(matches!(
+            idempotency_key(&with_key("   ")),
+            Err(TweetError::BadRequest(_))
+        ));
+        let longest = "k".repeat(IDEMPOTENCY_KEY_MAX_LENGTH);
+        assert!(idempotency_key(&with_key(&longest)).unwrap().is_some());
+        assert!(matches!(
+            idempotency_key(&with_key(&format!("{}k", longest))),
+            Err(TweetError::BadRequest(_))
+        ));
+    }
+
+    #[test]
+    fn hashes_the_method_path_and_payload() {
+        let request = |method: Method, path: &str| {
+            TestRequest::default()
+                .method(method)
+                .uri(path)
+                .to_http_request()
+        };
+        let post = request(Method::POST, "/api/v1/tweets");
+        let hash = request_hash(&post, &json!({"message": "hi"}));
+        assert_eq!(hash.len(), 64);
+        assert_eq!(hash, request_hash(&post, &json!({"message": "hi"})));
+        assert_ne!(hash, request_hash(&post, &json!({"message": "hello"})));
+        assert_ne!(
+            hash,
+            request_hash(
+                &request(Method::PUT, "/api/v1/tweets"),
+                &json!({"message": "hi"})
+            )
+        );
+        assert_ne!(
+            hash,
+            request_hash(
+                &request(Method::POST, "/api/v1/drafts"),
+                &json!({"message": "hi"})
+            )
+        );
+    }
+
+    #[test]
+    fn replays_the_stored_response() {
+        let response = replay(StoredResponse {
+            status: 201,
+            content_type: Some("application/json".into()),
+            body: "{}".into(),
+        });
+        assert_eq!(response.status(), StatusCode::CREATED);
+        assert_eq!(
+            response.headers().get(IDEMPOTENT_REPLAYED_HEADER).unwrap(),
+            "true"
+        );
+        assert_eq!(
+            response.headers().get(header::CONTENT_TYPE).unwrap(),
+            "application/json"
+        );
+    }
+}
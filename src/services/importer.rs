@@ -0,0 +1,148 @@
+use actix_web::web::Data;
+use bson::oid::ObjectId;
+use chrono::{DateTime, Utc};
+
+use crate::{
+    config::settings::{comment_max_length, tweet_max_length},
+    dtos::dto::{ImportErrorDto, ImportReportDto},
+    errors::error::TweetError,
+    model::{
+        auth_model::User,
+        import_model::{parse_archive, ArchiveTweet},
+        tweet_comment::Comment,
+        tweet_model::Tweet,
+    },
+    repo::{tweet_repo::TweetRepo, user_repo::UserRepo},
+    services::search::SearchService,
+    validation::content::validate_message,
+};
+
+/// Imports the tweets of a Twitter archive, replies to imported tweets become their comments.
+pub struct ArchiveImporter {
+    pub tweets: Data<TweetRepo<Tweet>>,
+    pub users: Data<UserRepo<User>>,
+    pub search: Data<SearchService>,
+}
+
+impl ArchiveImporter {
+    /// Imports a `tweets.js` file for the user, skipping the tweets a previous run imported
+    pub async fn import(
+        &self,
+        user_id: &str,
+        content: &str,
+    ) -> Result<ImportReportDto, TweetError> {
+        self.users.find_user(user_id).await?;
+        let mut report = ImportReportDto::default();
+        let mut records = Vec::<(usize, DateTime<Utc>, ArchiveTweet)>::new();
+        for (index, record) in parse_archive(content)?.into_iter().enumerate() {
+            let record = match record {
+                Ok(record) => record,
+                Err(err) => {
+                    report.errors.push(err);
+                    continue;
+                }
+            };
+            match record.created_at() {
+                Ok(created_at) => records.push((index, created_at, record)),
+                Err(message) => report.errors.push(record_error(index, &record, message)),
+            }
+        }
+        // Tweets are imported before the replies to them
+        records.sort_by_key(|(_, created_at, _)| *created_at);
+
+        let mut placed = self.tweets.imported_sources(user_id).await?;
+        for (index, created_at, record) in records {
+            if placed.contains_key(&record.id_str) {
+                report.skipped += 1;
+                continue;
+            }
+            let parent_id = record
+                .in_reply_to_status_id_str
+                .as_ref()
+                .and_then(|id| placed.get(id))
+                .copied();
+            let result = match parent_id {
+                Some(tweet_id) => {
+                    self.import_reply(user_id, tweet_id, created_at, &record)
+                        .await
+                }
+                None => self.import_tweet(user_id, created_at, &record).await,
+            };
+            match result {
+                Ok(tweet_id) => {
+                    if parent_id.is_some() {
+                        report.imported_replies += 1;
+                    } else {
+                        report.imported_tweets += 1;
+                    }
+                    placed.insert(record.id_str.clone(), tweet_id);
+                }
+                Err(err) => report
+                    .errors
+                    .push(record_error(index, &record, error_message(&err))),
+            }
+        }
+        log::info!(
+            "Imported {} tweets and {} replies for user {}, {} skipped, {} failed",
+            report.imported_tweets,
+            report.imported_replies,
+            user_id,
+            report.skipped,
+            report.errors.len()
+        );
+        Ok(report)
+    }
+
+    async fn import_tweet(
+        &self,
+        user_id: &str,
+        created_at: DateTime<Utc>,
+        record: &ArchiveTweet,
+    ) -> Result<ObjectId, TweetError> {
+        let message = validate_message("full_text", Some(&record.message()), tweet_max_length())?;
+        let mut tweet = Tweet::new(&message, user_id);
+        tweet.created_at = created_at;
+        tweet.source_id = Some(record.id_str.clone());
+        self.users.resolve_mentions(&mut tweet.entities).await?;
+        let tweet = self.tweets.import_tweet(tweet).await?;
+        self.search.index_tweet(&tweet.map());
+        Ok(tweet.id.unwrap())
+    }
+
+    async fn import_reply(
+        &self,
+        user_id: &str,
+        tweet_id: ObjectId,
+        created_at: DateTime<Utc>,
+        record: &ArchiveTweet,
+    ) -> Result<ObjectId, TweetError> {
+        let message = validate_message("full_text", Some(&record.message()), comment_max_length())?;
+        let user_id = ObjectId::parse_str(user_id)
+            .map_err(|_| TweetError::BadRequest("Invalid user Id provided".into()))?;
+        let mut comment = Comment::new(tweet_id, user_id, &message);
+        comment.created_at = created_at;
+        comment.source_id = Some(record.id_str.clone());
+        self.tweets.import_comment(tweet_id, comment).await?;
+        Ok(tweet_id)
+    }
+}
+
+fn record_error(index: usize, record: &ArchiveTweet, message: String) -> ImportErrorDto {
+    ImportErrorDto {
+        index,
+        source_id: Some(record.id_str.clone()),
+        message,
+    }
+}
+
+/// Describes why a record failed, with the field errors of a failed validation
+fn error_message(err: &TweetError) -> String {
+    match err {
+        TweetError::UnprocessableEntity(errors) => errors
+            .iter()
+            .map(|e| e.message.clone())
+            .collect::<Vec<String>>()
+            .join(", "),
+        _ => err.to_string(),
+    }
+}
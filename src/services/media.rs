@@ -0,0 +1,139 @@
+use std::io::Cursor;
+
+use image::{
+    io::{Limits, Reader},
+    DynamicImage, GenericImageView, ImageFormat, ImageOutputFormat,
+};
+
+use crate::{
+    config::settings::{media_max_image_size, media_max_video_size, media_thumbnail_size},
+    errors::error::{FieldError, TweetError},
+    model::media_model::MediaKind,
+};
+
+const MAX_IMAGE_DIMENSION: u32 = 8192;
+const JPEG_QUALITY: u8 = 90;
+
+/// An upload after validation, ready to be written to the media store
+pub struct ProcessedMedia {
+    pub kind: MediaKind,
+    pub content_type: &'static str,
+    pub extension: &'static str,
+    pub data: Vec<u8>,
+    pub thumbnail: Option<Vec<u8>>,
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+}
+
+impl ProcessedMedia {
+    /// Extension of the thumbnail, GIF thumbnails being a still PNG of the first frame
+    pub fn thumbnail_extension(&self) -> &'static str {
+        match self.kind {
+            MediaKind::Gif => "png",
+            _ => self.extension,
+        }
+    }
+}
+
+/// Validates an upload by sniffing its content, ignoring the type the client claimed.
+///
+/// JPEG and PNG images are decoded and encoded again, which drops EXIF and any
+/// other metadata. GIFs are kept as is to preserve animation, and videos are
+/// only checked for their type and size.
+pub fn process_upload(data: Vec<u8>) -> Result<ProcessedMedia, TweetError> {
+    let content_type = infer::get(&data).map(|t| t.mime_type());
+    match content_type {
+        Some("image/jpeg") => process_image(data, ImageFormat::Jpeg),
+        Some("image/png") => process_image(data, ImageFormat::Png),
+        Some("image/gif") => process_gif(data),
+        Some("video/mp4") => {
+            check_size(&data, media_max_video_size())?;
+            Ok(ProcessedMedia {
+                kind: MediaKind::Video,
+                content_type: "video/mp4",
+                extension: "mp4",
+                data,
+                thumbnail: None,
+                width: None,
+                height: None,
+            })
+        }
+        _ => Err(file_error(
+            "unsupported_type",
+            "Only JPEG, PNG and GIF images and MP4 videos are supported",
+        )),
+    }
+}
+
+fn process_image(data: Vec<u8>, format: ImageFormat) -> Result<ProcessedMedia, TweetError> {
+    check_size(&data, media_max_image_size())?;
+    let image = decode(&data, format)?;
+    let (width, height) = image.dimensions();
+    let (content_type, extension, output) = match format {
+        ImageFormat::Png => ("image/png", "png", ImageOutputFormat::Png),
+        _ => ("image/jpeg", "jpg", ImageOutputFormat::Jpeg(JPEG_QUALITY)),
+    };
+    Ok(ProcessedMedia {
+        kind: MediaKind::Image,
+        content_type,
+        extension,
+        data: encode(&image, output.clone())?,
+        thumbnail: Some(thumbnail(&image, output)?),
+        width: Some(width),
+        height: Some(height),
+    })
+}
+
+fn process_gif(data: Vec<u8>) -> Result<ProcessedMedia, TweetError> {
+    check_size(&data, media_max_image_size())?;
+    let first_frame = decode(&data, ImageFormat::Gif)?;
+    let (width, height) = first_frame.dimensions();
+    Ok(ProcessedMedia {
+        kind: MediaKind::Gif,
+        content_type: "image/gif",
+        extension: "gif",
+        thumbnail: Some(thumbnail(&first_frame, ImageOutputFormat::Png)?),
+        data,
+        width: Some(width),
+        height: Some(height),
+    })
+}
+
+/// Decodes an image, refusing dimensions large enough to exhaust memory
+fn decode(data: &[u8], format: ImageFormat) -> Result<DynamicImage, TweetError> {
+    let mut limits = Limits::default();
+    limits.max_image_width = Some(MAX_IMAGE_DIMENSION);
+    limits.max_image_height = Some(MAX_IMAGE_DIMENSION);
+    let mut reader = Reader::with_format(Cursor::new(data), format);
+    reader.limits(limits);
+    reader
+        .decode()
+        .map_err(|_| file_error("invalid_image", "The image could not be decoded"))
+}
+
+fn thumbnail(image: &DynamicImage, format: ImageOutputFormat) -> Result<Vec<u8>, TweetError> {
+    let size = media_thumbnail_size();
+    encode(&image.thumbnail(size, size), format)
+}
+
+fn encode(image: &DynamicImage, format: ImageOutputFormat) -> Result<Vec<u8>, TweetError> {
+    let mut output = Cursor::new(Vec::new());
+    image
+        .write_to(&mut output, format)
+        .map_err(|_| TweetError::InternalServerError)?;
+    Ok(output.into_inner())
+}
+
+fn check_size(data: &[u8], max_size: usize) -> Result<(), TweetError> {
+    if data.len() > max_size {
+        return Err(file_error(
+            "too_large",
+            &format!("The file must be at most {} bytes", max_size),
+        ));
+    }
+    Ok(())
+}
+
+fn file_error(code: &str, message: &str) -> TweetError {
+    TweetError::UnprocessableEntity(vec![FieldError::new("file", code, message)])
+}
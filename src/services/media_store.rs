@@ -0,0 +1,59 @@
+use std::{
+    fs,
+    path::{Component, Path, PathBuf},
+};
+
+use crate::errors::error::TweetError;
+
+/// Blob storage for uploaded media, addressed by keys such as `user_id/file.jpg`
+pub trait MediaStore: Send + Sync {
+    fn put(&self, key: &str, data: &[u8]) -> Result<(), TweetError>;
+    fn get(&self, key: &str) -> Result<Vec<u8>, TweetError>;
+    fn delete(&self, key: &str) -> Result<(), TweetError>;
+}
+
+/// Stores media as files below a root directory
+pub struct FileSystemStore {
+    root: PathBuf,
+}
+
+impl FileSystemStore {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        FileSystemStore { root: root.into() }
+    }
+
+    /// Resolves a key to a path, refusing keys that would escape the root directory
+    fn path(&self, key: &str) -> Result<PathBuf, TweetError> {
+        let relative = Path::new(key);
+        if !relative
+            .components()
+            .all(|c| matches!(c, Component::Normal(_)))
+        {
+            return Err(TweetError::BadRequest("Invalid media key".into()));
+        }
+        Ok(self.root.join(relative))
+    }
+}
+
+impl MediaStore for FileSystemStore {
+    fn put(&self, key: &str, data: &[u8]) -> Result<(), TweetError> {
+        let path = self.path(key)?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(|_| TweetError::InternalServerError)?;
+        }
+        fs::write(path, data).map_err(|_| TweetError::InternalServerError)
+    }
+
+    fn get(&self, key: &str) -> Result<Vec<u8>, TweetError> {
+        fs::read(self.path(key)?)
+            .map_err(|_| TweetError::NotFound(format!("No media file {} found", key)))
+    }
+
+    fn delete(&self, key: &str) -> Result<(), TweetError> {
+        match fs::remove_file(self.path(key)?) {
+            Ok(_) => Ok(()),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(()),
+            Err(_) => Err(TweetError::InternalServerError),
+        }
+    }
+}
@@ -0,0 +1,12 @@
+pub mod account;
+pub mod conditional;
+pub mod content_filter;
+pub mod event_bus;
+pub mod idempotency;
+pub mod importer;
+pub mod media;
+pub mod media_store;
+pub mod publisher;
+pub mod search;
+pub mod spam;
+pub mod trending;
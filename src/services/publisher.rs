@@ -0,0 +1,123 @@
+use actix_web::web::Data;
+use bson::oid::ObjectId;
+
+use crate::{
+    config::settings::media_max_per_tweet,
+    dtos::dto::TweetDto,
+    errors::error::TweetError,
+    model::{
+        auth_model::User,
+        draft_model::Draft,
+        media_model::Media,
+        report_model::ReportTarget,
+        tweet_model::{Tweet, TweetActions, TweetRequest},
+    },
+    repo::{
+        draft_repo::DraftRepo, media_repo::MediaRepo, tweet_repo::TweetRepo, user_repo::UserRepo,
+    },
+    services::{
+        content_filter::{ContentFilter, FilterAction},
+        search::SearchService,
+        spam::SpamGuard,
+    },
+};
+
+/// Turns tweet requests into published tweets, for the API and for drafts and scheduled tweets.
+#[derive(Clone)]
+pub struct TweetPublisher {
+    pub tweets: Data<TweetRepo<Tweet>>,
+    pub users: Data<UserRepo<User>>,
+    pub media: Data<MediaRepo<Media>>,
+    pub search: Data<SearchService>,
+    pub filter: Data<ContentFilter>,
+    pub spam: Data<SpamGuard>,
+}
+
+impl TweetPublisher {
+    /// Validates the request, attaches its media and creates the tweet, with the given id if any
+    pub async fn publish(
+        &self,
+        request: &TweetRequest,
+        user_id: &str,
+        tweet_id: Option<ObjectId>,
+    ) -> Result<TweetDto, TweetError> {
+        let mut tweet = request.tweet(user_id.to_string())?;
+        tweet.id = tweet_id;
+        if self.users.find_user(user_id).await?.suspended {
+            return Err(TweetError::Forbidden("This account is suspended".into()));
+        }
+        let decision = self.filter.check("message", &tweet.message)?;
+        if decision.action == FilterAction::Hold {
+            tweet.withheld = true;
+            tweet.held_reason = Some(decision.reason.unwrap_or_else(|| "Held for review".into()));
+        }
+        if request.media_ids.len() > media_max_per_tweet() {
+            return Err(TweetError::BadRequest(format!(
+                "A tweet can have at most {} media attached",
+                media_max_per_tweet()
+            )));
+        }
+        if !request.media_ids.is_empty() && request.poll.is_some() {
+            return Err(TweetError::BadRequest(
+                "A tweet cannot have both media and a poll".into(),
+            ));
+        }
+        self.spam
+            .check(user_id, ReportTarget::Tweet, &tweet.message)
+            .await?;
+        self.users.resolve_mentions(&mut tweet.entities).await?;
+        tweet.media = self.media.attach(&request.media_ids, user_id).await?;
+        let media_ids = tweet.media.iter().filter_map(|m| m.id).collect::<Vec<_>>();
+        let tweet = match self.tweets.create_tweet(tweet).await {
+            Ok(tweet) => tweet,
+            Err(err) => {
+                // Without the tweet nobody could see or reuse the uploads
+                if let Err(detach_err) = self.media.detach(&media_ids).await {
+                    log::error!(
+                        "Failed to release media of unpublished tweet: {}",
+                        detach_err
+                    );
+                }
+                return Err(err);
+            }
+        };
+        self.search.index_tweet(&tweet);
+        Ok(tweet)
+    }
+
+    /// Publishes a claimed draft. The tweet id reserved by the draft makes this
+    /// idempotent: a draft whose tweet already exists is only marked published.
+    pub async fn publish_draft(
+        &self,
+        drafts: &DraftRepo<Draft>,
+        draft: Draft,
+    ) -> Result<TweetDto, TweetError> {
+        if let Ok(tweet) = self.tweets.get_tweet(&draft.tweet_id.to_hex()).await {
+            drafts.mark_published(&draft).await?;
+            return Ok(tweet);
+        }
+        let result = self
+            .publish(
+                &draft.request(),
+                &draft.user_id.to_hex(),
+                Some(draft.tweet_id),
+            )
+            .await;
+        match &result {
+            Ok(_) => drafts.mark_published(&draft).await?,
+            Err(err) => drafts.mark_failed(&draft, err.to_string()).await?,
+        }
+        result
+    }
+
+    /// Publishes every scheduled draft that is due
+    pub async fn publish_due(&self, drafts: &DraftRepo<Draft>) -> Result<(), TweetError> {
+        while let Some(draft) = drafts.claim_due().await? {
+            let id = draft.id.map(|id| id.to_hex()).unwrap_or_default();
+            if let Err(err) = self.publish_draft(drafts, draft).await {
+                log::error!("Failed to publish scheduled tweet {}: {}", id, err);
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,327 @@
+use std::{
+    collections::{HashMap, HashSet},
+    sync::RwLock,
+};
+
+use chrono::{DateTime, Utc};
+use mongodb::bson::oid::ObjectId;
+
+use crate::{
+    dtos::dto::TweetDto,
+    errors::error::TweetError,
+    model::{
+        audience_model::{Audience, Visibility},
+        pagination::PageQuery,
+        search_model::SearchSort,
+        tweet_model::Tweet,
+    },
+    repo::tweet_repo::TweetRepo,
+    text::search_query::{tokenize, SearchQuery},
+};
+
+/// Where tweet searches are executed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SearchBackend {
+    /// MongoDB `$text` queries against the text index on `message`
+    Text,
+    /// Embedded inverted index, for backends without text index support
+    Memory,
+}
+
+impl SearchBackend {
+    /// Reads the backend from `SEARCH_BACKEND`, defaulting to the text index
+    pub fn from_env() -> Self {
+        match std::env::var("SEARCH_BACKEND").as_deref() {
+            Ok("memory") => SearchBackend::Memory,
+            _ => SearchBackend::Text,
+        }
+    }
+}
+
+/// Searches tweets using the configured backend and keeps the embedded index up to date.
+pub struct SearchService {
+    backend: SearchBackend,
+    index: RwLock<InvertedIndex>,
+}
+
+impl SearchService {
+    pub fn new(backend: SearchBackend) -> Self {
+        SearchService {
+            backend,
+            index: RwLock::new(InvertedIndex::default()),
+        }
+    }
+
+    /// Prepares the backend, creating the text index or loading every tweet in memory
+    pub async fn init(&self, db: &TweetRepo<Tweet>) -> Result<(), TweetError> {
+        match self.backend {
+            SearchBackend::Text => db.create_text_index().await,
+            SearchBackend::Memory => {
+                let tweets = db.every_tweet().await?;
+                let mut index = self.index.write().unwrap();
+                tweets.iter().for_each(|t| index.insert(t));
+                Ok(())
+            }
+        }
+    }
+
+    /// Adds or replaces a tweet in the embedded index
+    pub fn index_tweet(&self, tweet: &TweetDto) {
+        if self.backend == SearchBackend::Memory {
+            self.index.write().unwrap().insert(tweet);
+        }
+    }
+
+    /// Removes a tweet from the embedded index
+    pub fn remove_tweet(&self, id: &str) {
+        if self.backend == SearchBackend::Memory {
+            self.index.write().unwrap().remove(id);
+        }
+    }
+
+    pub async fn search(
+        &self,
+        db: &TweetRepo<Tweet>,
+        query: &SearchQuery,
+        author_id: Option<ObjectId>,
+        audience: &Audience,
+        sort: SearchSort,
+        page: &PageQuery,
+    ) -> Result<Vec<TweetDto>, TweetError> {
+        match self.backend {
+            SearchBackend::Text => {
+                db.search_tweets(query, author_id, audience, sort, page)
+                    .await
+            }
+            SearchBackend::Memory => {
+                let ids = self.index.read().unwrap().search(
+                    query,
+                    author_id.map(|id| id.to_hex()),
+                    audience,
+                    sort,
+                    page,
+                );
+                db.tweets_by_ids(&ids).await
+            }
+        }
+    }
+}
+
+struct IndexedTweet {
+    user_id: String,
+    created_at: DateTime<Utc>,
+    message: String,
+    hashtags: HashSet<String>,
+    has_media: bool,
+    withheld: bool,
+    visibility: Visibility,
+    mentioned: Vec<String>,
+}
+
+/// Term to tweet postings with term frequencies, plus the data needed to filter tweets.
+#[derive(Default)]
+struct InvertedIndex {
+    postings: HashMap<String, HashMap<String, usize>>,
+    tweets: HashMap<String, IndexedTweet>,
+}
+
+impl InvertedIndex {
+    fn insert(&mut self, tweet: &TweetDto) {
+        self.remove(&tweet.id);
+        for term in tokenize(&tweet.message) {
+            *self
+                .postings
+                .entry(term)
+                .or_default()
+                .entry(tweet.id.clone())
+                .or_insert(0) += 1;
+        }
+        self.tweets.insert(
+            tweet.id.clone(),
+            IndexedTweet {
+                user_id: tweet.user_id.clone(),
+                created_at: tweet.created_at,
+                message: tweet.message.to_lowercase(),
+                hashtags: tweet
+                    .entities
+                    .hashtags
+                    .iter()
+                    .map(|h| h.text.to_lowercase())
+                    .collect(),
+                has_media: !tweet.media.is_empty(),
+                withheld: tweet.withheld,
+                visibility: tweet.visibility,
+                mentioned: tweet
+                    .entities
+                    .mentions
+                    .iter()
+                    .filter_map(|m| m.user_id.clone())
+                    .collect(),
+            },
+        );
+    }
+
+    fn remove(&mut self, id: &str) {
+        if let Some(tweet) = self.tweets.remove(id) {
+            for term in tokenize(&tweet.message) {
+                if let Some(posting) = self.postings.get_mut(&term) {
+                    posting.remove(id);
+                    if posting.is_empty() {
+                        self.postings.remove(&term);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Returns the ids of one page of matching tweets, ranked by TF-IDF or recency
+    fn search(
+        &self,
+        query: &SearchQuery,
+        author_id: Option<String>,
+        audience: &Audience,
+        sort: SearchSort,
+        page: &PageQuery,
+    ) -> Vec<ObjectId> {
+        let total = self.tweets.len() as f64;
+        let mut scores = HashMap::<&str, f64>::new();
+        if query.terms.is_empty() {
+            scores.extend(self.tweets.keys().map(|id| (id.as_str(), 0.0)));
+        }
+        for term in &query.terms {
+            if let Some(posting) = self.postings.get(term) {
+                let idf = (1.0 + total / posting.len() as f64).ln();
+                for (id, frequency) in posting {
+                    *scores.entry(id.as_str()).or_insert(0.0) += *frequency as f64 * idf;
+                }
+            }
+        }
+
+        let mut matches = scores
+            .into_iter()
+            .filter_map(|(id, score)| self.tweets.get(id).map(|tweet| (id, tweet, score)))
+            .filter(|(_, tweet, _)| {
+                query.phrases.iter().all(|p| tweet.message.contains(p))
+                    && query.hashtags.iter().all(|h| tweet.hashtags.contains(h))
+                    && author_id.as_ref().is_none_or(|a| &tweet.user_id == a)
+                    && (tweet.user_id == audience.viewer_id
+                        || !(tweet.withheld || audience.mutes(&tweet.message)))
+                    && audience.allows(&tweet.user_id, tweet.visibility, &tweet.mentioned)
+                    && query.since.is_none_or(|since| tweet.created_at >= since)
+                    && query.until.is_none_or(|until| tweet.created_at < until)
+                    && (!query.has_media || tweet.has_media)
+            })
+            .collect::<Vec<_>>();
+
+        matches.sort_by(|a, b| match sort {
+            SearchSort::Relevance => {
+                b.2.total_cmp(&a.2)
+                    .then(b.1.created_at.cmp(&a.1.created_at))
+            }
+            SearchSort::Recent => b.1.created_at.cmp(&a.1.created_at),
+        });
+        matches
+            .into_iter()
+            .skip(page.skip() as usize)
+            .take(page.limit() as usize)
+            .filter_map(|(id, _, _)| ObjectId::parse_str(id).ok())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Duration;
+
+    use super::*;
+
+    const ALICE: &str = "000000000000000000000001";
+    const BOB: &str = "000000000000000000000002";
+
+    fn tweet(author: &str, message: &str, age_minutes: i64) -> TweetDto {
+        let mut tweet = Tweet::new(message, author);
+        tweet.id = Some(ObjectId::new());
+        tweet.created_at = Utc::now() - Duration::minutes(age_minutes);
+        tweet.map()
+    }
+
+    fn search(index: &InvertedIndex, query: &str, audience: &Audience) -> Vec<String> {
+        index
+            .search(
+                &SearchQuery::parse(query),
+                None,
+                audience,
+                SearchSort::Relevance,
+                &PageQuery::default(),
+            )
+            .iter()
+            .map(|id| id.to_hex())
+            .collect()
+    }
+
+    #[test]
+    fn ranks_by_term_frequency_and_rarity() {
+        let mut index = InvertedIndex::default();
+        let once = tweet(ALICE, "rust is fun", 1);
+        let twice = tweet(ALICE, "rust rust everywhere", 2);
+        let other = tweet(ALICE, "go is fun", 3);
+        for t in [&once, &twice, &other] {
+            index.insert(t);
+        }
+        let results = search(&index, "rust", &Audience::new(BOB));
+        assert_eq!(results, vec![twice.id.clone(), once.id.clone()]);
+    }
+
+    #[test]
+    fn filters_by_phrase_hashtag_and_media() {
+        let mut index = InvertedIndex::default();
+        let phrase = tweet(ALICE, "async io in #Rust", 1);
+        let words = tweet(ALICE, "io is async", 2);
+        index.insert(&phrase);
+        index.insert(&words);
+        let audience = Audience::new(BOB);
+        assert_eq!(
+            search(&index, r#""async io""#, &audience),
+            vec![phrase.id.clone()]
+        );
+        assert_eq!(search(&index, "#rust", &audience), vec![phrase.id.clone()]);
+        assert!(search(&index, "async has:media", &audience).is_empty());
+    }
+
+    #[test]
+    fn applies_the_audience() {
+        let mut index = InvertedIndex::default();
+        let mut followers_only = tweet(ALICE, "secret plans", 1);
+        followers_only.visibility = Visibility::Followers;
+        let mut withheld = tweet(ALICE, "withheld plans", 2);
+        withheld.withheld = true;
+        index.insert(&followers_only);
+        index.insert(&withheld);
+
+        assert!(search(&index, "plans", &Audience::new(BOB)).is_empty());
+        let mut follower = Audience::new(BOB);
+        follower.following.insert(ALICE.to_string());
+        assert_eq!(
+            search(&index, "plans", &follower),
+            vec![followers_only.id.clone()]
+        );
+        assert_eq!(search(&index, "plans", &Audience::new(ALICE)).len(), 2);
+    }
+
+    #[test]
+    fn reindexing_replaces_and_removal_forgets() {
+        let mut index = InvertedIndex::default();
+        let mut edited = tweet(ALICE, "first draft", 1);
+        index.insert(&edited);
+        edited.message = "final version".to_string();
+        index.insert(&edited);
+        let audience = Audience::new(BOB);
+        assert!(search(&index, "draft", &audience).is_empty());
+        assert_eq!(search(&index, "final", &audience), vec![edited.id.clone()]);
+
+        index.remove(&edited.id);
+        assert!(search(&index, "final", &audience).is_empty());
+        assert!(index.postings.is_empty());
+        assert!(index.tweets.is_empty());
+    }
+}
@@ -0,0 +1,276 @@
+use std::collections::HashSet;
+
+use actix_web::web::Data;
+use chrono::{Duration, Utc};
+
+use crate::{
+    config::settings::{
+        spam_duplicate_similarity, spam_duplicate_window, spam_flag_score, spam_link_density,
+        spam_max_mentions, spam_new_account_age, spam_new_account_velocity_limit,
+        spam_shadow_limit_score, spam_throttle_score, spam_velocity_limit, spam_velocity_window,
+    },
+    errors::error::TweetError,
+    model::{
+        auth_model::User,
+        report_model::ReportTarget,
+        spam_model::{SpamDecision, SpamEvent, SpamSignal},
+        tweet_model::Tweet,
+    },
+    repo::{spam_repo::SpamRepo, tweet_repo::TweetRepo, user_repo::UserRepo},
+    text::{entities::extract_entities, search_query::tokenize},
+};
+
+/// What the heuristics know about a post and its author
+struct SpamInput<'a> {
+    message: &'a str,
+    account_age: Duration,
+    recent_posts: usize,
+    recent_messages: &'a [String],
+}
+
+/// Scores tweets and comments before they are posted and throttles,
+/// shadow limits or flags the accounts posting spam.
+pub struct SpamGuard {
+    pub tweets: Data<TweetRepo<Tweet>>,
+    pub users: Data<UserRepo<User>>,
+    pub events: SpamRepo<SpamEvent>,
+}
+
+impl SpamGuard {
+    /// Scores a post the user is about to make, logging and applying any decision but allow.
+    /// Throttled posts are rejected with a too many requests error.
+    pub async fn check(
+        &self,
+        user_id: &str,
+        target: ReportTarget,
+        message: &str,
+    ) -> Result<SpamDecision, TweetError> {
+        let user = self.users.find_user(user_id).await?;
+        let now = Utc::now();
+        let (recent_posts, recent_messages) = self
+            .tweets
+            .recent_activity(
+                user_id,
+                now - spam_velocity_window(),
+                now - spam_duplicate_window(),
+            )
+            .await?;
+        let signals = score(&SpamInput {
+            message,
+            account_age: now - user.created_at,
+            recent_posts,
+            recent_messages: &recent_messages,
+        });
+        let total = signals.iter().map(|s| s.score).sum::<u32>();
+        let decision = decide(total);
+        if decision == SpamDecision::Allow {
+            return Ok(decision);
+        }
+
+        log::warn!(
+            "Spam decision {:?} for user {} with score {}",
+            decision,
+            user_id,
+            total
+        );
+        let event = SpamEvent {
+            id: None,
+            user_id: user.id.unwrap(),
+            target,
+            message: message.to_string(),
+            score: total,
+            signals,
+            decision,
+            created_at: now,
+        };
+        if let Err(err) = self.events.log(&event).await {
+            log::error!("Failed to log spam decision: {}", err);
+        }
+        match decision {
+            SpamDecision::Throttle => Err(TweetError::TooManyRequests(
+                "You are posting too fast, please try again later".into(),
+            )),
+            SpamDecision::ShadowLimit => {
+                self.users.set_limited(user_id, true).await?;
+                Ok(decision)
+            }
+            _ => Ok(decision),
+        }
+    }
+}
+
+fn score(input: &SpamInput) -> Vec<SpamSignal> {
+    let mut signals = Vec::<SpamSignal>::new();
+    let mut signal = |name: &str, score: u32, detail: String| {
+        signals.push(SpamSignal {
+            name: name.to_string(),
+            score,
+            detail,
+        })
+    };
+
+    let is_new = input.account_age < spam_new_account_age();
+    if is_new {
+        signal(
+            "new_account",
+            1,
+            format!("Account is {} minutes old", input.account_age.num_minutes()),
+        );
+    }
+    let velocity_limit = if is_new {
+        spam_new_account_velocity_limit()
+    } else {
+        spam_velocity_limit()
+    };
+    if input.recent_posts >= velocity_limit {
+        signal(
+            "velocity",
+            4,
+            format!(
+                "{} posts in the last {} minutes",
+                input.recent_posts,
+                spam_velocity_window().num_minutes()
+            ),
+        );
+    }
+
+    let duplicates = input
+        .recent_messages
+        .iter()
+        .filter(|m| similarity(input.message, m) >= spam_duplicate_similarity())
+        .count();
+    if duplicates > 0 {
+        signal(
+            "duplicate_content",
+            if duplicates >= 3 { 4 } else { 2 },
+            format!("Similar to {} recent tweets", duplicates),
+        );
+    }
+
+    let entities = extract_entities(input.message);
+    let words = input.message.split_whitespace().count().max(1);
+    let links = entities.urls.len();
+    if links >= 2 && links as f64 / words as f64 >= spam_link_density() {
+        signal(
+            "link_density",
+            2,
+            format!("{} links in {} words", links, words),
+        );
+    }
+    if entities.mentions.len() >= spam_max_mentions() {
+        signal(
+            "mention_spam",
+            2,
+            format!("{} mentions", entities.mentions.len()),
+        );
+    }
+    signals
+}
+
+fn decide(score: u32) -> SpamDecision {
+    if score >= spam_shadow_limit_score() {
+        SpamDecision::ShadowLimit
+    } else if score >= spam_throttle_score() {
+        SpamDecision::Throttle
+    } else if score >= spam_flag_score() {
+        SpamDecision::Flag
+    } else {
+        SpamDecision::Allow
+    }
+}
+
+/// Jaccard similarity of the words of two messages, between 0 and 1
+fn similarity(a: &str, b: &str) -> f64 {
+    let a = tokenize(a).into_iter().collect::<HashSet<String>>();
+    let b = tokenize(b).into_iter().collect::<HashSet<String>>();
+    if a.is_empty() || b.is_empty() {
+        return 0.0;
+    }
+    a.intersection(&b).count() as f64 / a.union(&b).count() as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn signals(input: &SpamInput) -> Vec<(String, u32)> {
+        score(input)
+            .into_iter()
+            .map(|s| (s.name, s.score))
+            .collect()
+    }
+
+    fn established(message: &str) -> SpamInput<'_> {
+        SpamInput {
+            message,
+            account_age: Duration::days(365),
+            recent_posts: 0,
+            recent_messages: &[],
+        }
+    }
+
+    #[test]
+    fn decides_on_the_score_thresholds() {
+        assert_eq!(decide(0), SpamDecision::Allow);
+        assert_eq!(decide(spam_flag_score() - 1), SpamDecision::Allow);
+        assert_eq!(decide(spam_flag_score()), SpamDecision::Flag);
+        assert_eq!(decide(spam_throttle_score() - 1), SpamDecision::Flag);
+        assert_eq!(decide(spam_throttle_score()), SpamDecision::Throttle);
+        assert_eq!(
+            decide(spam_shadow_limit_score() - 1),
+            SpamDecision::Throttle
+        );
+        assert_eq!(decide(spam_shadow_limit_score()), SpamDecision::ShadowLimit);
+    }
+
+    #[test]
+    fn ordinary_posts_have_no_signals() {
+        assert!(signals(&established("Just shipped a new release, have a look")).is_empty());
+    }
+
+    #[test]
+    fn new_accounts_have_a_lower_velocity_limit() {
+        let mut input = established("hello");
+        input.recent_posts = spam_new_account_velocity_limit();
+        assert!(signals(&input).is_empty());
+
+        input.account_age = Duration::minutes(5);
+        assert_eq!(
+            signals(&input),
+            vec![("new_account".to_string(), 1), ("velocity".to_string(), 4)]
+        );
+        assert_eq!(decide(1 + 4), SpamDecision::Throttle);
+    }
+
+    #[test]
+    fn scores_duplicates_by_how_often_they_repeat() {
+        let message = "buy cheap followers now";
+        let once = vec![message.to_string(), "something else entirely".to_string()];
+        let mut input = established(message);
+        input.recent_messages = &once;
+        assert_eq!(signals(&input), vec![("duplicate_content".to_string(), 2)]);
+
+        let thrice = vec![message.to_uppercase(); 3];
+        input.recent_messages = &thrice;
+        assert_eq!(signals(&input), vec![("duplicate_content".to_string(), 4)]);
+    }
+
+    #[test]
+    fn scores_link_and_mention_spam() {
+        let links = established("https://a.io https://b.io win");
+        assert_eq!(signals(&links), vec![("link_density".to_string(), 2)]);
+        let one_link = established("https://a.io is where the release notes live");
+        assert!(signals(&one_link).is_empty());
+
+        let mentions = established("@a @b @c @d @e look");
+        assert_eq!(signals(&mentions), vec![("mention_spam".to_string(), 2)]);
+    }
+
+    #[test]
+    fn measures_word_overlap() {
+        assert_eq!(similarity("a b c", "A, B. C!"), 1.0);
+        assert_eq!(similarity("a b", "c d"), 0.0);
+        assert_eq!(similarity("a b c d", "a b"), 0.5);
+        assert_eq!(similarity("", "a"), 0.0);
+    }
+}
@@ -0,0 +1,155 @@
+use std::{collections::HashMap, sync::RwLock};
+
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    config::settings::trending_limit, dtos::dto::TrendDto, errors::error::TweetError,
+    model::tweet_model::Tweet, repo::tweet_repo::TweetRepo,
+};
+
+/// Sliding window trending topics are computed over
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize, Serialize)]
+pub enum TrendWindow {
+    #[serde(rename = "1h")]
+    Hour,
+    #[serde(rename = "24h")]
+    Day,
+}
+
+impl TrendWindow {
+    pub const ALL: [TrendWindow; 2] = [TrendWindow::Hour, TrendWindow::Day];
+
+    pub fn duration(&self) -> Duration {
+        match self {
+            TrendWindow::Hour => Duration::hours(1),
+            TrendWindow::Day => Duration::hours(24),
+        }
+    }
+
+    /// Age at which a hashtag use counts for half, a quarter of the window
+    fn half_life(&self) -> Duration {
+        self.duration() / 4
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TrendQuery {
+    pub window: Option<TrendWindow>,
+}
+
+/// Trending hashtags per window, refreshed in the background and served from memory.
+#[derive(Default)]
+pub struct TrendingService {
+    cache: RwLock<HashMap<TrendWindow, Vec<TrendDto>>>,
+}
+
+impl TrendingService {
+    /// Returns the cached trending topics for a window
+    pub fn trends(&self, window: TrendWindow) -> Vec<TrendDto> {
+        self.cache
+            .read()
+            .unwrap()
+            .get(&window)
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// Recomputes the trending topics of every window
+    pub async fn refresh(&self, db: &TweetRepo<Tweet>) -> Result<(), TweetError> {
+        let now = Utc::now();
+        let longest = TrendWindow::ALL.iter().map(|w| w.duration()).max().unwrap();
+        let hashtags = db.hashtags_since(now - longest).await?;
+
+        let trends = TrendWindow::ALL
+            .iter()
+            .map(|window| (*window, score_hashtags(&hashtags, *window, now)))
+            .collect::<HashMap<TrendWindow, Vec<TrendDto>>>();
+        *self.cache.write().unwrap() = trends;
+        Ok(())
+    }
+}
+
+/// Scores hashtags used within the window, each use decaying exponentially with its age
+fn score_hashtags(
+    hashtags: &[(String, DateTime<Utc>)],
+    window: TrendWindow,
+    now: DateTime<Utc>,
+) -> Vec<TrendDto> {
+    let half_life = window.half_life().num_seconds() as f64;
+    let mut scores = HashMap::<&str, (f64, usize)>::new();
+    for (hashtag, created_at) in hashtags {
+        let age = now - *created_at;
+        if age > window.duration() {
+            continue;
+        }
+        let weight = 0.5_f64.powf(age.num_seconds().max(0) as f64 / half_life);
+        let entry = scores.entry(hashtag.as_str()).or_insert((0.0, 0));
+        entry.0 += weight;
+        entry.1 += 1;
+    }
+
+    let mut trends = scores
+        .into_iter()
+        .map(|(hashtag, (score, tweet_count))| TrendDto {
+            hashtag: hashtag.to_string(),
+            tweet_count,
+            score,
+        })
+        .collect::<Vec<TrendDto>>();
+    trends.sort_by(|a, b| b.score.total_cmp(&a.score));
+    trends.truncate(trending_limit());
+    trends
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn uses(hashtag: &str, ages: &[Duration], now: DateTime<Utc>) -> Vec<(String, DateTime<Utc>)> {
+        ages.iter()
+            .map(|age| (hashtag.to_string(), now - *age))
+            .collect()
+    }
+
+    #[test]
+    fn halves_the_weight_every_half_life() {
+        let now = Utc::now();
+        let half_life = TrendWindow::Hour.half_life();
+        let hashtags = uses("rust", &[Duration::zero(), half_life, half_life * 2], now);
+        let trends = score_hashtags(&hashtags, TrendWindow::Hour, now);
+        assert_eq!(trends.len(), 1);
+        assert_eq!(trends[0].tweet_count, 3);
+        assert!((trends[0].score - 1.75).abs() < 1e-9);
+    }
+
+    #[test]
+    fn ignores_uses_outside_the_window() {
+        let now = Utc::now();
+        let hashtags = uses("old", &[Duration::hours(2)], now);
+        assert!(score_hashtags(&hashtags, TrendWindow::Hour, now).is_empty());
+        let trends = score_hashtags(&hashtags, TrendWindow::Day, now);
+        assert_eq!(trends[0].hashtag, "old");
+    }
+
+    #[test]
+    fn recent_uses_outrank_older_ones() {
+        let now = Utc::now();
+        let mut hashtags = uses("steady", &[Duration::minutes(50); 3], now);
+        hashtags.extend(uses("rising", &[Duration::minutes(1); 2], now));
+        let trends = score_hashtags(&hashtags, TrendWindow::Hour, now);
+        let order = trends
+            .iter()
+            .map(|t| t.hashtag.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(order, vec!["rising", "steady"]);
+    }
+
+    #[test]
+    fn future_uses_count_fully() {
+        let now = Utc::now();
+        let hashtags = uses("skew", &[Duration::seconds(-30)], now);
+        let trends = score_hashtags(&hashtags, TrendWindow::Hour, now);
+        assert!((trends[0].score - 1.0).abs() < 1e-9);
+    }
+}
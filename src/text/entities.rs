@@ -0,0 +1,161 @@
+use std::sync::OnceLock;
+
+use regex::Regex;
+
+use crate::model::tweet_entity::{Entity, TweetEntities};
+
+static URL: OnceLock<Regex> = OnceLock::new();
+static HASHTAG: OnceLock<Regex> = OnceLock::new();
+static MENTION: OnceLock<Regex> = OnceLock::new();
+static CASHTAG: OnceLock<Regex> = OnceLock::new();
+
+/// Extracts hashtags, mentions, URLs and cashtags from a message.
+///
+/// Mentions are returned unresolved, see `UserRepo::resolve_mentions`.
+pub fn extract_entities(message: &str) -> TweetEntities {
+    let urls = find_urls(message);
+    let outside_urls = |start: usize, end: usize| {
+        !urls
+            .iter()
+            .any(|&(url_start, url_end)| start < url_end && end > url_start)
+    };
+
+    let hashtags = find_tagged(message, hashtag_regex())
+        .into_iter()
+        .filter(|&(start, end)| outside_urls(start, end))
+        .filter(|&(start, end)| !message[start + 1..end].chars().all(|c| c.is_numeric()))
+        .map(|(start, end)| entity(message, start, end, 1))
+        .collect();
+    let mentions = find_tagged(message, mention_regex())
+        .into_iter()
+        .filter(|&(start, end)| outside_urls(start, end))
+        .filter(|&(_, end)| !message[end..].starts_with(is_word_char))
+        .map(|(start, end)| entity(message, start, end, 1))
+        .collect();
+    let cashtags = find_tagged(message, cashtag_regex())
+        .into_iter()
+        .filter(|&(start, end)| outside_urls(start, end))
+        .filter(|&(_, end)| !message[end..].starts_with(is_word_char))
+        .map(|(start, end)| entity(message, start, end, 1))
+        .collect();
+    let urls = urls
+        .into_iter()
+        .map(|(start, end)| entity(message, start, end, 0))
+        .collect();
+
+    TweetEntities {
+        hashtags,
+        mentions,
+        urls,
+        cashtags,
+    }
+}
+
+/// Finds the byte ranges of URLs, without trailing punctuation
+fn find_urls(message: &str) -> Vec<(usize, usize)> {
+    url_regex()
+        .find_iter(message)
+        .map(|m| {
+            let url = m
+                .as_str()
+                .trim_end_matches(|c: char| ".,;:!?'\")]}".contains(c));
+            (m.start(), m.start() + url.len())
+        })
+        .collect()
+}
+
+/// Finds the byte ranges of sigil prefixed tokens that do not follow a word character
+fn find_tagged(message: &str, regex: &Regex) -> Vec<(usize, usize)> {
+    regex
+        .find_iter(message)
+        .filter(|m| !message[..m.start()].ends_with(|c: char| is_word_char(c) || c == '&'))
+        .map(|m| (m.start(), m.end()))
+        .collect()
+}
+
+/// Builds an entity from a byte range, skipping `sigil_len` bytes for its text
+fn entity(message: &str, start: usize, end: usize, sigil_len: usize) -> Entity {
+    let char_start = message[..start].chars().count();
+    let utf16_start = message[..start].encode_utf16().count();
+    Entity {
+        text: message[start + sigil_len..end].to_string(),
+        start: char_start,
+        end: char_start + message[start..end].chars().count(),
+        utf16_start,
+        utf16_end: utf16_start + message[start..end].encode_utf16().count(),
+        user_id: None,
+    }
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+fn url_regex() -> &'static Regex {
+    URL.get_or_init(|| Regex::new(r"https?://[^\s]+").unwrap())
+}
+
+fn hashtag_regex() -> &'static Regex {
+    HASHTAG.get_or_init(|| Regex::new(r"#[\p{L}\p{M}\p{N}_]+").unwrap())
+}
+
+fn mention_regex() -> &'static Regex {
+    MENTION.get_or_init(|| Regex::new(r"@[A-Za-z0-9_]{1,15}").unwrap())
+}
+
+fn cashtag_regex() -> &'static Regex {
+    CASHTAG.get_or_init(|| Regex::new(r"\$[A-Za-z]{1,6}(?:[._][A-Za-z]{1,2})?").unwrap())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn texts(entities: &[Entity]) -> Vec<&str> {
+        entities.iter().map(|e| e.text.as_str()).collect()
+    }
+
+    #[test]
+    fn extracts_every_kind_of_entity() {
+        let entities = extract_entities("#rust by @ferris costs $TSLA, see https://rust-lang.org.");
+        assert_eq!(texts(&entities.hashtags), vec!["rust"]);
+        assert_eq!(texts(&entities.mentions), vec!["ferris"]);
+        assert_eq!(texts(&entities.cashtags), vec!["TSLA"]);
+        assert_eq!(texts(&entities.urls), vec!["https://rust-lang.org"]);
+    }
+
+    #[test]
+    fn skips_tokens_inside_words_and_urls() {
+        let entities = extract_entities("mail@example.com a#b &#39; https://x.io/#frag #123");
+        assert!(entities.mentions.is_empty());
+        assert!(entities.hashtags.is_empty());
+        assert_eq!(texts(&entities.urls), vec!["https://x.io/#frag"]);
+    }
+
+    #[test]
+    fn rejects_too_long_mentions() {
+        let entities = extract_entities("@abcdefghijklmnop @abcdefghijklmno");
+        assert_eq!(texts(&entities.mentions), vec!["abcdefghijklmno"]);
+    }
+
+    #[test]
+    fn offsets_are_in_chars_and_utf16_units() {
+        // The emoji is one char but two UTF-16 code units
+        let entities = extract_entities("😀 é #tag");
+        let hashtag = &entities.hashtags[0];
+        assert_eq!((hashtag.start, hashtag.end), (4, 8));
+        assert_eq!((hashtag.utf16_start, hashtag.utf16_end), (5, 9));
+    }
+
+    #[test]
+    fn utf16_offsets_cover_astral_characters_in_the_entity() {
+        let message = "go #𝔯𝔲𝔰𝔱 now";
+        let hashtag = &extract_entities(message).hashtags[0];
+        assert_eq!(hashtag.text, "𝔯𝔲𝔰𝔱");
+        assert_eq!((hashtag.start, hashtag.end), (3, 8));
+        assert_eq!((hashtag.utf16_start, hashtag.utf16_end), (3, 12));
+        let utf16 = message.encode_utf16().collect::<Vec<u16>>();
+        let slice = String::from_utf16(&utf16[hashtag.utf16_start..hashtag.utf16_end]).unwrap();
+        assert_eq!(slice, "#𝔯𝔲𝔰𝔱");
+    }
+}
@@ -0,0 +1,130 @@
+use chrono::{DateTime, Duration, NaiveDate, TimeZone, Utc};
+
+/// A parsed search query such as `rust "async io" from:alice #tokio since:2022-01-01 has:media`
+#[derive(Debug, Default, Clone)]
+pub struct SearchQuery {
+    pub terms: Vec<String>,
+    pub phrases: Vec<String>,
+    pub from: Option<String>,
+    pub hashtags: Vec<String>,
+    pub since: Option<DateTime<Utc>>,
+    pub until: Option<DateTime<Utc>>,
+    pub has_media: bool,
+}
+
+impl SearchQuery {
+    /// Parses a raw query, ignoring operators with invalid values
+    pub fn parse(query: &str) -> Self {
+        let mut search = SearchQuery::default();
+        let mut rest = query;
+        while let Some(start) = rest.find('"') {
+            search.parse_operators(&rest[..start]);
+            let after = &rest[start + 1..];
+            let end = after.find('"').unwrap_or(after.len());
+            let phrase = after[..end].trim().to_lowercase();
+            if !phrase.is_empty() {
+                search.phrases.push(phrase);
+            }
+            rest = after.get(end + 1..).unwrap_or("");
+        }
+        search.parse_operators(rest);
+        search
+    }
+
+    /// Checks whether the query has free text to rank results by
+    pub fn has_text(&self) -> bool {
+        !self.terms.is_empty() || !self.phrases.is_empty()
+    }
+
+    fn parse_operators(&mut self, text: &str) {
+        for word in text.split_whitespace() {
+            if let Some(handle) = word.strip_prefix("from:") {
+                let handle = handle.trim_start_matches('@');
+                if !handle.is_empty() {
+                    self.from = Some(handle.to_lowercase());
+                }
+            } else if let Some(date) = word.strip_prefix("since:") {
+                self.since = parse_date(date);
+            } else if let Some(date) = word.strip_prefix("until:") {
+                self.until = parse_date(date).map(|d| d + Duration::days(1));
+            } else if word == "has:media" {
+                self.has_media = true;
+            } else if let Some(tag) = word.strip_prefix('#') {
+                if !tag.is_empty() {
+                    self.hashtags.push(tag.to_lowercase());
+                }
+            } else {
+                self.terms.extend(tokenize(word));
+            }
+        }
+    }
+}
+
+/// Splits text into lowercase alphanumeric search terms
+pub fn tokenize(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|t| !t.is_empty())
+        .map(|t| t.to_lowercase())
+        .collect()
+}
+
+fn parse_date(date: &str) -> Option<DateTime<Utc>> {
+    NaiveDate::parse_from_str(date, "%Y-%m-%d")
+        .ok()
+        .and_then(|d| d.and_hms_opt(0, 0, 0))
+        .map(|d| Utc.from_utc_datetime(&d))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_terms_phrases_and_operators() {
+        let query = SearchQuery::parse(
+            r#"Rust "Async IO" from:@Alice #Tokio since:2022-01-01 until:2022-01-31 has:media"#,
+        );
+        assert_eq!(query.terms, vec!["rust"]);
+        assert_eq!(query.phrases, vec!["async io"]);
+        assert_eq!(query.from.as_deref(), Some("alice"));
+        assert_eq!(query.hashtags, vec!["tokio"]);
+        assert_eq!(query.since, Some("2022-01-01T00:00:00Z".parse().unwrap()));
+        // `until` includes the whole day
+        assert_eq!(query.until, Some("2022-02-01T00:00:00Z".parse().unwrap()));
+        assert!(query.has_media);
+        assert!(query.has_text());
+    }
+
+    #[test]
+    fn ignores_invalid_operator_values() {
+        let query = SearchQuery::parse("from: # since:yesterday until:2022-13-01");
+        assert_eq!(query.from, None);
+        assert!(query.hashtags.is_empty());
+        assert_eq!(query.since, None);
+        assert_eq!(query.until, None);
+        assert!(!query.has_text());
+    }
+
+    #[test]
+    fn keeps_an_unterminated_phrase() {
+        let query = SearchQuery::parse(r#"one "two three"#);
+        assert_eq!(query.terms, vec!["one"]);
+        assert_eq!(query.phrases, vec!["two three"]);
+    }
+
+    #[test]
+    fn drops_empty_phrases() {
+        let query = SearchQuery::parse(r#"a "" "  " b"#);
+        assert!(query.phrases.is_empty());
+        assert_eq!(query.terms, vec!["a", "b"]);
+    }
+
+    #[test]
+    fn tokenizes_on_non_alphanumeric_characters() {
+        assert_eq!(
+            tokenize("Hello, wörld! it's 2024"),
+            vec!["hello", "wörld", "it", "s", "2024"]
+        );
+        assert!(tokenize("--- !!!").is_empty());
+    }
+}
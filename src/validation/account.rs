@@ -0,0 +1,24 @@
+use crate::errors::error::{FieldError, TweetError};
+
+/// Validates an optional username and returns it lowercased.
+///
+/// Usernames are 1 to 15 ASCII letters, digits or underscores, the same
+/// characters mentions are matched on.
+pub fn validate_username(username: Option<&str>) -> Result<Option<String>, TweetError> {
+    let username = match username {
+        Some(username) => username.trim(),
+        None => return Ok(None),
+    };
+    let valid = (1..=15).contains(&username.len())
+        && username
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_');
+    if !valid {
+        return Err(TweetError::UnprocessableEntity(vec![FieldError::new(
+            "username",
+            "invalid_username",
+            "Username must be 1 to 15 letters, digits or underscores",
+        )]));
+    }
+    Ok(Some(username.to_lowercase()))
+}
@@ -0,0 +1,149 @@
+use unicode_normalization::UnicodeNormalization;
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::{
+    config::settings::url_weight,
+    errors::error::{FieldError, TweetError},
+};
+
+/// Validates a user supplied message and returns it NFC normalized.
+///
+/// The message must be present, must not be blank, must not contain control
+/// characters other than line breaks and tabs, and its weighted length must not
+/// exceed `max_length`.
+pub fn validate_message(
+    field: &str,
+    message: Option<&str>,
+    max_length: usize,
+) -> Result<String, TweetError> {
+    let message = match message {
+        Some(message) => message.nfc().collect::<String>(),
+        None => {
+            return Err(TweetError::UnprocessableEntity(vec![FieldError::new(
+                field,
+                "required",
+                "This field is required",
+            )]))
+        }
+    };
+
+    let mut errors = Vec::<FieldError>::new();
+    if message.trim().is_empty() {
+        errors.push(FieldError::new(
+            field,
+            "blank",
+            "This field must not be empty or whitespace only",
+        ));
+    }
+    if message
+        .chars()
+        .any(|c| c.is_control() && !matches!(c, '\n' | '\r' | '\t'))
+    {
+        errors.push(FieldError::new(
+            field,
+            "invalid_characters",
+            "This field must not contain control characters",
+        ));
+    }
+    if weighted_length(&message) > max_length {
+        errors.push(FieldError::new(
+            field,
+            "too_long",
+            &format!("This field must not exceed {} characters", max_length),
+        ));
+    }
+
+    if errors.is_empty() {
+        Ok(message)
+    } else {
+        Err(TweetError::UnprocessableEntity(errors))
+    }
+}
+
+/// Counts the length of a message in grapheme clusters, with every URL
+/// counting as a fixed weight regardless of its real length.
+pub fn weighted_length(message: &str) -> usize {
+    message
+        .split_inclusive(char::is_whitespace)
+        .map(|word| {
+            let token = word.trim_end_matches(char::is_whitespace);
+            if is_url(token) {
+                url_weight() + word[token.len()..].graphemes(true).count()
+            } else {
+                word.graphemes(true).count()
+            }
+        })
+        .sum()
+}
+
+fn is_url(token: &str) -> bool {
+    token.starts_with("http://") || token.starts_with("https://")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn error_codes(result: Result<String, TweetError>) -> Vec<String> {
+        match result {
+            Err(TweetError::UnprocessableEntity(errors)) => {
+                errors.into_iter().map(|e| e.code).collect()
+            }
+            other => panic!("expected a validation error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn counts_grapheme_clusters() {
+        assert_eq!(weighted_length("hello"), 5);
+        // Family emoji made of several code points joined by zero width joiners
+        assert_eq!(weighted_length("👨‍👩‍👧‍👦"), 1);
+        // Flag made of two regional indicators
+        assert_eq!(weighted_length("🇫🇷!"), 2);
+        assert_eq!(weighted_length("e\u{301}"), 1);
+    }
+
+    #[test]
+    fn urls_count_as_a_fixed_weight() {
+        let short = "see https://t.co/a";
+        let long = format!("see https://example.com/{}", "a".repeat(200));
+        assert_eq!(weighted_length(short), 4 + url_weight());
+        assert_eq!(weighted_length(&long), 4 + url_weight());
+        assert_eq!(weighted_length("https://a.io and"), url_weight() + 4);
+        assert_eq!(weighted_length("ftp://a.io"), 10);
+    }
+
+    #[test]
+    fn normalizes_to_nfc() {
+        let message = validate_message("message", Some("cafe\u{301}"), 10).unwrap();
+        assert_eq!(message, "café");
+        assert_eq!(message.chars().count(), 4);
+    }
+
+    #[test]
+    fn measures_the_normalized_message() {
+        let decomposed = "e\u{301}".repeat(3);
+        assert!(validate_message("message", Some(&decomposed), 3).is_ok());
+        assert_eq!(
+            error_codes(validate_message("message", Some(&decomposed), 2)),
+            vec!["too_long"]
+        );
+    }
+
+    #[test]
+    fn rejects_missing_blank_and_control_characters() {
+        assert_eq!(
+            error_codes(validate_message("message", None, 280)),
+            vec!["required"]
+        );
+        assert_eq!(
+            error_codes(validate_message("message", Some(" \n\t "), 280)),
+            vec!["blank"]
+        );
+        assert_eq!(
+            error_codes(validate_message("message", Some("hi\u{7}"), 280)),
+            vec!["invalid_characters"]
+        );
+        assert!(validate_message("message", Some("line\r\nbreak\tand tab"), 280).is_ok());
+    }
+}